@@ -0,0 +1,106 @@
+use ant_sim_core::config::SensorConfig;
+use ant_sim_core::grid::{
+    sensor_cells, GridCellData, GridMap, GridType, MarkerType, GRID_CELL_SIZE,
+};
+use bevy::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+const MAP_SIZE: (u32, u32) = (1000, 1000);
+const MARKER_COUNT: u32 = 50_000;
+const ANT_COUNT: usize = 10_000;
+
+fn random_cells(rng: &mut rand::rngs::StdRng, count: u32) -> Vec<(i32, i32)> {
+    (0..count)
+        .map(|_| {
+            (
+                rng.gen_range(0..MAP_SIZE.0 as i32),
+                rng.gen_range(0..MAP_SIZE.1 as i32),
+            )
+        })
+        .collect()
+}
+
+// Ant positions and headings, as follow_markers sees them each frame
+fn random_ants(rng: &mut rand::rngs::StdRng) -> Vec<(Vec2, Vec2)> {
+    let map_width_pixels = MAP_SIZE.0 as f32 * GRID_CELL_SIZE;
+    let map_height_pixels = MAP_SIZE.1 as f32 * GRID_CELL_SIZE;
+    (0..ANT_COUNT)
+        .map(|_| {
+            let pos = Vec2::new(
+                rng.gen_range(0.0..map_width_pixels),
+                rng.gen_range(0.0..map_height_pixels),
+            );
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            (pos, Vec2::new(angle.cos(), angle.sin()))
+        })
+        .collect()
+}
+
+fn bench_front_cell_lookups(c: &mut Criterion) {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let cells = random_cells(&mut rng, MARKER_COUNT);
+    let ants = random_ants(&mut rng);
+
+    let mut grid_map = GridMap::new(MAP_SIZE, GridType::Square);
+    // Previous HashMap-backed storage, kept as a baseline for comparison
+    let mut hash_map: HashMap<(i32, i32), GridCellData> = HashMap::new();
+    for (i, cell) in cells.iter().enumerate() {
+        let entity = Entity::from_raw(i as u32);
+        grid_map.set_marker(*cell, MarkerType::Food, entity);
+        hash_map.entry(*cell).or_default().food_marker = Some(entity);
+    }
+
+    let sensor = SensorConfig::default();
+    let mut group = c.benchmark_group("front_cell_lookups");
+    group.bench_function("grid_map", |b| {
+        b.iter(|| {
+            let mut found = 0;
+            for (pos, velocity) in &ants {
+                for (_, data) in
+                    grid_map.iter_cells(sensor_cells(*pos, *velocity, &sensor, GridType::Square))
+                {
+                    if data.food_marker.is_some() {
+                        found += 1;
+                    }
+                }
+            }
+            black_box(found)
+        })
+    });
+    group.bench_function("hash_map", |b| {
+        b.iter(|| {
+            let mut found = 0;
+            for (pos, velocity) in &ants {
+                for cell in sensor_cells(*pos, *velocity, &sensor, GridType::Square) {
+                    if hash_map.get(&cell).is_some_and(|d| d.food_marker.is_some()) {
+                        found += 1;
+                    }
+                }
+            }
+            black_box(found)
+        })
+    });
+    group.finish();
+}
+
+fn bench_marker_churn(c: &mut Criterion) {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let cells = random_cells(&mut rng, MARKER_COUNT);
+
+    c.bench_function("set_and_remove_markers", |b| {
+        let mut grid_map = GridMap::new(MAP_SIZE, GridType::Square);
+        b.iter(|| {
+            for (i, cell) in cells.iter().enumerate() {
+                grid_map.set_marker(*cell, MarkerType::Base, Entity::from_raw(i as u32));
+            }
+            for cell in &cells {
+                grid_map.remove_marker(*cell, MarkerType::Base);
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_front_cell_lookups, bench_marker_churn);
+criterion_main!(benches);
@@ -0,0 +1,424 @@
+use crate::colony::Colony;
+use crate::config::{Config, TraitsConfig, VisionConfig};
+use crate::grid::GridMap;
+use crate::marker::{Marker, MarkerType};
+use crate::obstacle::{FlowFields, Obstacles};
+use crate::rng::SimRng;
+use crate::spatial::Landmarks;
+use crate::task::Task;
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// Walking speed in pixels per second
+pub const ANT_SPEED: f32 = 50.0;
+
+#[derive(Component, Debug)]
+pub struct Ant {
+    pub state: AntState,
+    pub has_food: bool,
+    // Richness of the source the carried food came from, which scales the trail laid home
+    pub food_richness: f32,
+    pub velocity: Vec2,
+    pub direction_change_timer: f32,
+    pub marker_timer: f32,
+    pub state_timer: f32,
+    // Seconds since the ant last left a base, reported as the trip duration on delivery
+    pub trip_timer: f32,
+    // Pheromone left for laying markers, see PheromoneReserveConfig
+    pub pheromone: f32,
+    // Exploring ants ignore food pheromones for the rest of their search trip
+    pub exploring: bool,
+    pub caste: Caste,
+    pub task: Task,
+    pub traits: AntTraits,
+}
+
+// Individual variation, multipliers of the colony-wide behavior drawn at spawn time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AntTraits {
+    pub speed: f32,
+    pub turn_noise: f32,
+    pub pheromone_sensitivity: f32,
+}
+
+impl Default for AntTraits {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            turn_noise: 1.0,
+            pheromone_sensitivity: 1.0,
+        }
+    }
+}
+
+impl AntTraits {
+    pub fn roll(config: &TraitsConfig, rng: &mut impl Rng) -> Self {
+        Self {
+            speed: config.speed.sample(rng),
+            turn_noise: config.turn_noise.sample(rng),
+            pheromone_sensitivity: config.pheromone_sensitivity.sample(rng),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Caste {
+    Worker,
+    // Soldiers don't forage; they patrol and fight ants of other colonies
+    Soldier,
+}
+
+impl Caste {
+    pub fn roll(soldier_ratio: f32, rng: &mut impl Rng) -> Self {
+        if soldier_ratio > 0.0 && rng.gen::<f32>() < soldier_ratio {
+            Caste::Soldier
+        } else {
+            Caste::Worker
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AntState {
+    Searching,
+    Returning,
+    Resting,
+    // Taking a corpse away from the base to the refuse zone
+    CarryingCorpse,
+}
+
+impl Ant {
+    pub fn new(config: &Config, rng: &mut impl Rng) -> Self {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        Self {
+            state: AntState::Searching,
+            has_food: false,
+            food_richness: 1.0,
+            velocity: Vec2::new(angle.cos(), angle.sin()),
+            direction_change_timer: 0.0,
+            marker_timer: 0.0,
+            state_timer: 0.0,
+            trip_timer: 0.0,
+            pheromone: config.pheromone_reserve.full(),
+            exploring: roll_exploring(config.exploration_rate, rng),
+            caste: Caste::Worker,
+            task: Task::Forage,
+            traits: AntTraits::roll(&config.traits, rng),
+        }
+    }
+
+    // Leave for a new search trip, deciding whether this trip explores or exploits trails
+    pub fn start_searching(&mut self, config: &Config, rng: &mut impl Rng) {
+        self.state = AntState::Searching;
+        self.state_timer = 0.0;
+        self.trip_timer = 0.0;
+        self.marker_timer = 0.0; // Reset marker timer to start leaving base markers immediately
+        self.exploring = roll_exploring(config.exploration_rate, rng);
+    }
+}
+
+pub fn spawn_ant(
+    commands: &mut Commands,
+    config: &Config,
+    rng: &mut impl Rng,
+    colony: Colony,
+    caste: Caste,
+    translation: Vec3,
+) -> Entity {
+    let mut ant = Ant::new(config, rng);
+    ant.caste = caste;
+    ant.task = Task::initial(caste);
+    commands
+        .spawn((
+            ant,
+            colony,
+            TransformBundle::from_transform(Transform::from_translation(translation)),
+        ))
+        .id()
+}
+
+fn roll_exploring(exploration_rate: f32, rng: &mut impl Rng) -> bool {
+    exploration_rate > 0.0 && rng.gen::<f32>() < exploration_rate
+}
+
+// Whether target is within the vision range and field of view of an ant at pos heading along
+// velocity, with no obstacle in between
+pub fn in_view(
+    pos: Vec2,
+    velocity: Vec2,
+    target: Vec2,
+    vision: &VisionConfig,
+    obstacles: &Obstacles,
+) -> bool {
+    // Default to facing right if velocity is too small, like the marker sensor
+    let direction = if velocity.length() > 0.01 {
+        velocity.normalize()
+    } else {
+        Vec2::new(1.0, 0.0)
+    };
+    let offset = target - pos;
+    let distance = offset.length();
+    if distance > vision.range {
+        return false;
+    }
+    // Food the ant stands on is always seen
+    distance == 0.0
+        || (offset.dot(direction) >= (vision.fov_degrees.to_radians() / 2.0).cos() * distance
+            && obstacles.line_clear(pos, target))
+}
+
+pub fn move_ants(
+    mut ants: Query<(&mut Transform, &mut Ant, &Colony)>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+    landmarks: Landmarks,
+    obstacles: Res<Obstacles>,
+    flow_fields: Res<FlowFields>,
+    config: Res<Config>,
+) {
+    const DIRECTION_CHANGE_INTERVAL: f32 = 1.5;
+
+    let dt = time.delta_seconds();
+    let largest_food_radius = landmarks.largest_food_radius();
+
+    for (mut transform, mut ant, colony) in ants.iter_mut() {
+        match ant.state {
+            AntState::Searching => {
+                let ant_pos = transform.translation.truncate();
+                let mut closest_food: Option<Vec2> = None;
+                let mut closest_distance = f32::INFINITY;
+
+                if ant.task.collects_food() {
+                    let nearby_food =
+                        landmarks.food_near(ant_pos, config.vision.range, largest_food_radius);
+                    for (food_pos, food_source) in nearby_food {
+                        // Large sources are seen as soon as their edge is in view
+                        let edge = food_source.nearest_point(food_pos, ant_pos);
+                        if !in_view(ant_pos, ant.velocity, edge, &config.vision, &obstacles) {
+                            continue;
+                        }
+                        let distance = ant_pos.distance(food_pos);
+                        if distance < closest_distance {
+                            closest_distance = distance;
+                            closest_food = Some(food_pos);
+                        }
+                    }
+                }
+
+                // If food is in front, move directly toward it
+                if let Some(food_pos) = closest_food {
+                    let direction_to_food = (food_pos - ant_pos).normalize();
+                    ant.velocity = direction_to_food;
+                } else {
+                    // No food in front, continue with normal searching behavior
+                    // Update direction change timer
+                    ant.direction_change_timer += dt;
+
+                    // Change direction periodically
+                    // But only a few degrees at a time
+                    if ant.direction_change_timer >= DIRECTION_CHANGE_INTERVAL {
+                        // Get current angle of velocity vector
+                        let current_angle = ant.velocity.y.atan2(ant.velocity.x);
+                        // Add a small random change (in radians, ~±6 degrees for an average ant)
+                        let angle_change = rng.gen_range(-0.1..0.1) * ant.traits.turn_noise;
+                        let new_angle = current_angle + angle_change;
+                        // Create new velocity vector with slightly changed direction
+                        ant.velocity = Vec2::new(new_angle.cos(), new_angle.sin()).normalize();
+                        ant.direction_change_timer = 0.0;
+                    }
+                }
+            }
+            AntState::Resting => {
+                // Resting ants stay at the base until woken by wake_resting_ants
+                continue;
+            }
+            AntState::CarryingCorpse => {
+                // Heading is steered toward the refuse zone by carry_corpses
+            }
+            AntState::Returning => {
+                // Move toward the nearest base of the ant's colony, but marker following may have already influenced direction
+                // If no markers were found, move directly toward base
+                let ant_pos = transform.translation.truncate();
+                if let Some((base_position, _)) = landmarks.nearest_base(ant_pos, Some(*colony)) {
+                    let base_direction = (base_position - ant_pos).normalize();
+
+                    // Blend base direction with current velocity (which may have been influenced by markers)
+                    // This allows markers to guide the path while still generally heading toward base.
+                    // When a wall hides the base, the flow field leads around it instead.
+                    let detour = flow_fields
+                        .get(*colony)
+                        .filter(|_| !obstacles.line_clear(ant_pos, base_position))
+                        .and_then(|field| field.direction(&obstacles, ant_pos));
+                    let (home_direction, weight) = match detour {
+                        Some(direction) => (direction, config.pathfinding.weight),
+                        None => (base_direction, 0.3),
+                    };
+                    let blended = (ant.velocity * (1.0 - weight) + home_direction * weight)
+                        .normalize_or_zero();
+                    ant.velocity = if blended == Vec2::ZERO {
+                        home_direction
+                    } else {
+                        blended
+                    };
+
+                    // Reaching the base is handled by the base collision system
+                }
+            }
+        }
+
+        // Move ant, turning back along any axis a wall stops it on
+        let step = ant.velocity * ANT_SPEED * ant.traits.speed * dt;
+        let (next, stopped) = obstacles.slide(transform.translation.truncate(), step);
+        if stopped.x {
+            ant.velocity.x = -ant.velocity.x;
+        }
+        if stopped.y {
+            ant.velocity.y = -ant.velocity.y;
+        }
+        transform.translation = next.extend(transform.translation.z);
+    }
+}
+
+pub fn follow_markers(
+    mut ants: Query<(&Transform, &mut Ant)>,
+    markers: Query<(&Marker, &Transform), (With<Marker>, Without<Ant>)>,
+    grid_map: Res<GridMap>,
+    config: Res<Config>,
+) {
+    use crate::grid::sensor_cells;
+
+    const MAX_INTENSITY: f32 = 100.0;
+    const INFLUENCE_STRENGTH: f32 = 0.3; // How much markers influence direction (0.0 to 1.0)
+    const REPULSION_STRENGTH: f32 = 0.3; // How strongly danger markers push ants away
+
+    let exponent = config.steering.weighting_exponent;
+    let min_alignment = config.steering.max_angle_degrees.to_radians().cos();
+
+    for (ant_transform, mut ant) in ants.iter_mut() {
+        // Determine which marker type to follow based on ant state
+        let target_marker_type = match ant.state {
+            AntState::Searching if ant.exploring || ant.task != Task::Forage => None,
+            AntState::Searching => Some(MarkerType::Food),
+            AntState::Returning => Some(MarkerType::Base),
+            AntState::Resting | AntState::CarryingCorpse => continue,
+        };
+        // Foragers and scouts steer away from danger markers, even while exploring
+        let avoids_danger = ant.state == AntState::Searching && ant.task.collects_food();
+
+        let ant_pos = ant_transform.translation.truncate();
+        let heading = ant.velocity.normalize_or_zero();
+        // Intensity-weighted sum of the directions to sensed markers, and the strongest intensity
+        let mut gradient = Vec2::ZERO;
+        let mut strongest_intensity: f32 = 0.0;
+        let mut repulsion = Vec2::ZERO;
+
+        // Get the grid cells the ant senses
+        let front_cells = sensor_cells(ant_pos, ant.velocity, &config.sensor, config.grid_type);
+
+        // Check markers only in the sensed cells
+        for (_, cell_data) in grid_map.iter_cells(front_cells) {
+            if avoids_danger {
+                if let Some(entity) = cell_data.danger_marker {
+                    if let Ok((marker, marker_transform)) = markers.get(entity) {
+                        let away = ant_pos - marker_transform.translation.truncate();
+                        repulsion += away.normalize_or_zero() * (marker.intensity / MAX_INTENSITY);
+                    }
+                }
+            }
+
+            let Some(target_marker_type) = target_marker_type else {
+                continue;
+            };
+
+            // Get the marker entity of the target type
+            if let Some(entity) = cell_data.marker(target_marker_type) {
+                // Query the marker to get its data
+                if let Ok((marker, marker_transform)) = markers.get(entity) {
+                    if marker.marker_type != target_marker_type {
+                        continue;
+                    }
+
+                    // Markers under the ant or too far off its heading give no direction
+                    let direction =
+                        (marker_transform.translation.truncate() - ant_pos).normalize_or_zero();
+                    if direction == Vec2::ZERO
+                        || (heading != Vec2::ZERO && direction.dot(heading) < min_alignment)
+                    {
+                        continue;
+                    }
+
+                    let strength = (marker.intensity / MAX_INTENSITY).clamp(0.0, 1.0);
+                    gradient += direction * strength.powf(exponent);
+                    strongest_intensity = strongest_intensity.max(marker.intensity);
+                }
+            }
+        }
+
+        // Blend the gradient direction with current velocity
+        if gradient != Vec2::ZERO {
+            // Calculate influence factor based on the strongest sensed marker
+            let influence = ((strongest_intensity / MAX_INTENSITY)
+                * INFLUENCE_STRENGTH
+                * ant.traits.pheromone_sensitivity)
+                .min(1.0);
+
+            let blended_velocity =
+                ant.velocity * (1.0 - influence) + gradient.normalize() * influence;
+            ant.velocity = blended_velocity.normalize_or_zero();
+            if ant.velocity == Vec2::ZERO {
+                ant.velocity = gradient.normalize();
+            }
+        }
+
+        // Push away from nearby danger markers
+        if repulsion != Vec2::ZERO {
+            let influence = (repulsion.length().min(1.0)
+                * REPULSION_STRENGTH
+                * ant.traits.pheromone_sensitivity)
+                .min(1.0);
+            let blended_velocity =
+                ant.velocity * (1.0 - influence) + repulsion.normalize() * influence;
+            ant.velocity = blended_velocity.normalize_or_zero();
+            if ant.velocity == Vec2::ZERO {
+                ant.velocity = repulsion.normalize();
+            }
+        }
+    }
+}
+
+pub fn keep_ants_in_bounds(
+    mut ants: Query<&mut Transform, With<Ant>>,
+    config: Res<crate::config::Config>,
+) {
+    use crate::grid::GRID_CELL_SIZE;
+    // Map size in config is grid cells, convert to pixels
+    let map_width_pixels = config.map_size.0 as f32 * GRID_CELL_SIZE;
+    let map_height_pixels = config.map_size.1 as f32 * GRID_CELL_SIZE;
+
+    for mut transform in ants.iter_mut() {
+        // Wrap around horizontally: left to right, right to left
+        if transform.translation.x < 0.0 {
+            transform.translation.x = map_width_pixels;
+        } else if transform.translation.x > map_width_pixels {
+            transform.translation.x = 0.0;
+        }
+
+        // Wrap around vertically: up to down, down to up
+        if transform.translation.y < 0.0 {
+            transform.translation.y = map_height_pixels;
+        } else if transform.translation.y > map_height_pixels {
+            transform.translation.y = 0.0;
+        }
+    }
+}
+
+// Rotate each ant so its sprite faces its direction of travel
+pub fn orient_ants(mut ants: Query<(&mut Transform, &Ant)>) {
+    for (mut transform, ant) in ants.iter_mut() {
+        if ant.velocity.length_squared() > 0.0 {
+            let angle = ant.velocity.y.atan2(ant.velocity.x);
+            transform.rotation = Quat::from_rotation_z(angle);
+        }
+    }
+}
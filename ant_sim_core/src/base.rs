@@ -0,0 +1,160 @@
+use crate::ant::{Ant, AntState};
+use crate::colony::Colony;
+use crate::events::FoodDelivered;
+use crate::grid::GridMap;
+use crate::marker::Marker;
+use crate::rng::SimRng;
+use crate::spatial::Landmarks;
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Base;
+
+// Position of the base closest to pos
+pub fn nearest_base(pos: Vec2, bases: impl IntoIterator<Item = Vec2>) -> Option<Vec2> {
+    bases.into_iter().min_by(|a, b| {
+        pos.distance_squared(*a)
+            .total_cmp(&pos.distance_squared(*b))
+    })
+}
+
+pub fn check_base_collision(
+    mut ants: Query<(Entity, &Transform, &mut Ant, &Colony)>,
+    landmarks: Landmarks,
+    mut delivered_events: EventWriter<FoodDelivered>,
+    config: Res<crate::config::Config>,
+) {
+    const COLLISION_THRESHOLD: f32 = 10.0;
+
+    for (entity, transform, mut ant, colony) in ants.iter_mut() {
+        if ant.state == AntState::Returning && ant.has_food {
+            // Ants only deliver to bases of their own colony
+            let ant_pos = transform.translation.truncate();
+            let Some((base_pos, _)) = landmarks.nearest_base(ant_pos, Some(*colony)) else {
+                continue;
+            };
+
+            if ant_pos.distance(base_pos) < COLLISION_THRESHOLD {
+                delivered_events.send(FoodDelivered {
+                    ant: entity,
+                    colony: *colony,
+                    position: ant_pos,
+                    arrival_direction: ant.velocity,
+                    trip_secs: ant.trip_timer,
+                });
+
+                // Drop food at base, refill on pheromone and rest before heading out again
+                ant.has_food = false;
+                ant.pheromone = config.pheromone_reserve.full();
+                ant.state = AntState::Resting;
+                ant.state_timer = 0.0;
+                // Face back the way the ant came so it leaves with a U-turn
+                ant.velocity = -ant.velocity;
+            }
+        }
+    }
+}
+
+pub fn wake_resting_ants(
+    mut ants: Query<(&Transform, &mut Ant)>,
+    markers: Query<&Marker>,
+    grid_map: Res<GridMap>,
+    config: Res<crate::config::Config>,
+    mut rng: ResMut<SimRng>,
+) {
+    const RECRUITMENT_RADIUS: f32 = 96.0;
+
+    for (transform, mut ant) in ants.iter_mut() {
+        if ant.state != AntState::Resting {
+            continue;
+        }
+
+        let ant_pos = transform.translation.truncate();
+
+        // Sum food marker intensity around the base to gauge incoming trail strength.
+        // A single ant's own trail is not enough to trigger recruitment.
+        let mut trail_strength = 0.0;
+        let mut strongest_trail: Option<(Vec2, f32)> = None; // (position, intensity)
+        let nearby_cells = grid_map.get_nearby_cells(ant_pos, RECRUITMENT_RADIUS);
+        for (_, cell_data) in grid_map.iter_cells(nearby_cells) {
+            let Some(entity) = cell_data.food_marker else {
+                continue;
+            };
+            if let Ok(marker) = markers.get(entity) {
+                trail_strength += marker.intensity;
+                let is_stronger =
+                    strongest_trail.is_none_or(|(_, intensity)| marker.intensity > intensity);
+                if is_stronger {
+                    let trail_pos = grid_map.grid_type().grid_to_world(marker.grid_cell);
+                    strongest_trail = Some((trail_pos, marker.intensity));
+                }
+            }
+        }
+
+        let recruited = match strongest_trail {
+            Some((trail_pos, _)) if trail_strength >= config.recruitment_intensity => {
+                // Head out along the incoming trail
+                let direction = (trail_pos - ant_pos).normalize_or_zero();
+                if direction != Vec2::ZERO {
+                    ant.velocity = direction;
+                }
+                true
+            }
+            _ => false,
+        };
+
+        if recruited || ant.state_timer >= config.rest_duration {
+            ant.start_searching(&config, &mut *rng);
+        }
+    }
+}
+
+// Ants waiting near the base are recruited by a delivering ant and
+// biased to leave in the direction it arrived from
+pub fn recruit_at_base(
+    mut delivered_events: EventReader<FoodDelivered>,
+    mut ants: Query<(Entity, &Transform, &mut Ant, &Colony)>,
+    config: Res<crate::config::Config>,
+    mut rng: ResMut<SimRng>,
+) {
+    const RECRUITMENT_RADIUS: f32 = 48.0;
+    const SEARCHING_BIAS: f32 = 0.5; // How much a searching ant's heading is pulled toward the source
+
+    for event in delivered_events.read() {
+        let source_direction = (-event.arrival_direction).normalize_or_zero();
+        if source_direction == Vec2::ZERO {
+            continue;
+        }
+
+        let mut recruited = 0;
+        for (entity, transform, mut ant, colony) in ants.iter_mut() {
+            if recruited >= config.recruits_per_delivery {
+                break;
+            }
+            if entity == event.ant || ant.has_food || *colony != event.colony {
+                continue;
+            }
+            let distance = transform.translation.truncate().distance(event.position);
+            if distance > RECRUITMENT_RADIUS {
+                continue;
+            }
+
+            match ant.state {
+                AntState::Resting => {
+                    ant.start_searching(&config, &mut *rng);
+                    ant.velocity = source_direction;
+                }
+                AntState::Searching => {
+                    ant.velocity = (ant.velocity * (1.0 - SEARCHING_BIAS)
+                        + source_direction * SEARCHING_BIAS)
+                        .normalize_or_zero();
+                    if ant.velocity == Vec2::ZERO {
+                        ant.velocity = source_direction;
+                    }
+                }
+                AntState::Returning | AntState::CarryingCorpse => continue,
+            }
+            recruited += 1;
+        }
+    }
+}
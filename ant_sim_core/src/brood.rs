@@ -0,0 +1,179 @@
+use crate::ant::{spawn_ant, Caste};
+use crate::base::Base;
+use crate::colony::Colony;
+use crate::config::{BroodConfig, Config};
+use crate::nest::Nest;
+use crate::rng::SimRng;
+use bevy::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroodStage {
+    Egg,
+    Larva,
+    Pupa,
+}
+
+impl BroodConfig {
+    // Stage of brood laid age_secs ago, None once it is old enough to hatch
+    pub fn stage(&self, age_secs: f32) -> Option<BroodStage> {
+        if age_secs < self.egg_secs {
+            Some(BroodStage::Egg)
+        } else if age_secs < self.egg_secs + self.larva_secs {
+            Some(BroodStage::Larva)
+        } else if age_secs < self.egg_secs + self.larva_secs + self.pupa_secs {
+            Some(BroodStage::Pupa)
+        } else {
+            None
+        }
+    }
+}
+
+// Queen of a base, laying eggs in its brood chamber
+#[derive(Component, Debug, Default)]
+pub struct Queen {
+    // Fraction of the next egg laid so far, an egg is laid each time it reaches 1
+    laying_progress: f32,
+}
+
+impl Queen {
+    // Eggs per second: 1 / spawn_rate when the nest is well stocked, halved when it holds
+    // half_rate_food and falling to 0 as it runs out
+    pub fn laying_rate(config: &Config, stored_food: u32) -> f32 {
+        if config.spawn_rate <= 0.0 {
+            return 0.0;
+        }
+        let stored = stored_food as f32;
+        let saturation = stored / (stored + config.brood.half_rate_food.max(f32::EPSILON));
+        saturation / config.spawn_rate
+    }
+}
+
+// Eggs, larvae and pupae developing in a base's brood chamber, as seconds since each was laid
+#[derive(Component, Debug, Default)]
+pub struct Brood {
+    pub ages: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroodCounts {
+    pub eggs: usize,
+    pub larvae: usize,
+    pub pupae: usize,
+}
+
+impl BroodCounts {
+    pub fn total(&self) -> usize {
+        self.eggs + self.larvae + self.pupae
+    }
+
+    fn add(&mut self, stage: BroodStage) {
+        match stage {
+            BroodStage::Egg => self.eggs += 1,
+            BroodStage::Larva => self.larvae += 1,
+            BroodStage::Pupa => self.pupae += 1,
+        }
+    }
+}
+
+impl Brood {
+    pub fn counts(&self, config: &BroodConfig) -> BroodCounts {
+        let mut counts = BroodCounts::default();
+        for stage in self.ages.iter().filter_map(|age| config.stage(*age)) {
+            counts.add(stage);
+        }
+        counts
+    }
+}
+
+// Brood of each colony over its bases, indexed by colony id
+#[derive(Resource, Default, Debug)]
+pub struct BroodStats {
+    pub colonies: Vec<BroodCounts>,
+}
+
+impl BroodStats {
+    pub fn new(colony_count: usize) -> Self {
+        Self {
+            colonies: vec![BroodCounts::default(); colony_count],
+        }
+    }
+}
+
+// Queens lay at a rate following the food stored in their nest, each egg using up
+// food_per_egg from the chambers. Without enough food the next egg waits.
+pub fn lay_eggs(
+    mut bases: Query<(&mut Queen, &mut Brood, &mut Nest), With<Base>>,
+    config: Res<Config>,
+    time: Res<Time>,
+) {
+    for (mut queen, mut brood, mut nest) in bases.iter_mut() {
+        let rate = Queen::laying_rate(&config, nest.stored_food());
+        queen.laying_progress = (queen.laying_progress + rate * time.delta_seconds()).min(1.0);
+        if queen.laying_progress < 1.0 {
+            continue;
+        }
+        if nest.take_food(config.brood.food_per_egg) {
+            queen.laying_progress = 0.0;
+            brood.ages.push(0.0);
+        }
+    }
+}
+
+// Age the brood and hatch pupae that have finished developing into ants at the base
+pub fn develop_brood(
+    mut commands: Commands,
+    mut bases: Query<(&Transform, &Colony, &mut Brood), With<Base>>,
+    config: Res<Config>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+) {
+    let dt = time.delta_seconds();
+    for (transform, colony, mut brood) in bases.iter_mut() {
+        let mut hatched = 0;
+        brood.ages.retain_mut(|age| {
+            *age += dt;
+            let developing = config.brood.stage(*age).is_some();
+            if !developing {
+                hatched += 1;
+            }
+            developing
+        });
+
+        let soldier_ratio = config
+            .colony_configs()
+            .get(colony.0 as usize)
+            .map_or(0.0, |colony_config| colony_config.soldier_ratio);
+        for _ in 0..hatched {
+            let caste = Caste::roll(soldier_ratio, &mut *rng);
+            spawn_ant(
+                &mut commands,
+                &config,
+                &mut *rng,
+                *colony,
+                caste,
+                transform.translation,
+            );
+        }
+    }
+}
+
+pub fn track_brood_stats(
+    bases: Query<(&Colony, &Brood)>,
+    config: Res<Config>,
+    mut stats: ResMut<BroodStats>,
+) {
+    for counts in stats.colonies.iter_mut() {
+        *counts = BroodCounts::default();
+    }
+    for (colony, brood) in bases.iter() {
+        let index = colony.0 as usize;
+        if index >= stats.colonies.len() {
+            stats.colonies.resize(index + 1, BroodCounts::default());
+        }
+        let counts = brood.counts(&config.brood);
+        let totals = &mut stats.colonies[index];
+        totals.eggs += counts.eggs;
+        totals.larvae += counts.larvae;
+        totals.pupae += counts.pupae;
+    }
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+// Simulated time and the number of simulation steps run, both standing still while paused.
+// Unlike wall time it doesn't depend on how fast frames are rendered when time is stepped manually.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct SimClock {
+    elapsed_secs: f64,
+    tick: u64,
+}
+
+impl SimClock {
+    pub fn elapsed_secs(&self) -> f64 {
+        self.elapsed_secs
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+}
+
+pub fn advance_sim_clock(mut clock: ResMut<SimClock>, time: Res<Time<Virtual>>) {
+    if time.is_paused() {
+        return;
+    }
+    clock.elapsed_secs += time.delta_seconds_f64();
+    clock.tick += 1;
+}
@@ -0,0 +1,90 @@
+use crate::events::{AntKilled, FoodDelivered};
+use bevy::prelude::*;
+
+// Colony an ant or base belongs to, as an index into Config::colony_configs
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Colony(pub u32);
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColonyTotals {
+    pub food_delivered: u32,
+    pub kills: u32,
+    pub deaths: u32,
+}
+
+// Running totals per colony, indexed by colony id
+#[derive(Resource, Default)]
+pub struct ColonyStats {
+    pub colonies: Vec<ColonyTotals>,
+}
+
+impl ColonyStats {
+    pub fn new(colony_count: usize) -> Self {
+        Self {
+            colonies: vec![ColonyTotals::default(); colony_count],
+        }
+    }
+
+    fn totals_mut(&mut self, colony: u32) -> &mut ColonyTotals {
+        let index = colony as usize;
+        if index >= self.colonies.len() {
+            self.colonies.resize(index + 1, ColonyTotals::default());
+        }
+        &mut self.colonies[index]
+    }
+}
+
+// Distribution of the trips that ended in a delivery over some interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TripSummary {
+    pub count: usize,
+    pub mean_secs: f32,
+    pub p95_secs: f32,
+}
+
+// Durations of the trips completed since they were last summarized
+#[derive(Resource, Default)]
+pub struct TripDurations {
+    pending: Vec<f32>,
+}
+
+impl TripDurations {
+    pub fn record(&mut self, trip_secs: f32) {
+        self.pending.push(trip_secs);
+    }
+
+    // Summarize the trips recorded so far and start a new interval; None if there were none
+    pub fn take_summary(&mut self) -> Option<TripSummary> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let mut trips = std::mem::take(&mut self.pending);
+        trips.sort_by(f32::total_cmp);
+        // Nearest-rank percentile
+        let p95_rank = ((trips.len() as f32 * 0.95).ceil() as usize).clamp(1, trips.len());
+        Some(TripSummary {
+            count: trips.len(),
+            mean_secs: trips.iter().sum::<f32>() / trips.len() as f32,
+            p95_secs: trips[p95_rank - 1],
+        })
+    }
+}
+
+pub fn track_colony_stats(
+    mut stats: ResMut<ColonyStats>,
+    mut trips: ResMut<TripDurations>,
+    mut delivered_events: EventReader<FoodDelivered>,
+    mut killed_events: EventReader<AntKilled>,
+) {
+    for event in delivered_events.read() {
+        stats.totals_mut(event.colony.0).food_delivered += 1;
+        trips.record(event.trip_secs);
+    }
+
+    for event in killed_events.read() {
+        if let Some(killer) = event.killer_colony {
+            stats.totals_mut(killer.0).kills += 1;
+        }
+        stats.totals_mut(event.victim_colony.0).deaths += 1;
+    }
+}
@@ -0,0 +1,70 @@
+use crate::ant::{Ant, Caste};
+use crate::colony::Colony;
+use crate::events::AntKilled;
+use crate::rng::SimRng;
+use crate::spatial::SpatialIndex;
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Ants of different colonies that come into contact may kill each other.
+// Each pair in range rolls once per frame, scaled by frame time so kill chances are per second.
+// Candidates come from the ant index, which has positions from the start of the frame.
+pub fn resolve_combat(
+    mut commands: Commands,
+    ants: Query<(Entity, &Transform, &Ant, &Colony)>,
+    ant_index: Res<SpatialIndex<Ant>>,
+    config: Res<crate::config::Config>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+    mut killed_events: EventWriter<AntKilled>,
+) {
+    let combat = &config.combat;
+    let dt = time.delta_seconds();
+
+    // Nothing to fight over with a single colony
+    let colonies: HashSet<Colony> = ants.iter().map(|(_, _, _, colony)| *colony).collect();
+    if colonies.len() < 2 {
+        return;
+    }
+
+    let mut dead = HashSet::new();
+
+    for (entity, transform, ant, colony) in ants.iter() {
+        if dead.contains(&entity) {
+            continue;
+        }
+        let pos = transform.translation.truncate();
+
+        for (other, _) in ant_index.within_radius(pos, combat.encounter_radius) {
+            let Ok((_, other_transform, other_ant, other_colony)) = ants.get(other) else {
+                continue;
+            };
+            if other_colony == colony || dead.contains(&other) {
+                continue;
+            }
+            let other_pos = other_transform.translation.truncate();
+            if pos.distance(other_pos) > combat.encounter_radius {
+                continue;
+            }
+
+            let kill_chance = match (ant.caste, other_ant.caste) {
+                (Caste::Soldier, Caste::Worker) => combat.soldier_kills_worker,
+                (Caste::Soldier, Caste::Soldier) => combat.soldier_kills_soldier,
+                (Caste::Worker, Caste::Worker) => combat.worker_kills_worker,
+                (Caste::Worker, Caste::Soldier) => 0.0,
+            };
+
+            if kill_chance > 0.0 && rng.gen::<f32>() < kill_chance * dt {
+                dead.insert(other);
+                commands.entity(other).despawn();
+                killed_events.send(AntKilled {
+                    position: other_pos,
+                    victim_colony: *other_colony,
+                    killer_colony: Some(*colony),
+                    carried_food: other_ant.has_food,
+                });
+            }
+        }
+    }
+}
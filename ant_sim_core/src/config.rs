@@ -0,0 +1,1126 @@
+use crate::ant::Caste;
+use crate::grid::GridType;
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct Config {
+    pub map_size: (u32, u32),
+    // Accepts a single [x, y] location or a list of them
+    #[serde(alias = "base_location", deserialize_with = "one_or_many_locations")]
+    pub base_locations: Vec<(u32, u32)>,
+    // Each entry is either a plain [x, y] cell or a FoodSourceConfig object
+    pub food_locations: Vec<FoodSourceConfig>,
+    /// Rectangles of grid cells ants can't walk or see through
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleRect>,
+    #[serde(default)]
+    pub pathfinding: PathfindingConfig,
+    /// Fastest seconds between eggs laid by each base's queen, reached when its nest is well
+    /// stocked with food. 0 disables laying.
+    pub spawn_rate: f32,
+    pub marker_spawn_interval: f32,
+    pub marker_lifetime: f32,
+    /// How marker intensity fades over marker_lifetime, kept constant when unset
+    #[serde(default)]
+    pub marker_decay: MarkerDecay,
+    /// Pheromone each ant can lay before it is back at the base
+    #[serde(default)]
+    pub pheromone_reserve: PheromoneReserveConfig,
+    /// Shape of the cells markers are laid in and sensed by
+    #[serde(default)]
+    pub grid_type: GridType,
+    pub initial_ant_count: u32,
+    pub food_quantity: u32,
+    /// How much a source's remaining food strengthens the trails laid from it: ants returning
+    /// with food deposit quality * (remaining / food_quantity)^richness_exponent times the usual
+    /// intensity. 0 makes trail strength depend on quality alone.
+    #[serde(default = "default_richness_exponent")]
+    pub richness_exponent: f32,
+    /// Seconds an ant rests at the base after delivering food
+    #[serde(default = "default_rest_duration")]
+    pub rest_duration: f32,
+    /// Total food marker intensity around the base needed to recruit a resting ant
+    #[serde(default = "default_recruitment_intensity")]
+    pub recruitment_intensity: f32,
+    /// Probability that an ant ignores pheromones and explores randomly on a search trip
+    #[serde(default = "default_exploration_rate")]
+    pub exploration_rate: f32,
+    /// Maximum number of ants at the base recruited by each food delivery
+    #[serde(default = "default_recruits_per_delivery")]
+    pub recruits_per_delivery: u32,
+    /// Draw entities with the sprite sheets below instead of colored rectangles
+    #[serde(default)]
+    pub use_sprite_sheets: bool,
+    #[serde(default)]
+    pub sprite_sheets: SpriteSheetConfig,
+    /// Competing colonies. When empty, base_locations and initial_ant_count form a single colony.
+    #[serde(default)]
+    pub colonies: Vec<ColonyConfig>,
+    #[serde(default)]
+    pub combat: CombatConfig,
+    #[serde(default)]
+    pub steering: SteeringConfig,
+    #[serde(default)]
+    pub sensor: SensorConfig,
+    #[serde(default)]
+    pub vision: VisionConfig,
+    #[serde(default)]
+    pub transport: TransportConfig,
+    #[serde(default)]
+    pub tasks: TaskConfig,
+    #[serde(default)]
+    pub traits: TraitsConfig,
+    /// Seconds before a corpse decays away
+    #[serde(default = "default_corpse_lifetime")]
+    pub corpse_lifetime: f32,
+    /// Corpses within this distance (pixels) of a base are carried to the refuse zone
+    #[serde(default = "default_necrophoresis_radius")]
+    pub necrophoresis_radius: f32,
+    /// Save a screenshot every this many seconds into captures/<run-timestamp>/, disabled when unset
+    #[serde(default)]
+    pub screenshot_interval_secs: Option<f32>,
+    #[serde(default)]
+    pub recording: RecordingConfig,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    /// Seed for the simulation's random number generator, random when unset
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub keybindings: KeyBindingsConfig,
+    /// Language of the GUI
+    #[serde(default)]
+    pub language: Language,
+    /// Colors of the GUI and the map, also selectable from the GUI
+    #[serde(default)]
+    pub theme: ThemeName,
+    /// Number of ants dropped at the cursor by the spawn_ants key
+    #[serde(default = "default_cursor_spawn_count")]
+    pub cursor_spawn_count: u32,
+    /// Quantity of food sources placed with the food tool, food_quantity when unset
+    #[serde(default)]
+    pub placed_food_quantity: Option<u32>,
+    #[serde(default)]
+    pub log_rotation: LogRotationConfig,
+    /// Add entity, grid, memory and per-system timing columns to the simulation log
+    #[serde(default)]
+    pub detailed_logging: bool,
+    #[serde(default)]
+    pub end_conditions: EndConditionsConfig,
+    #[serde(default)]
+    pub frame_budget: FrameBudgetConfig,
+    #[serde(default)]
+    pub nest: NestConfig,
+    #[serde(default)]
+    pub brood: BroodConfig,
+    /// Changes scripted at set simulation times, for repeatable perturbation experiments
+    #[serde(default)]
+    pub timeline: Vec<TimelineEvent>,
+    #[serde(default)]
+    pub milestones: MilestonesConfig,
+    #[serde(default)]
+    pub trails: TrailConfig,
+    #[serde(default)]
+    pub spike_capture: SpikeCaptureConfig,
+    #[serde(default)]
+    pub predators: PredatorConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        serde_json::from_str(DEFAULT_CONFIG).expect("default config should be valid")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "FoodLocationEntry")]
+pub struct FoodSourceConfig {
+    pub location: (u32, u32),
+    /// Overrides the global food_quantity
+    pub quantity: Option<u32>,
+    /// Radius in grid cells around location; 0 is a single-cell source
+    pub radius: u32,
+    /// Seconds after depletion before the source refills, never when unset
+    pub respawn_secs: Option<f32>,
+    /// Multiplier of the trail strength laid by ants returning from this source
+    pub quality: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FoodLocationEntry {
+    Cell((u32, u32)),
+    Source {
+        location: (u32, u32),
+        #[serde(default)]
+        quantity: Option<u32>,
+        #[serde(default)]
+        radius: u32,
+        #[serde(default)]
+        respawn_secs: Option<f32>,
+        #[serde(default = "default_food_quality")]
+        quality: f32,
+    },
+}
+
+fn default_food_quality() -> f32 {
+    1.0
+}
+
+impl From<FoodLocationEntry> for FoodSourceConfig {
+    fn from(entry: FoodLocationEntry) -> Self {
+        match entry {
+            FoodLocationEntry::Cell(location) => Self {
+                location,
+                quantity: None,
+                radius: 0,
+                respawn_secs: None,
+                quality: default_food_quality(),
+            },
+            FoodLocationEntry::Source {
+                location,
+                quantity,
+                radius,
+                respawn_secs,
+                quality,
+            } => Self {
+                location,
+                quantity,
+                radius,
+                respawn_secs,
+                quality,
+            },
+        }
+    }
+}
+
+/// Video/GIF recording of the window, toggled with the toggle_recording key (F11 by default)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingConfig {
+    /// Start recording as soon as the simulation starts
+    pub enabled: bool,
+    pub format: RecordingFormat,
+    pub fps: u32,
+    /// Output width in pixels, at least 2, height keeps the window's aspect ratio. Window size
+    /// when unset.
+    #[serde(deserialize_with = "recording_width")]
+    pub width: Option<u32>,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: RecordingFormat::Gif,
+            fps: 10,
+            width: None,
+        }
+    }
+}
+
+/// When the run is over. Each enabled condition ends it; with none the simulation runs forever.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EndConditionsConfig {
+    /// End once every source is empty and all food has reached a base
+    pub all_food_delivered: bool,
+    /// End after this many simulated seconds
+    pub max_sim_secs: Option<f32>,
+    /// End when no ants are left
+    pub extinction: bool,
+    /// Close the app once the run ends instead of pausing it
+    pub exit: bool,
+    /// Where the summary JSON is written when the run ends
+    pub summary_path: String,
+}
+
+impl Default for EndConditionsConfig {
+    fn default() -> Self {
+        Self {
+            all_food_delivered: false,
+            max_sim_secs: None,
+            extinction: false,
+            exit: false,
+            summary_path: "summary.json".to_string(),
+        }
+    }
+}
+
+/// Splitting of the CSV log into segments, disabled unless a limit is set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRotationConfig {
+    /// Start a new segment once the current one reaches this size
+    pub max_size_mb: Option<f32>,
+    /// Start a new segment after this many hours
+    pub max_hours: Option<f32>,
+    /// Gzip each segment once it is closed
+    pub compress: bool,
+}
+
+impl LogRotationConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_size_mb.is_some() || self.max_hours.is_some()
+    }
+}
+
+/// Visual detail the GUI drops while frames run long, before the simulation is slowed down
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrameBudgetConfig {
+    /// Average frame time (ms) above which detail is reduced, never reduced when unset
+    pub max_frame_ms: Option<f32>,
+    /// Detail comes back once the average is below this fraction of max_frame_ms
+    pub recover_fraction: f32,
+    /// Seconds between detail changes, so each one can take effect before the next
+    pub step_secs: f32,
+}
+
+impl Default for FrameBudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_frame_ms: Some(33.0),
+            recover_fraction: 0.7,
+            step_secs: 2.0,
+        }
+    }
+}
+
+/// Diagnostic bundles written to the run's log folder when a frame takes too long
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpikeCaptureConfig {
+    /// Frame time (ms) above which a bundle is written, never written when unset
+    pub threshold_ms: Option<f32>,
+    /// Seconds after a bundle before the next one, so a slow stretch gives a single bundle
+    pub cooldown_secs: f32,
+}
+
+impl Default for SpikeCaptureConfig {
+    fn default() -> Self {
+        Self {
+            threshold_ms: Some(250.0),
+            cooldown_secs: 10.0,
+        }
+    }
+}
+
+/// Predators that follow food trails and lie in wait on them for passing ants. Strong trails
+/// lead ants to food faster but also draw predators to them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PredatorConfig {
+    /// Grid cells a predator starts on, one predator each; none by default. Square map cells
+    /// like base_locations, with hex markers too.
+    pub locations: Vec<(u32, u32)>,
+    /// Pixels per second
+    pub speed: f32,
+    /// Food markers within this distance (pixels) draw predators toward them
+    pub sense_radius: f32,
+    /// Predators stop and wait on food markers at least this strong
+    pub ambush_intensity: f32,
+    /// Ants within this distance (pixels) of a predator can be caught, and soldiers can fight
+    pub strike_radius: f32,
+    /// Chance per second of catching an ant within strike_radius
+    pub kill_chance: f32,
+    /// Chance per second of a soldier within strike_radius killing the predator
+    pub soldier_kills_predator: f32,
+    /// Seconds a predator stays still after a catch
+    pub digest_secs: f32,
+}
+
+impl Default for PredatorConfig {
+    fn default() -> Self {
+        Self {
+            locations: Vec::new(),
+            speed: 30.0,
+            sense_radius: 96.0,
+            ambush_intensity: 40.0,
+            strike_radius: 10.0,
+            kill_chance: 2.0,
+            soldier_kills_predator: 0.5,
+            digest_secs: 15.0,
+        }
+    }
+}
+
+/// Underground nest below each base, where ants store the food they deliver and rest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NestConfig {
+    /// Food storage chambers dug off each nest's gallery, beside one brood chamber
+    pub food_chambers: u32,
+    /// Food units a chamber holds before deliveries go to the next one
+    pub chamber_capacity: u32,
+    /// Walking speed of ants inside the nest, in pixels per second
+    pub walk_speed: f32,
+}
+
+impl Default for NestConfig {
+    fn default() -> Self {
+        Self {
+            food_chambers: 2,
+            chamber_capacity: 200,
+            walk_speed: 60.0,
+        }
+    }
+}
+
+/// Eggs laid by the queens and how long they take to become ants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BroodConfig {
+    /// Food units taken from the nest's chambers for each egg
+    pub food_per_egg: u32,
+    /// Stored food at which a queen lays at half her fastest rate
+    pub half_rate_food: f32,
+    /// Seconds spent as an egg, then a larva, then a pupa before hatching into an ant
+    pub egg_secs: f32,
+    pub larva_secs: f32,
+    pub pupa_secs: f32,
+}
+
+impl Default for BroodConfig {
+    fn default() -> Self {
+        Self {
+            food_per_egg: 2,
+            half_rate_food: 20.0,
+            egg_secs: 10.0,
+            larva_secs: 15.0,
+            pupa_secs: 10.0,
+        }
+    }
+}
+
+/// When the milestones announced during a run and recorded in its summary are reached. The
+/// first stable trail is the one found by the trail analysis, see TrailConfig.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MilestonesConfig {
+    /// Food units delivered over all colonies for the delivery milestone
+    pub delivery_target: u32,
+}
+
+impl Default for MilestonesConfig {
+    fn default() -> Self {
+        Self {
+            delivery_target: 100,
+        }
+    }
+}
+
+/// How trails are picked out of the food markers, and when they count as stable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrailConfig {
+    /// Simulated seconds between analyses
+    pub interval_secs: f32,
+    /// Food marker intensity a cell needs to be part of a trail
+    pub min_intensity: f32,
+    /// Connected cells a chain needs to count as a trail
+    pub min_cells: u32,
+    /// Share of trail cells an analysis must have in common with the one before for the trails
+    /// to count as unchanged
+    pub stable_overlap: f32,
+    /// Seconds the trails must stay unchanged to be stable
+    pub stable_secs: f32,
+}
+
+impl Default for TrailConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 1.0,
+            min_intensity: 25.0,
+            min_cells: 8,
+            stable_overlap: 0.5,
+            stable_secs: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    Gif,
+    // Encoded by the ffmpeg executable, which must be on the PATH
+    Mp4,
+}
+
+// Key names per action, e.g. "W", "F12" or "Space". Each action takes one key or a list of keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindingsConfig {
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub camera_up: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub camera_down: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub camera_left: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub camera_right: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub screenshot: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub toggle_recording: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub spawn_ants: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub toggle_profiler: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub toggle_console: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub export_pheromones: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub toggle_dashboard: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub next_world: Vec<String>,
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub switch_layer: Vec<String>,
+    /// Centers the camera on the map and zooms to fit all of it
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub fit_map: Vec<String>,
+    /// Held while pressing 1-9 to save the camera to that bookmark instead of going to it
+    #[serde(deserialize_with = "one_or_many_keys")]
+    pub save_bookmark: Vec<String>,
+}
+
+impl Default for KeyBindingsConfig {
+    fn default() -> Self {
+        let keys = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        // ZQSD (French layout) and WASD (English layout) both move the camera
+        Self {
+            camera_up: keys(&["Z", "W"]),
+            camera_down: keys(&["S"]),
+            camera_left: keys(&["Q", "A"]),
+            camera_right: keys(&["D"]),
+            screenshot: keys(&["F12"]),
+            toggle_recording: keys(&["F11"]),
+            spawn_ants: keys(&["N"]),
+            toggle_profiler: keys(&["F3"]),
+            toggle_console: keys(&["`"]),
+            export_pheromones: keys(&["F9"]),
+            toggle_dashboard: keys(&["F2"]),
+            next_world: keys(&["Tab"]),
+            switch_layer: keys(&["L"]),
+            fit_map: keys(&["Home"]),
+            save_bookmark: keys(&["LCtrl", "RCtrl"]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColonyConfig {
+    #[serde(alias = "base_location", deserialize_with = "one_or_many_locations")]
+    pub base_locations: Vec<(u32, u32)>,
+    pub initial_ant_count: u32,
+    /// Fraction of spawned ants that are soldiers
+    #[serde(default)]
+    pub soldier_ratio: f32,
+}
+
+// Kill chances are per second of contact between ants of different colonies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CombatConfig {
+    pub encounter_radius: f32,
+    pub soldier_kills_worker: f32,
+    pub soldier_kills_soldier: f32,
+    pub worker_kills_worker: f32,
+}
+
+impl Default for CombatConfig {
+    fn default() -> Self {
+        Self {
+            encounter_radius: 8.0,
+            soldier_kills_worker: 0.8,
+            soldier_kills_soldier: 0.3,
+            worker_kills_worker: 0.0,
+        }
+    }
+}
+
+/// Division of labour between forage, guard, corpse_removal and scout tasks. A searching ant
+/// takes up a task with probability stimulus² / (stimulus² + threshold²), so ants with low
+/// thresholds answer weak stimuli.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskConfig {
+    /// Average seconds between a searching ant reconsidering its task
+    pub reassess_secs: f32,
+    /// Guards stay within this distance (pixels) of their base, and ants of other colonies
+    /// inside it call for guards
+    pub guard_radius: f32,
+    pub worker: TaskThresholds,
+    pub soldier: TaskThresholds,
+}
+
+impl Default for TaskConfig {
+    fn default() -> Self {
+        Self {
+            reassess_secs: 5.0,
+            guard_radius: 320.0,
+            worker: TaskThresholds {
+                forage: Some(0.5),
+                guard: Some(4.0),
+                corpse_removal: Some(1.0),
+                scout: Some(3.0),
+            },
+            soldier: TaskThresholds {
+                forage: None,
+                guard: Some(0.1),
+                corpse_removal: None,
+                scout: None,
+            },
+        }
+    }
+}
+
+impl TaskConfig {
+    pub fn thresholds(&self, caste: Caste) -> &TaskThresholds {
+        match caste {
+            Caste::Worker => &self.worker,
+            Caste::Soldier => &self.soldier,
+        }
+    }
+}
+
+/// Response thresholds of a caste per task. Tasks left out are never taken up.
+/// Stimuli are intruders near the base for guard, corpses near the base for corpse_removal,
+/// minutes since the last food delivery for scout, and always 1 for forage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskThresholds {
+    pub forage: Option<f32>,
+    pub guard: Option<f32>,
+    pub corpse_removal: Option<f32>,
+    pub scout: Option<f32>,
+}
+
+/// How ants steer along pheromone gradients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SteeringConfig {
+    /// Power applied to marker intensities when averaging the directions of sensed markers.
+    /// 0 weighs every marker equally, higher values lean towards the strongest ones.
+    pub weighting_exponent: f32,
+    /// Markers further than this many degrees off the ant's heading are ignored
+    pub max_angle_degrees: f32,
+}
+
+impl Default for SteeringConfig {
+    fn default() -> Self {
+        Self {
+            weighting_exponent: 2.0,
+            max_angle_degrees: 90.0,
+        }
+    }
+}
+
+/// What ants perceive of markers: grid cells in a cone ahead of them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorConfig {
+    /// Full opening angle of the cone, in degrees
+    pub fov_degrees: f32,
+    /// Sensing distance in pixels
+    pub range: f32,
+}
+
+impl Default for SensorConfig {
+    fn default() -> Self {
+        Self {
+            fov_degrees: 120.0,
+            range: 64.0,
+        }
+    }
+}
+
+/// How far and how wide searching ants see food, measured to the nearest edge of a source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisionConfig {
+    /// Full opening angle of the field of view, in degrees
+    pub fov_degrees: f32,
+    /// Viewing distance in pixels
+    pub range: f32,
+}
+
+impl Default for VisionConfig {
+    fn default() -> Self {
+        Self {
+            fov_degrees: 120.0,
+            range: 64.0,
+        }
+    }
+}
+
+/// Grid cells from one corner to the opposite one, both included
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ObstacleRect {
+    pub from: (u32, u32),
+    pub to: (u32, u32),
+}
+
+/// Scripted change made once the simulation clock reaches at_secs, e.g.
+/// `{ "at_secs": 120, "action": "remove_food_source", "index": 2 }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub at_secs: f32,
+    #[serde(flatten)]
+    pub action: TimelineAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TimelineAction {
+    /// Remove the source at index in food_locations, if it hasn't run out already
+    RemoveFoodSource { index: usize },
+    /// Place a food source, written like an entry of food_locations
+    AddFoodSource(FoodSourceConfig),
+    /// Block a rectangle of cells, written like an entry of obstacles
+    AddObstacle(ObstacleRect),
+    /// Spawn ants of a colony on a grid cell, soldiers in the colony's soldier_ratio
+    SpawnAnts {
+        colony: u32,
+        count: u32,
+        location: (u32, u32),
+    },
+    /// Spawn predators on a grid cell, a square map cell like in predators.locations
+    SpawnPredators { count: u32, location: (u32, u32) },
+}
+
+/// Pheromone each ant carries for laying markers. Markers weaken as the reserve empties and
+/// stop once it is spent; it is refilled at the base on each delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PheromoneReserveConfig {
+    /// Reserve of a full ant, unlimited when unset
+    pub capacity: Option<f32>,
+    /// Reserve used up by each marker
+    pub deposit_cost: f32,
+}
+
+impl Default for PheromoneReserveConfig {
+    fn default() -> Self {
+        Self {
+            capacity: Some(400.0),
+            deposit_cost: 1.0,
+        }
+    }
+}
+
+impl PheromoneReserveConfig {
+    pub fn full(&self) -> f32 {
+        self.capacity.unwrap_or(0.0)
+    }
+
+    // Share of a full reserve left, scaling the intensity of the next marker
+    pub fn fraction(&self, reserve: f32) -> f32 {
+        match self.capacity {
+            Some(capacity) if capacity > 0.0 => (reserve / capacity).clamp(0.0, 1.0),
+            _ => 1.0,
+        }
+    }
+
+    pub fn can_deposit(&self, reserve: f32) -> bool {
+        self.capacity.is_none() || reserve >= self.deposit_cost
+    }
+}
+
+/// Flow fields leading returning ants home around obstacles, one per colony over its bases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathfindingConfig {
+    pub enabled: bool,
+    /// Share of a returning ant's heading taken from the flow field when an obstacle hides its
+    /// base, the rest coming from pheromone steering
+    pub weight: f32,
+}
+
+impl Default for PathfindingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            weight: 0.5,
+        }
+    }
+}
+
+/// Heavy food items that several ants carry back to a base together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransportConfig {
+    pub items: Vec<HeavyFoodConfig>,
+    /// Speed of an item with enough carriers, as a fraction of the ants' walking speed
+    pub speed_factor: f32,
+    /// Searching workers this close (pixels) to an item's edge latch onto it
+    pub latch_radius: f32,
+    /// Seconds a carrier waits for enough help before letting go
+    pub give_up_secs: f32,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            speed_factor: 0.3,
+            latch_radius: 8.0,
+            give_up_secs: 20.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeavyFoodConfig {
+    pub location: (u32, u32),
+    /// Ants that must be latched on before the item moves
+    pub carriers_needed: u32,
+    /// Food units credited to the colony once the item reaches its base
+    #[serde(default = "default_heavy_food_value")]
+    pub value: u32,
+}
+
+// e.g. {"model": "exponential", "half_life_secs": 10.0}
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum MarkerDecay {
+    /// Markers keep their intensity until they evaporate
+    #[default]
+    None,
+    /// Falls steadily to zero over the marker's lifetime
+    Linear,
+    /// Halves every half_life_secs
+    Exponential { half_life_secs: f32 },
+    /// Drops by an equal share at evenly spaced points over the lifetime
+    Step { steps: u32 },
+}
+
+impl MarkerDecay {
+    // Share of its initial intensity a marker has left after age of lifetime seconds
+    pub fn remaining(&self, age: f32, lifetime: f32) -> f32 {
+        let progress = if lifetime > 0.0 {
+            (age / lifetime).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        match *self {
+            MarkerDecay::None => 1.0,
+            MarkerDecay::Linear => 1.0 - progress,
+            MarkerDecay::Exponential { half_life_secs } if half_life_secs > 0.0 => {
+                0.5_f32.powf(age / half_life_secs)
+            }
+            MarkerDecay::Exponential { .. } => 0.0,
+            MarkerDecay::Step { steps } => {
+                let steps = steps.max(1) as f32;
+                1.0 - (progress * steps).floor() / steps
+            }
+        }
+    }
+}
+
+/// Per-ant variation, drawn once for each ant when it spawns. Each trait multiplies the
+/// shared behavior, so 1.0 is an average ant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraitsConfig {
+    /// Walking speed
+    pub speed: TraitDistribution,
+    /// Size of the random turns taken while searching
+    pub turn_noise: TraitDistribution,
+    /// How strongly pheromones pull the ant
+    pub pheromone_sensitivity: TraitDistribution,
+}
+
+impl Default for TraitsConfig {
+    fn default() -> Self {
+        Self {
+            speed: TraitDistribution::Normal {
+                mean: 1.0,
+                std_dev: 0.1,
+            },
+            turn_noise: TraitDistribution::Normal {
+                mean: 1.0,
+                std_dev: 0.25,
+            },
+            pheromone_sensitivity: TraitDistribution::Normal {
+                mean: 1.0,
+                std_dev: 0.2,
+            },
+        }
+    }
+}
+
+// e.g. {"distribution": "uniform", "min": 0.8, "max": 1.2}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum TraitDistribution {
+    Constant { value: f32 },
+    Uniform { min: f32, max: f32 },
+    Normal { mean: f32, std_dev: f32 },
+}
+
+impl TraitDistribution {
+    // Draw a value, never below zero
+    pub fn sample(&self, rng: &mut impl rand::Rng) -> f32 {
+        let value = match *self {
+            TraitDistribution::Constant { value } => value,
+            TraitDistribution::Uniform { min, max } if min < max => rng.gen_range(min..max),
+            TraitDistribution::Uniform { min, .. } => min,
+            TraitDistribution::Normal { mean, std_dev } => {
+                // Box-Muller transform
+                let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+                let u2: f32 = rng.gen();
+                mean + std_dev * (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+            }
+        };
+        value.max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Fr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+}
+
+// Sound paths are relative to the assets/ directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// Play sounds for simulation events
+    pub enabled: bool,
+    /// Volume of every sound, from 0 (silent) to 1
+    pub master_volume: f32,
+    /// Played when an ant picks up food
+    pub pickup: String,
+    /// Played when food is delivered to a base
+    pub delivery: String,
+    /// Looped while the simulation runs, louder as the colony grows
+    pub ambient: String,
+    /// Ant count at which the ambient hum reaches full volume
+    pub ambient_full_population: u32,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            master_volume: 0.5,
+            pickup: "sounds/pickup.ogg".to_string(),
+            delivery: "sounds/delivery.ogg".to_string(),
+            ambient: "sounds/ambient.ogg".to_string(),
+            ambient_full_population: 2000,
+        }
+    }
+}
+
+// Sprite sheet paths are relative to the assets/ folder.
+// The ant walk sheet is a single row of frames with the ant facing right.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpriteSheetConfig {
+    pub ant_walk: String,
+    pub ant_frame_size: (u32, u32),
+    pub ant_frame_count: u32,
+    pub food: String,
+    pub base: String,
+}
+
+impl Default for SpriteSheetConfig {
+    fn default() -> Self {
+        Self {
+            ant_walk: "sprites/ant_walk.png".to_string(),
+            ant_frame_size: (16, 16),
+            ant_frame_count: 4,
+            food: "sprites/food.png".to_string(),
+            base: "sprites/base.png".to_string(),
+        }
+    }
+}
+
+fn one_or_many_locations<'de, D>(deserializer: D) -> Result<Vec<(u32, u32)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One((u32, u32)),
+        Many(Vec<(u32, u32)>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(location) => vec![location],
+        OneOrMany::Many(locations) => locations,
+    })
+}
+
+fn one_or_many_keys<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(key) => vec![key],
+        OneOrMany::Many(keys) => keys,
+    })
+}
+
+// MP4s round the width down to an even number, which must leave at least 2 pixels
+fn recording_width<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let width = Option::<u32>::deserialize(deserializer)?;
+    if let Some(width) = width.filter(|width| *width < 2) {
+        return Err(serde::de::Error::custom(format!(
+            "recording width must be at least 2 pixels, got {}",
+            width
+        )));
+    }
+    Ok(width)
+}
+
+fn default_cursor_spawn_count() -> u32 {
+    10
+}
+
+fn default_rest_duration() -> f32 {
+    2.0
+}
+
+fn default_recruitment_intensity() -> f32 {
+    500.0
+}
+
+fn default_richness_exponent() -> f32 {
+    0.5
+}
+
+fn default_exploration_rate() -> f32 {
+    0.05
+}
+
+fn default_recruits_per_delivery() -> u32 {
+    3
+}
+
+fn default_heavy_food_value() -> u32 {
+    10
+}
+
+fn default_corpse_lifetime() -> f32 {
+    120.0
+}
+
+fn default_necrophoresis_radius() -> f32 {
+    160.0
+}
+
+const CONFIG_PATH: &str = "config.json";
+
+// Written to disk when config.json is missing. JSON has no comments, so "//" keys
+// describe the setting that follows them; unknown keys are ignored when loading.
+const DEFAULT_CONFIG: &str = r#"{
+  "// map_size": "Map width and height in grid cells of 32x32 pixels",
+  "map_size": [100, 75],
+  "// base_locations": "Bottom-left grid cell of each 2x2 base",
+  "base_locations": [[50, 25]],
+  "// food_locations": "Grid cells of food sources, or objects {location, quantity, radius, respawn_secs, quality}",
+  "food_locations": [
+    [25, 25],
+    [25, 35],
+    [75, 25]
+  ],
+  "// obstacles": "Walls as rectangles of grid cells {from: [x, y], to: [x, y]}, corners included",
+  "obstacles": [],
+  "// spawn_rate": "Fastest seconds between eggs laid by each base's queen, slower as stored food runs low; 0 disables laying",
+  "spawn_rate": 0,
+  "// marker_spawn_interval": "Seconds between pheromone markers left by each ant",
+  "marker_spawn_interval": 0.15,
+  "// marker_lifetime": "Seconds before a pheromone marker evaporates",
+  "marker_lifetime": 30.0,
+  "// marker_decay": "How markers fade over their lifetime: none, linear, exponential (half_life_secs) or step (steps)",
+  "marker_decay": { "model": "none" },
+  "// grid_type": "Shape of the pheromone grid cells: square or hex, which keeps trails from following the map axes",
+  "grid_type": "square",
+  "// initial_ant_count": "Ants present when the simulation starts",
+  "initial_ant_count": 1000,
+  "// food_quantity": "Food units in each source unless the source sets its own quantity",
+  "food_quantity": 100,
+  "// richness_exponent": "How strongly a source's remaining food strengthens its trails, 0 to only use each source's quality",
+  "richness_exponent": 0.5,
+  "// rest_duration": "Seconds an ant rests at the base after delivering food",
+  "rest_duration": 2.0,
+  "// recruitment_intensity": "Food marker intensity around the base needed to recruit resting ants",
+  "recruitment_intensity": 500.0,
+  "// exploration_rate": "Probability that a search trip ignores pheromones",
+  "exploration_rate": 0.05,
+  "// recruits_per_delivery": "Ants at the base recruited by each food delivery",
+  "recruits_per_delivery": 3,
+  "// language": "Language of the GUI: en or fr",
+  "language": "en",
+  "// theme": "Colors of the GUI and the map: light, dark or high_contrast",
+  "theme": "light",
+  "// use_sprite_sheets": "Draw with the sprite sheets in assets/ instead of colored rectangles",
+  "use_sprite_sheets": false
+}
+"#;
+
+impl Config {
+    // Load config.json, writing the default config first if the file doesn't exist yet
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = std::path::Path::new(CONFIG_PATH);
+        if !path.exists() {
+            match std::fs::write(path, DEFAULT_CONFIG) {
+                Ok(()) => println!(
+                    "{} not found, wrote a default config to it. Edit it and restart to change the simulation.",
+                    CONFIG_PATH
+                ),
+                Err(e) => eprintln!(
+                    "{} not found and the default config could not be written ({}), running with defaults",
+                    CONFIG_PATH, e
+                ),
+            }
+            return Ok(Self::default());
+        }
+
+        Self::load_from(path)
+    }
+
+    pub fn load_from(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let config_str = std::fs::read_to_string(path)?;
+        let config: Config = serde_json::from_str(&config_str)?;
+        Ok(config)
+    }
+
+    // Colonies in the simulation, falling back to a single colony built from the top-level settings
+    pub fn colony_configs(&self) -> Vec<ColonyConfig> {
+        if self.colonies.is_empty() {
+            vec![ColonyConfig {
+                base_locations: self.base_locations.clone(),
+                initial_ant_count: self.initial_ant_count,
+                soldier_ratio: 0.0,
+            }]
+        } else {
+            self.colonies.clone()
+        }
+    }
+}
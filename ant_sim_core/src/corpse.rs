@@ -0,0 +1,177 @@
+use crate::ant::{Ant, AntState};
+use crate::colony::Colony;
+use crate::config::Config;
+use crate::events::AntKilled;
+use crate::grid::{GridMap, GRID_CELL_SIZE};
+use crate::marker::{place_marker, MarkerType};
+use crate::rng::SimRng;
+use crate::spatial::{Landmarks, SpatialIndex};
+use crate::task::Task;
+use bevy::prelude::*;
+
+// Corpses give off a much weaker signal than trail pheromones
+const CORPSE_MARKER_INTENSITY: f32 = 30.0;
+const PICKUP_THRESHOLD: f32 = 10.0;
+const DROP_THRESHOLD: f32 = 10.0;
+
+#[derive(Component)]
+pub struct Corpse {
+    // Corpses already dropped in the refuse zone are left there to decay
+    pub disposed: bool,
+    pub lifetime: Timer,
+    pub marker_timer: f32,
+}
+
+// Added to an ant while it carries a corpse to the refuse zone
+#[derive(Component)]
+pub struct CarriedCorpse {
+    pub refuse_point: Vec2,
+}
+
+fn corpse_bundle(position: Vec2, disposed: bool, config: &Config) -> (Corpse, TransformBundle) {
+    (
+        Corpse {
+            disposed,
+            lifetime: Timer::from_seconds(config.corpse_lifetime, TimerMode::Once),
+            marker_timer: 0.0,
+        },
+        TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+    )
+}
+
+// Closest point on the map edge, where the refuse zone is
+fn refuse_point(pos: Vec2, config: &Config) -> Vec2 {
+    let map_width_pixels = config.map_size.0 as f32 * GRID_CELL_SIZE;
+    let map_height_pixels = config.map_size.1 as f32 * GRID_CELL_SIZE;
+
+    let candidates = [
+        Vec2::new(0.0, pos.y),
+        Vec2::new(map_width_pixels, pos.y),
+        Vec2::new(pos.x, 0.0),
+        Vec2::new(pos.x, map_height_pixels),
+    ];
+    candidates
+        .into_iter()
+        .min_by(|a, b| {
+            pos.distance_squared(*a)
+                .total_cmp(&pos.distance_squared(*b))
+        })
+        .unwrap_or(pos)
+}
+
+pub fn spawn_corpses(
+    mut commands: Commands,
+    mut killed_events: EventReader<AntKilled>,
+    config: Res<Config>,
+) {
+    for event in killed_events.read() {
+        commands.spawn(corpse_bundle(event.position, false, &config));
+    }
+}
+
+// Corpses keep a mild repellent marker around them until they decay
+pub fn update_corpses(
+    mut commands: Commands,
+    mut corpses: Query<(Entity, &Transform, &mut Corpse)>,
+    mut grid_map: ResMut<GridMap>,
+    time: Res<Time>,
+    config: Res<Config>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, transform, mut corpse) in corpses.iter_mut() {
+        corpse.lifetime.tick(time.delta());
+        if corpse.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        corpse.marker_timer += dt;
+        if corpse.marker_timer >= config.marker_spawn_interval {
+            let grid_cell = config
+                .grid_type
+                .world_to_grid(transform.translation.truncate());
+            place_marker(
+                &mut commands,
+                &mut grid_map,
+                &config,
+                grid_cell,
+                MarkerType::Danger,
+                CORPSE_MARKER_INTENSITY,
+                None,
+            );
+            corpse.marker_timer = 0.0;
+        }
+    }
+}
+
+// Corpse removers that come across a corpse near their own base carry it away
+pub fn pick_up_corpses(
+    mut commands: Commands,
+    mut ants: Query<(&mut Ant, &Colony)>,
+    ant_index: Res<SpatialIndex<Ant>>,
+    corpses: Query<(Entity, &Transform, &Corpse)>,
+    landmarks: Landmarks,
+    config: Res<Config>,
+) {
+    let mut taken = Vec::new();
+
+    for (corpse_entity, corpse_transform, corpse) in corpses.iter() {
+        if corpse.disposed {
+            continue;
+        }
+        let corpse_pos = corpse_transform.translation.truncate();
+
+        for (ant_entity, _) in ant_index.within_radius(corpse_pos, PICKUP_THRESHOLD) {
+            let Ok((mut ant, colony)) = ants.get_mut(ant_entity) else {
+                continue;
+            };
+            if ant.state != AntState::Searching || ant.task != Task::CorpseRemoval {
+                continue;
+            }
+
+            // Only corpses close to the ant's own nest get cleared
+            let Some((base_pos, _)) = landmarks.nearest_base(corpse_pos, Some(*colony)) else {
+                continue;
+            };
+            if base_pos.distance(corpse_pos) > config.necrophoresis_radius {
+                continue;
+            }
+
+            ant.state = AntState::CarryingCorpse;
+            ant.state_timer = 0.0;
+            commands.entity(ant_entity).insert(CarriedCorpse {
+                refuse_point: refuse_point(base_pos, &config),
+            });
+            taken.push(corpse_entity);
+            break;
+        }
+    }
+
+    for corpse_entity in taken {
+        commands.entity(corpse_entity).despawn();
+    }
+}
+
+// Steer carriers to the refuse zone and drop the corpse there
+pub fn carry_corpses(
+    mut commands: Commands,
+    mut carriers: Query<(Entity, &Transform, &mut Ant, &CarriedCorpse)>,
+    config: Res<Config>,
+    mut rng: ResMut<SimRng>,
+) {
+    for (entity, transform, mut ant, carried) in carriers.iter_mut() {
+        let pos = transform.translation.truncate();
+        let to_refuse = carried.refuse_point - pos;
+
+        if to_refuse.length() < DROP_THRESHOLD {
+            commands.spawn(corpse_bundle(pos, true, &config));
+            commands.entity(entity).remove::<CarriedCorpse>();
+            // Head back into the map to forage again
+            ant.velocity = -ant.velocity;
+            ant.start_searching(&config, &mut *rng);
+        } else {
+            ant.velocity = to_refuse.normalize();
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::colony::Colony;
+use bevy::prelude::*;
+
+// Simulation events shared between systems
+
+// Sent when an ant picks up a unit of food at a food source
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FoodPickedUp {
+    pub ant: Entity,
+    pub position: Vec2,
+}
+
+// Sent when an ant delivers food to the base
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FoodDelivered {
+    pub ant: Entity,
+    pub colony: Colony,
+    pub position: Vec2,
+    // Direction of travel when the ant reached the base (pointing into the base)
+    pub arrival_direction: Vec2,
+    // Seconds from leaving the base to this delivery
+    pub trip_secs: f32,
+}
+
+// Sent when an ant is killed by an ant of another colony or by a predator
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AntKilled {
+    pub position: Vec2,
+    pub victim_colony: Colony,
+    // None for predators
+    pub killer_colony: Option<Colony>,
+    // Whether the ant was carrying food, which is dropped where it died
+    pub carried_food: bool,
+}
@@ -0,0 +1,153 @@
+use crate::ant::{Ant, AntState};
+use crate::config::{Config, FoodSourceConfig};
+use crate::events::{AntKilled, FoodPickedUp};
+use crate::grid::{grid_to_world, GRID_CELL_SIZE};
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct FoodSource {
+    // Radius in pixels; ants reach the source anywhere within it
+    pub radius: f32,
+    pub initial_quantity: u32,
+    // Refill timer for sources that respawn after being depleted
+    pub respawn: Option<Timer>,
+    pub quality: f32,
+}
+
+impl FoodSource {
+    // Point of the source closest to pos
+    pub fn nearest_point(&self, center: Vec2, pos: Vec2) -> Vec2 {
+        let offset = pos - center;
+        if offset.length() <= self.radius {
+            pos
+        } else {
+            center + offset.normalize_or_zero() * self.radius
+        }
+    }
+
+    // Trail strength multiplier for food taken while quantity is left, see richness_exponent
+    pub fn richness(&self, quantity: u32, config: &Config) -> f32 {
+        let size = quantity as f32 / config.food_quantity.max(1) as f32;
+        self.quality * size.powf(config.richness_exponent)
+    }
+}
+
+#[derive(Component)]
+pub struct FoodQuantity {
+    pub quantity: u32,
+}
+
+// Spawn a food source centered on its grid cell
+pub fn spawn_food_source(
+    commands: &mut Commands,
+    food_config: &FoodSourceConfig,
+    config: &Config,
+) -> Entity {
+    let food_cell = (food_config.location.0 as i32, food_config.location.1 as i32);
+    let food_world_pos = grid_to_world(food_cell);
+    let quantity = food_config.quantity.unwrap_or(config.food_quantity);
+    // Multi-cell sources cover every cell within their radius
+    let radius = food_config.radius as f32 * GRID_CELL_SIZE;
+    commands
+        .spawn((
+            FoodSource {
+                radius,
+                initial_quantity: quantity,
+                respawn: food_config
+                    .respawn_secs
+                    .map(|secs| Timer::from_seconds(secs, TimerMode::Once)),
+                quality: food_config.quality,
+            },
+            FoodQuantity { quantity },
+            TransformBundle::from_transform(Transform::from_translation(
+                food_world_pos.extend(0.0),
+            )),
+        ))
+        .id()
+}
+
+// Food carried by killed ants is left where they died, as a single unit source others can
+// pick up again
+pub fn drop_carried_food(mut commands: Commands, mut killed_events: EventReader<AntKilled>) {
+    for event in killed_events.read() {
+        if !event.carried_food {
+            continue;
+        }
+        commands.spawn((
+            FoodSource {
+                radius: 0.0,
+                initial_quantity: 1,
+                respawn: None,
+                quality: 1.0,
+            },
+            FoodQuantity { quantity: 1 },
+            TransformBundle::from_transform(Transform::from_translation(
+                event.position.extend(0.0),
+            )),
+        ));
+    }
+}
+
+pub fn check_food_collision(
+    mut commands: Commands,
+    mut ants: Query<(Entity, &Transform, &mut Ant), (With<Ant>, Without<FoodSource>)>,
+    mut food_query: Query<(Entity, &Transform, &FoodSource, &mut FoodQuantity), Without<Ant>>,
+    mut picked_up_events: EventWriter<FoodPickedUp>,
+    config: Res<Config>,
+) {
+    const COLLISION_THRESHOLD: f32 = 10.0;
+
+    for (ant_entity, ant_transform, mut ant) in ants.iter_mut() {
+        if ant.state == AntState::Searching && !ant.has_food && ant.task.collects_food() {
+            for (food_entity, food_transform, food_source, mut food_quantity) in
+                food_query.iter_mut()
+            {
+                let ant_pos = ant_transform.translation.truncate();
+                let food_pos = food_transform.translation.truncate();
+                let distance = ant_pos.distance(food_source.nearest_point(food_pos, ant_pos));
+
+                if distance < COLLISION_THRESHOLD && food_quantity.quantity > 0 {
+                    // Pick up food
+                    ant.has_food = true;
+                    ant.food_richness = food_source.richness(food_quantity.quantity, &config);
+                    ant.state = AntState::Returning;
+                    ant.state_timer = 0.0;
+                    ant.marker_timer = 0.0; // Reset marker timer to start leaving food markers immediately
+                                            // Make ant do a U-turn
+                    ant.velocity = -ant.velocity;
+
+                    // Decrease food quantity
+                    food_quantity.quantity -= 1;
+                    picked_up_events.send(FoodPickedUp {
+                        ant: ant_entity,
+                        position: ant_pos,
+                    });
+
+                    // Despawn food source if quantity reaches 0, unless it respawns
+                    if food_quantity.quantity == 0 && food_source.respawn.is_none() {
+                        commands.entity(food_entity).despawn();
+                    }
+
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// Refill depleted food sources once their respawn delay has passed
+pub fn respawn_food(mut food_query: Query<(&mut FoodSource, &mut FoodQuantity)>, time: Res<Time>) {
+    for (mut food_source, mut food_quantity) in food_query.iter_mut() {
+        if food_quantity.quantity > 0 {
+            continue;
+        }
+        let initial_quantity = food_source.initial_quantity;
+        if let Some(timer) = food_source.respawn.as_mut() {
+            timer.tick(time.delta());
+            if timer.just_finished() {
+                timer.reset();
+                food_quantity.quantity = initial_quantity;
+            }
+        }
+    }
+}
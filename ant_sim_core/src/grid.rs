@@ -0,0 +1,347 @@
+use crate::config::SensorConfig;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const GRID_CELL_SIZE: f32 = 32.0;
+// Hex cells are pointy-topped and GRID_CELL_SIZE wide, so rows are closer than columns
+pub const HEX_SIZE: f32 = GRID_CELL_SIZE / 1.732_050_8; // Center to corner
+pub const HEX_ROW_HEIGHT: f32 = 1.5 * HEX_SIZE;
+
+// Width and height of a grid chunk, in cells
+const CHUNK_SIZE: i32 = 16;
+const CHUNK_AREA: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MarkerType {
+    Base,
+    Food,
+    // Mild repellent left around corpses
+    Danger,
+}
+
+// Shape of the marker grid cells. Config locations are always given in square map cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridType {
+    #[default]
+    Square,
+    // Rows of hexes with odd rows shifted half a cell right ("odd-r" offset coordinates).
+    // Cell (0, 0) is centered on the map origin.
+    Hex,
+}
+
+impl GridType {
+    // Columns and rows of cells needed to cover a map of map_size square map cells
+    pub fn cell_counts(self, map_size: (u32, u32)) -> (u32, u32) {
+        match self {
+            GridType::Square => map_size,
+            GridType::Hex => {
+                let width = map_size.0 as f32 * GRID_CELL_SIZE;
+                let height = map_size.1 as f32 * GRID_CELL_SIZE;
+                (
+                    (width / GRID_CELL_SIZE).ceil() as u32 + 1,
+                    (height / HEX_ROW_HEIGHT).ceil() as u32 + 1,
+                )
+            }
+        }
+    }
+
+    // Cell containing a world position
+    pub fn world_to_grid(self, pos: Vec2) -> (i32, i32) {
+        match self {
+            GridType::Square => (
+                (pos.x / GRID_CELL_SIZE).floor() as i32,
+                (pos.y / GRID_CELL_SIZE).floor() as i32,
+            ),
+            GridType::Hex => {
+                // Fractional axial coordinates, rounded in cube space
+                let q = (pos.x * 1.732_050_8 / 3.0 - pos.y / 3.0) / HEX_SIZE;
+                let r = (pos.y * 2.0 / 3.0) / HEX_SIZE;
+                let s = -q - r;
+                let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+                let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+                if dq > dr && dq > ds {
+                    rq = -rr - rs;
+                } else if dr > ds {
+                    rr = -rq - rs;
+                }
+                let (q, r) = (rq as i32, rr as i32);
+                (q + (r - (r & 1)) / 2, r)
+            }
+        }
+    }
+
+    // World position of the center of a cell
+    pub fn grid_to_world(self, cell: (i32, i32)) -> Vec2 {
+        match self {
+            GridType::Square => Vec2::new(
+                (cell.0 as f32 * GRID_CELL_SIZE) + (GRID_CELL_SIZE / 2.0),
+                (cell.1 as f32 * GRID_CELL_SIZE) + (GRID_CELL_SIZE / 2.0),
+            ),
+            GridType::Hex => Vec2::new(
+                (cell.0 as f32 + 0.5 * (cell.1 & 1) as f32) * GRID_CELL_SIZE,
+                cell.1 as f32 * HEX_ROW_HEIGHT,
+            ),
+        }
+    }
+
+    // Cells sharing an edge with a cell, or a corner too for squares
+    pub fn neighbors(self, cell: (i32, i32)) -> Vec<(i32, i32)> {
+        let offsets: &[(i32, i32)] = match self {
+            GridType::Square => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+            GridType::Hex if cell.1 & 1 == 0 => {
+                &[(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)]
+            }
+            GridType::Hex => &[(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)],
+        };
+        offsets
+            .iter()
+            .map(|(dx, dy)| (cell.0 + dx, cell.1 + dy))
+            .collect()
+    }
+
+    // Columns and rows to look either side of a cell to find every cell center within distance
+    fn reach(self, distance: f32) -> (i32, i32) {
+        match self {
+            GridType::Square => {
+                let cells = (distance / GRID_CELL_SIZE).ceil() as i32;
+                (cells, cells)
+            }
+            // One more each way as odd rows are offset and the ant isn't at the cell center
+            GridType::Hex => (
+                (distance / GRID_CELL_SIZE).ceil() as i32 + 1,
+                (distance / HEX_ROW_HEIGHT).ceil() as i32 + 1,
+            ),
+        }
+    }
+}
+
+// Grid cell data structure
+#[derive(Default, Clone)]
+pub struct GridCellData {
+    pub base_marker: Option<Entity>,
+    pub food_marker: Option<Entity>,
+    pub danger_marker: Option<Entity>,
+}
+
+impl GridCellData {
+    pub fn marker(&self, marker_type: MarkerType) -> Option<Entity> {
+        match marker_type {
+            MarkerType::Base => self.base_marker,
+            MarkerType::Food => self.food_marker,
+            MarkerType::Danger => self.danger_marker,
+        }
+    }
+}
+
+// Fixed-size block of cells stored densely
+struct GridChunk {
+    cells: Box<[GridCellData]>,
+}
+
+impl GridChunk {
+    fn new() -> Self {
+        Self {
+            cells: vec![GridCellData::default(); CHUNK_AREA].into_boxed_slice(),
+        }
+    }
+}
+
+// Grid map resource to track markers per cell.
+// Cells are grouped into chunks held in a flat array covering the map bounds, so a
+// lookup is plain index arithmetic. Chunks are only allocated once a marker is placed
+// in them, so large, mostly empty maps stay cheap. Cells outside the map are ignored.
+#[derive(Resource)]
+pub struct GridMap {
+    chunks: Vec<Option<GridChunk>>,
+    width: i32,
+    height: i32,
+    chunks_wide: i32,
+    grid_type: GridType,
+}
+
+impl GridMap {
+    // map_size is the map size in square map cells, as in Config
+    pub fn new(map_size: (u32, u32), grid_type: GridType) -> Self {
+        let (width, height) = grid_type.cell_counts(map_size);
+        let width = width as i32;
+        let height = height as i32;
+        let chunks_wide = (width + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        let chunks_high = (height + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        let mut chunks = Vec::new();
+        chunks.resize_with((chunks_wide * chunks_high) as usize, || None);
+
+        Self {
+            chunks,
+            width,
+            height,
+            chunks_wide,
+            grid_type,
+        }
+    }
+
+    pub fn grid_type(&self) -> GridType {
+        self.grid_type
+    }
+
+    // Columns and rows of cells
+    pub fn size(&self) -> (u32, u32) {
+        (self.width as u32, self.height as u32)
+    }
+
+    // Index of the chunk holding a cell and of the cell inside that chunk
+    fn chunk_index(&self, cell: (i32, i32)) -> Option<(usize, usize)> {
+        if cell.0 < 0 || cell.1 < 0 || cell.0 >= self.width || cell.1 >= self.height {
+            return None;
+        }
+        let chunk = (cell.1 / CHUNK_SIZE) * self.chunks_wide + cell.0 / CHUNK_SIZE;
+        let local = (cell.1 % CHUNK_SIZE) * CHUNK_SIZE + cell.0 % CHUNK_SIZE;
+        Some((chunk as usize, local as usize))
+    }
+
+    pub fn contains(&self, cell: (i32, i32)) -> bool {
+        self.chunk_index(cell).is_some()
+    }
+
+    pub fn get_cell(&self, cell: (i32, i32)) -> Option<&GridCellData> {
+        let (chunk, index) = self.chunk_index(cell)?;
+        self.chunks[chunk].as_ref().map(|chunk| &chunk.cells[index])
+    }
+
+    pub fn get_cell_mut(&mut self, cell: (i32, i32)) -> Option<&mut GridCellData> {
+        let (chunk, index) = self.chunk_index(cell)?;
+        let chunk = self.chunks[chunk].get_or_insert_with(GridChunk::new);
+        Some(&mut chunk.cells[index])
+    }
+
+    // Iterate over the given cells, skipping those in chunks that were never allocated
+    pub fn iter_cells<'a, I>(
+        &'a self,
+        cells: I,
+    ) -> impl Iterator<Item = ((i32, i32), &'a GridCellData)> + 'a
+    where
+        I: IntoIterator<Item = (i32, i32)>,
+        I::IntoIter: 'a,
+    {
+        cells
+            .into_iter()
+            .filter_map(move |cell| self.get_cell(cell).map(|data| (cell, data)))
+    }
+
+    pub fn set_marker(&mut self, cell: (i32, i32), marker_type: MarkerType, entity: Entity) {
+        if let Some(cell_data) = self.get_cell_mut(cell) {
+            match marker_type {
+                MarkerType::Base => cell_data.base_marker = Some(entity),
+                MarkerType::Food => cell_data.food_marker = Some(entity),
+                MarkerType::Danger => cell_data.danger_marker = Some(entity),
+            }
+        }
+    }
+
+    pub fn remove_marker(&mut self, cell: (i32, i32), marker_type: MarkerType) {
+        let Some((chunk, index)) = self.chunk_index(cell) else {
+            return;
+        };
+        if let Some(chunk) = self.chunks[chunk].as_mut() {
+            let cell_data = &mut chunk.cells[index];
+            match marker_type {
+                MarkerType::Base => cell_data.base_marker = None,
+                MarkerType::Food => cell_data.food_marker = None,
+                MarkerType::Danger => cell_data.danger_marker = None,
+            }
+        }
+    }
+
+    // Cells in allocated chunks, whether or not they hold markers
+    pub fn allocated_cells(&self) -> usize {
+        self.chunks.iter().flatten().count() * CHUNK_AREA
+    }
+
+    // Approximate heap size of the map
+    pub fn memory_bytes(&self) -> usize {
+        self.chunks.len() * std::mem::size_of::<Option<GridChunk>>()
+            + self.allocated_cells() * std::mem::size_of::<GridCellData>()
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.iter_mut().for_each(|chunk| *chunk = None);
+    }
+
+    pub fn get_nearby_cells(&self, pos: Vec2, radius: f32) -> Vec<(i32, i32)> {
+        let center_cell = self.grid_type.world_to_grid(pos);
+        let (reach_x, reach_y) = self.grid_type.reach(radius);
+        let mut cells = Vec::new();
+
+        for dx in -reach_x..=reach_x {
+            for dy in -reach_y..=reach_y {
+                let cell = (center_cell.0 + dx, center_cell.1 + dy);
+                let cell_world = self.grid_type.grid_to_world(cell);
+                if pos.distance(cell_world) <= radius {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+    }
+}
+
+// Grid cells sensed by an ant: those whose center lies within range pixels and within
+// half the field of view either side of its heading. The cell at the tip of the cone is
+// always sensed, so ranges shorter than a cell still see ahead.
+pub fn sensor_cells(
+    pos: Vec2,
+    velocity: Vec2,
+    sensor: &SensorConfig,
+    grid_type: GridType,
+) -> Vec<(i32, i32)> {
+    // Default to facing right if velocity is too small
+    let direction = if velocity.length() > 0.01 {
+        velocity.normalize()
+    } else {
+        Vec2::new(1.0, 0.0)
+    };
+    let min_alignment = (sensor.fov_degrees.to_radians() / 2.0).cos();
+
+    let current_cell = grid_type.world_to_grid(pos);
+    let (reach_x, reach_y) = grid_type.reach(sensor.range);
+    let mut cells = Vec::new();
+    for dx in -reach_x..=reach_x {
+        for dy in -reach_y..=reach_y {
+            let cell = (current_cell.0 + dx, current_cell.1 + dy);
+            let offset = grid_type.grid_to_world(cell) - pos;
+            let distance = offset.length();
+            if distance > 0.0
+                && distance <= sensor.range
+                && offset.dot(direction) >= min_alignment * distance
+            {
+                cells.push(cell);
+            }
+        }
+    }
+
+    let tip_cell = grid_type.world_to_grid(pos + direction * sensor.range);
+    if !cells.contains(&tip_cell) {
+        cells.push(tip_cell);
+    }
+    cells
+}
+
+// Convert world position to square map cell coordinates, as used for locations in Config.
+// Markers live in the cells of the configured grid type instead.
+pub fn world_to_grid(pos: Vec2) -> (i32, i32) {
+    GridType::Square.world_to_grid(pos)
+}
+
+// Convert square map cell coordinates to world position (center of cell)
+pub fn grid_to_world(cell: (i32, i32)) -> Vec2 {
+    GridType::Square.grid_to_world(cell)
+}
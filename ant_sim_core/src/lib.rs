@@ -0,0 +1,27 @@
+// Headless ant simulation: components, resources and systems without any rendering.
+// Frontends add visuals by reacting to the entities spawned here.
+pub mod ant;
+pub mod base;
+pub mod brood;
+pub mod clock;
+pub mod colony;
+pub mod combat;
+pub mod config;
+pub mod corpse;
+pub mod events;
+pub mod food;
+pub mod grid;
+pub mod marker;
+pub mod milestone;
+pub mod nest;
+pub mod obstacle;
+pub mod predator;
+pub mod profiling;
+pub mod rng;
+pub mod simulation;
+pub mod spatial;
+pub mod summary;
+pub mod task;
+pub mod timeline;
+pub mod trail;
+pub mod transport;
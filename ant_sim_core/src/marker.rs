@@ -0,0 +1,163 @@
+use crate::ant::AntState;
+use crate::config::MarkerDecay;
+use crate::grid::GridMap;
+use bevy::prelude::*;
+
+pub use crate::grid::MarkerType;
+
+#[derive(Component)]
+pub struct Marker {
+    pub intensity: f32,
+    pub marker_type: MarkerType,
+    pub grid_cell: (i32, i32), // Grid cell coordinates
+    // Ant that laid the marker, None for markers left by corpses
+    pub owner: Option<Entity>,
+}
+
+#[derive(Component)]
+pub struct MarkerLifetime {
+    pub timer: Timer,
+    // Intensity the marker was laid with, which marker_decay fades from
+    pub initial_intensity: f32,
+}
+
+pub const INITIAL_INTENSITY: f32 = 100.0;
+
+// Spawn a marker at the center of a grid cell, replacing any marker of the same type there
+pub fn place_marker(
+    commands: &mut Commands,
+    grid_map: &mut GridMap,
+    config: &crate::config::Config,
+    grid_cell: (i32, i32),
+    marker_type: MarkerType,
+    intensity: f32,
+    owner: Option<Entity>,
+) {
+    // Cells past the map edge can't hold markers; ants sit on the edge as they wrap around
+    if !grid_map.contains(grid_cell) {
+        return;
+    }
+
+    // If marker exists, despawn it (replace behavior)
+    if let Some(old_entity) = grid_map
+        .get_cell(grid_cell)
+        .and_then(|cell_data| cell_data.marker(marker_type))
+    {
+        commands.entity(old_entity).despawn();
+    }
+
+    // Position marker at center of grid cell
+    let marker_world_pos = config.grid_type.grid_to_world(grid_cell);
+
+    // Spawn new marker
+    let marker_entity = commands
+        .spawn((
+            Marker {
+                intensity,
+                marker_type,
+                grid_cell,
+                owner,
+            },
+            MarkerLifetime {
+                timer: Timer::from_seconds(config.marker_lifetime, TimerMode::Once),
+                initial_intensity: intensity,
+            },
+            Transform::from_translation(marker_world_pos.extend(-0.1)),
+        ))
+        .id();
+
+    // Register marker in grid map
+    grid_map.set_marker(grid_cell, marker_type, marker_entity);
+}
+
+// Intensity of the next marker an ant lays, weaker the longer it has been in its state and
+// the emptier its pheromone reserve. Trails home from food are scaled by the richness of the
+// source it came from.
+pub fn deposit_intensity(ant: &crate::ant::Ant, config: &crate::config::Config) -> f32 {
+    let intensity = (INITIAL_INTENSITY - (ant.state_timer / config.marker_lifetime))
+        * config.pheromone_reserve.fraction(ant.pheromone);
+    if ant.state == AntState::Returning && ant.has_food {
+        intensity * ant.food_richness
+    } else {
+        intensity
+    }
+}
+
+// Spawn markers for ants
+// Depending on the state of the ant, the marker type is different
+pub fn spawn_markers(
+    mut commands: Commands,
+    mut ants: Query<(Entity, &Transform, &mut crate::ant::Ant)>,
+    mut grid_map: ResMut<GridMap>,
+    time: Res<Time>,
+    config: Res<crate::config::Config>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, transform, mut ant) in ants.iter_mut() {
+        // Update marker timer
+        ant.marker_timer += dt;
+        ant.state_timer += dt;
+        ant.trip_timer += dt;
+
+        // Only foraging ants lay trails
+        if matches!(ant.state, AntState::Resting | AntState::CarryingCorpse) {
+            continue;
+        }
+
+        // Spawn marker at intervals, while the ant has pheromone left
+        if ant.marker_timer >= config.marker_spawn_interval
+            && config.pheromone_reserve.can_deposit(ant.pheromone)
+        {
+            // Find nearest grid cell to ant's position
+            let ant_pos = transform.translation.truncate();
+            let grid_cell = config.grid_type.world_to_grid(ant_pos);
+            let marker_type = if ant.state == AntState::Returning {
+                MarkerType::Food
+            } else {
+                MarkerType::Base
+            };
+
+            place_marker(
+                &mut commands,
+                &mut grid_map,
+                &config,
+                grid_cell,
+                marker_type,
+                deposit_intensity(&ant, &config),
+                Some(entity),
+            );
+
+            ant.pheromone -= config.pheromone_reserve.deposit_cost;
+            ant.marker_timer = 0.0;
+        }
+    }
+}
+
+// Age markers, fading their intensity by the configured decay model
+pub fn update_marker_lifetimes(
+    mut commands: Commands,
+    mut markers: Query<(&mut Marker, &mut MarkerLifetime, Entity)>,
+    mut grid_map: ResMut<GridMap>,
+    time: Res<Time>,
+    config: Res<crate::config::Config>,
+) {
+    for (mut marker, mut lifetime, entity) in markers.iter_mut() {
+        lifetime.timer.tick(time.delta());
+
+        // Left untouched without decay, so unchanged markers aren't flagged as changed
+        if config.marker_decay != MarkerDecay::None {
+            let remaining = config
+                .marker_decay
+                .remaining(lifetime.timer.elapsed_secs(), config.marker_lifetime);
+            marker.intensity = lifetime.initial_intensity * remaining;
+        }
+
+        // Remove marker when timer finishes (reaches 0)
+        if lifetime.timer.just_finished() {
+            // Remove from grid map
+            grid_map.remove_marker(marker.grid_cell, marker.marker_type);
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::ant::Ant;
+use crate::clock::SimClock;
+use crate::colony::ColonyStats;
+use crate::config::Config;
+use crate::events::FoodPickedUp;
+use crate::trail::TrailStats;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Milestone {
+    FirstFoodFound,
+    // The trail analysis found trails that stayed stable, see TrailConfig
+    FirstStableTrail,
+    // MilestonesConfig::delivery_target units delivered
+    DeliveryTarget,
+    // Twice as many ants as at the start
+    PopulationDoubled,
+}
+
+// Sent once for each milestone, when the run first reaches it
+#[derive(Event, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MilestoneReached {
+    pub milestone: Milestone,
+    pub sim_secs: f32,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct MilestoneTracker {
+    reached: Vec<Milestone>,
+    initial_population: Option<usize>,
+}
+
+impl MilestoneTracker {
+    pub fn is_reached(&self, milestone: Milestone) -> bool {
+        self.reached.contains(&milestone)
+    }
+}
+
+// Where the run stands, for the milestones to be checked against
+#[derive(SystemParam)]
+pub struct RunProgress<'w, 's> {
+    ants: Query<'w, 's, (), With<Ant>>,
+    colonies: Res<'w, ColonyStats>,
+    trails: Res<'w, TrailStats>,
+}
+
+pub fn track_milestones(
+    mut tracker: ResMut<MilestoneTracker>,
+    mut picked_up_events: EventReader<FoodPickedUp>,
+    progress: RunProgress,
+    clock: Res<SimClock>,
+    config: Res<Config>,
+    mut reached_events: EventWriter<MilestoneReached>,
+) {
+    let population = progress.ants.iter().count();
+    let initial_population = *tracker.initial_population.get_or_insert(population);
+    let delivered: u32 = progress
+        .colonies
+        .colonies
+        .iter()
+        .map(|totals| totals.food_delivered)
+        .sum();
+
+    let reached = [
+        (
+            Milestone::FirstFoodFound,
+            picked_up_events.read().count() > 0,
+        ),
+        (
+            Milestone::FirstStableTrail,
+            progress.trails.first_stable_secs.is_some(),
+        ),
+        (
+            Milestone::DeliveryTarget,
+            delivered >= config.milestones.delivery_target,
+        ),
+        (
+            Milestone::PopulationDoubled,
+            initial_population > 0 && population >= initial_population * 2,
+        ),
+    ];
+    for (milestone, met) in reached {
+        if met && !tracker.is_reached(milestone) {
+            tracker.reached.push(milestone);
+            reached_events.send(MilestoneReached {
+                milestone,
+                sim_secs: clock.elapsed_secs() as f32,
+            });
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::ant::{Ant, AntState};
+use crate::base::Base;
+use crate::colony::Colony;
+use crate::config::{Config, NestConfig};
+use crate::events::FoodDelivered;
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+// Depth of the gallery below the entrance, and of the brood chamber below the gallery
+const GALLERY_DEPTH: f32 = 96.0;
+const BROOD_DEPTH: f32 = 80.0;
+// Distance between neighbouring chambers along the gallery
+const CHAMBER_SPACING: f32 = 96.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChamberKind {
+    FoodStorage,
+    Brood,
+}
+
+#[derive(Debug, Clone)]
+pub struct Chamber {
+    pub kind: ChamberKind,
+    // Center of the chamber relative to the entrance, y pointing up
+    pub position: Vec2,
+    pub stored_food: u32,
+}
+
+// Chambers under a base, reached from the entrance at the base's center through a shaft
+// down to a gallery. Food chambers line the gallery, alternating left and right of the
+// shaft, and the brood chamber lies below it.
+#[derive(Component, Debug, Clone)]
+pub struct Nest {
+    pub chambers: Vec<Chamber>,
+    pub capacity: u32,
+}
+
+impl Nest {
+    pub fn new(config: &NestConfig) -> Self {
+        let mut chambers: Vec<Chamber> = (0..config.food_chambers)
+            .map(|i| {
+                let side = if i % 2 == 0 { -1.0 } else { 1.0 };
+                Chamber {
+                    kind: ChamberKind::FoodStorage,
+                    position: Vec2::new(
+                        side * (i / 2 + 1) as f32 * CHAMBER_SPACING,
+                        -GALLERY_DEPTH,
+                    ),
+                    stored_food: 0,
+                }
+            })
+            .collect();
+        chambers.push(Chamber {
+            kind: ChamberKind::Brood,
+            position: Vec2::new(0.0, -GALLERY_DEPTH - BROOD_DEPTH),
+            stored_food: 0,
+        });
+        Self {
+            chambers,
+            capacity: config.chamber_capacity,
+        }
+    }
+
+    // Where the shaft from the entrance meets the gallery
+    pub fn junction() -> Vec2 {
+        Vec2::new(0.0, -GALLERY_DEPTH)
+    }
+
+    pub fn food_chambers(&self) -> impl Iterator<Item = &Chamber> {
+        self.chambers
+            .iter()
+            .filter(|chamber| chamber.kind == ChamberKind::FoodStorage)
+    }
+
+    pub fn brood_chamber(&self) -> Option<&Chamber> {
+        self.chambers
+            .iter()
+            .find(|chamber| chamber.kind == ChamberKind::Brood)
+    }
+
+    pub fn stored_food(&self) -> u32 {
+        self.food_chambers()
+            .map(|chamber| chamber.stored_food)
+            .sum()
+    }
+
+    // Store one unit of food in the first chamber with room, or the least full one once all
+    // are full. Returns the index of the chamber, None without food chambers.
+    pub fn store_food(&mut self) -> Option<usize> {
+        let capacity = self.capacity;
+        let food_chambers = || {
+            self.chambers
+                .iter()
+                .enumerate()
+                .filter(|(_, chamber)| chamber.kind == ChamberKind::FoodStorage)
+        };
+        let index = food_chambers()
+            .find(|(_, chamber)| chamber.stored_food < capacity)
+            .or_else(|| food_chambers().min_by_key(|(_, chamber)| chamber.stored_food))
+            .map(|(index, _)| index)?;
+        self.chambers[index].stored_food += 1;
+        Some(index)
+    }
+
+    // Take amount of food out of the fullest chambers, or nothing if the nest holds less
+    pub fn take_food(&mut self, amount: u32) -> bool {
+        if self.stored_food() < amount {
+            return false;
+        }
+        for _ in 0..amount {
+            let fullest = self
+                .chambers
+                .iter_mut()
+                .filter(|chamber| chamber.kind == ChamberKind::FoodStorage)
+                .max_by_key(|chamber| chamber.stored_food);
+            if let Some(chamber) = fullest {
+                chamber.stored_food -= 1;
+            }
+        }
+        true
+    }
+}
+
+// Resting ants are down in the nest of the base they rested at. Their Transform stays at the
+// entrance; position is where they are in the nest, relative to the entrance.
+#[derive(Component, Debug, Clone)]
+pub struct Underground {
+    pub nest: Entity,
+    pub position: Vec2,
+    // Points still to walk through, in order
+    pub path: VecDeque<Vec2>,
+}
+
+// Food stored in each food chamber, summed over the bases of each colony
+#[derive(Resource, Default, Debug)]
+pub struct NestStats {
+    pub colonies: Vec<Vec<u32>>,
+}
+
+impl NestStats {
+    pub fn new(colony_count: usize, food_chambers: u32) -> Self {
+        Self {
+            colonies: vec![vec![0; food_chambers as usize]; colony_count],
+        }
+    }
+}
+
+// Nest of colony closest to pos
+fn nearest_nest(
+    nests: &Query<(Entity, &Transform, &Colony, &mut Nest), With<Base>>,
+    pos: Vec2,
+    colony: Colony,
+) -> Option<Entity> {
+    nests
+        .iter()
+        .filter(|(_, _, nest_colony, _)| **nest_colony == colony)
+        .map(|(entity, transform, _, _)| {
+            (
+                entity,
+                transform.translation.truncate().distance_squared(pos),
+            )
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+// Send ants that started resting down into the nearest nest of their colony. Ants that just
+// delivered food carry it to a food chamber first; all of them then rest in the brood chamber.
+pub fn enter_nests(
+    mut commands: Commands,
+    mut delivered_events: EventReader<FoodDelivered>,
+    ants: Query<(Entity, &Transform, &Ant, &Colony), Without<Underground>>,
+    mut nests: Query<(Entity, &Transform, &Colony, &mut Nest), With<Base>>,
+) {
+    // Each delivered unit goes into storage, with one of the ants that brought it
+    let mut carried: HashMap<Entity, usize> = HashMap::new();
+    for event in delivered_events.read() {
+        let Some(nest_entity) = nearest_nest(&nests, event.position, event.colony) else {
+            continue;
+        };
+        if let Ok((_, _, _, mut nest)) = nests.get_mut(nest_entity) {
+            if let Some(chamber) = nest.store_food() {
+                carried.insert(event.ant, chamber);
+            }
+        }
+    }
+
+    for (entity, transform, ant, colony) in ants.iter() {
+        if ant.state != AntState::Resting {
+            continue;
+        }
+        let Some(nest_entity) = nearest_nest(&nests, transform.translation.truncate(), *colony)
+        else {
+            continue;
+        };
+        let Ok((_, _, _, nest)) = nests.get(nest_entity) else {
+            continue;
+        };
+        let mut path = VecDeque::from([Nest::junction()]);
+        if let Some(&chamber) = carried.get(&entity) {
+            path.extend([nest.chambers[chamber].position, Nest::junction()]);
+        }
+        if let Some(brood) = nest.brood_chamber() {
+            path.push_back(brood.position);
+        }
+        // The ant may have been killed this frame
+        commands.entity(entity).try_insert(Underground {
+            nest: nest_entity,
+            position: Vec2::ZERO,
+            path,
+        });
+    }
+}
+
+// Ants leave the nest through the entrance as soon as they stop resting
+pub fn leave_nests(mut commands: Commands, ants: Query<(Entity, &Ant), With<Underground>>) {
+    for (entity, ant) in ants.iter() {
+        if ant.state != AntState::Resting {
+            commands.entity(entity).remove::<Underground>();
+        }
+    }
+}
+
+pub fn walk_underground(mut ants: Query<&mut Underground>, config: Res<Config>, time: Res<Time>) {
+    for mut underground in ants.iter_mut() {
+        let mut step = config.nest.walk_speed * time.delta_seconds();
+        while let Some(&target) = underground.path.front() {
+            let distance = underground.position.distance(target);
+            if distance > step {
+                let heading = (target - underground.position) / distance;
+                underground.position += heading * step;
+                break;
+            }
+            underground.position = target;
+            step -= distance;
+            underground.path.pop_front();
+        }
+    }
+}
+
+pub fn track_nest_stats(
+    changed: Query<(), Changed<Nest>>,
+    nests: Query<(&Colony, &Nest)>,
+    mut stats: ResMut<NestStats>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+    for totals in stats.colonies.iter_mut() {
+        totals.fill(0);
+    }
+    for (colony, nest) in nests.iter() {
+        let index = colony.0 as usize;
+        if index >= stats.colonies.len() {
+            stats.colonies.resize(index + 1, Vec::new());
+        }
+        let totals = &mut stats.colonies[index];
+        for (i, chamber) in nest.food_chambers().enumerate() {
+            if i >= totals.len() {
+                totals.resize(i + 1, 0);
+            }
+            totals[i] += chamber.stored_food;
+        }
+    }
+}
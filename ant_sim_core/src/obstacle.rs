@@ -0,0 +1,254 @@
+use crate::base::Base;
+use crate::colony::Colony;
+use crate::config::{Config, ObstacleRect};
+use crate::grid::{grid_to_world, world_to_grid, GRID_CELL_SIZE};
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// Neighbouring cells and the cost of stepping to them: 2 straight, 3 diagonally (about 2√2)
+const STEPS: [((i32, i32), u32); 8] = [
+    ((1, 0), 2),
+    ((-1, 0), 2),
+    ((0, 1), 2),
+    ((0, -1), 2),
+    ((1, 1), 3),
+    ((1, -1), 3),
+    ((-1, 1), 3),
+    ((-1, -1), 3),
+];
+
+// Map cells ants can't walk or see through, in the square cells of Config::map_size
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Obstacles {
+    width: u32,
+    height: u32,
+    blocked: Vec<bool>,
+    blocked_count: usize,
+}
+
+impl Obstacles {
+    pub fn new(map_size: (u32, u32), rects: &[ObstacleRect]) -> Self {
+        let mut obstacles = Self {
+            width: map_size.0,
+            height: map_size.1,
+            blocked: vec![false; (map_size.0 * map_size.1) as usize],
+            blocked_count: 0,
+        };
+        for rect in rects {
+            obstacles.add(rect);
+        }
+        obstacles
+    }
+
+    // Block every cell of rect that lies on the map
+    pub fn add(&mut self, rect: &ObstacleRect) {
+        let (min_x, max_x) = (rect.from.0.min(rect.to.0), rect.from.0.max(rect.to.0));
+        let (min_y, max_y) = (rect.from.1.min(rect.to.1), rect.from.1.max(rect.to.1));
+        for y in min_y..=max_y.min(self.height.saturating_sub(1)) {
+            for x in min_x..=max_x.min(self.width.saturating_sub(1)) {
+                let index = (y * self.width + x) as usize;
+                if !self.blocked[index] {
+                    self.blocked[index] = true;
+                    self.blocked_count += 1;
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocked_count == 0
+    }
+
+    fn index(&self, cell: (i32, i32)) -> Option<usize> {
+        let (x, y) = cell;
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some((y as u32 * self.width + x as u32) as usize)
+    }
+
+    // Cells off the map are open, as ants wrap around its edges
+    pub fn is_blocked_cell(&self, cell: (i32, i32)) -> bool {
+        self.index(cell).is_some_and(|index| self.blocked[index])
+    }
+
+    pub fn is_blocked(&self, pos: Vec2) -> bool {
+        self.is_blocked_cell(world_to_grid(pos))
+    }
+
+    pub fn blocked_cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let width = self.width as usize;
+        self.blocked
+            .iter()
+            .enumerate()
+            .filter(|(_, blocked)| **blocked)
+            .map(move |(index, _)| ((index % width) as i32, (index / width) as i32))
+    }
+
+    // Whether the straight line between two points crosses no blocked cell, checked every
+    // quarter cell
+    pub fn line_clear(&self, from: Vec2, to: Vec2) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let samples = (from.distance(to) / (GRID_CELL_SIZE / 4.0)).ceil().max(1.0) as u32;
+        (0..=samples).all(|i| !self.is_blocked(from.lerp(to, i as f32 / samples as f32)))
+    }
+
+    // Move from pos by step, stopping along each axis that would enter a blocked cell so ants
+    // slide along walls. Returns the new position and which axes were stopped. Ants caught
+    // inside a wall, as when it is added on top of them, walk out freely.
+    pub fn slide(&self, pos: Vec2, step: Vec2) -> (Vec2, BVec2) {
+        if self.is_empty() || self.is_blocked(pos) || !self.is_blocked(pos + step) {
+            return (pos + step, BVec2::FALSE);
+        }
+        let blocked_x = self.is_blocked(pos + Vec2::new(step.x, 0.0));
+        let blocked_y = self.is_blocked(pos + Vec2::new(0.0, step.y));
+        let mut next = pos;
+        match (blocked_x, blocked_y) {
+            (false, true) => next.x += step.x,
+            (true, false) => next.y += step.y,
+            // Only the diagonal is blocked, at the corner of a wall: stop rather than cut it
+            _ => {}
+        }
+        let stopped = if blocked_x || blocked_y {
+            BVec2::new(blocked_x, blocked_y)
+        } else {
+            BVec2::TRUE
+        };
+        (next, stopped)
+    }
+}
+
+// Path cost from every open cell to the nearest of a set of target cells, going around
+// obstacles. Ants follow it by stepping to the cheapest neighbouring cell.
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    width: u32,
+    // u32::MAX for blocked or unreachable cells
+    costs: Vec<u32>,
+}
+
+impl FlowField {
+    pub fn build(obstacles: &Obstacles, targets: &[(i32, i32)]) -> Self {
+        let mut costs = vec![u32::MAX; obstacles.blocked.len()];
+        let mut queue = BinaryHeap::new();
+        for &target in targets {
+            if let Some(index) = obstacles.index(target) {
+                costs[index] = 0;
+                queue.push(Reverse((0, target)));
+            }
+        }
+
+        // Dijkstra over the 8-connected grid
+        while let Some(Reverse((cost, cell))) = queue.pop() {
+            if obstacles
+                .index(cell)
+                .is_some_and(|index| cost > costs[index])
+            {
+                continue;
+            }
+            for (next, step_cost) in open_steps(obstacles, cell) {
+                let Some(index) = obstacles.index(next) else {
+                    continue;
+                };
+                let next_cost = cost + step_cost;
+                if next_cost < costs[index] {
+                    costs[index] = next_cost;
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        Self {
+            width: obstacles.width,
+            costs,
+        }
+    }
+
+    fn cost(&self, cell: (i32, i32)) -> u32 {
+        let (x, y) = cell;
+        let height = self.costs.len() as i32 / self.width.max(1) as i32;
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= height {
+            return u32::MAX;
+        }
+        self.costs[(y as u32 * self.width + x as u32) as usize]
+    }
+
+    // Heading from pos toward the center of the cheapest neighbouring cell, None on a target
+    // cell or where no target can be reached
+    pub fn direction(&self, obstacles: &Obstacles, pos: Vec2) -> Option<Vec2> {
+        let cell = world_to_grid(pos);
+        let cost = self.cost(cell);
+        if cost == 0 || cost == u32::MAX {
+            return None;
+        }
+        let (next, _) = open_steps(obstacles, cell)
+            .map(|(next, step_cost)| (next, self.cost(next).saturating_add(step_cost)))
+            .filter(|(next, _)| self.cost(*next) < cost)
+            .min_by_key(|(_, total)| *total)?;
+        Some((grid_to_world(next) - pos).normalize_or_zero()).filter(|d| *d != Vec2::ZERO)
+    }
+}
+
+// Steps from cell into open cells. Diagonal steps need both cells beside them open, so paths
+// don't squeeze between the corners of two walls.
+fn open_steps(
+    obstacles: &Obstacles,
+    cell: (i32, i32),
+) -> impl Iterator<Item = ((i32, i32), u32)> + '_ {
+    STEPS.into_iter().filter_map(move |((dx, dy), cost)| {
+        let next = (cell.0 + dx, cell.1 + dy);
+        let open = |cell| obstacles.index(cell).is_some() && !obstacles.is_blocked_cell(cell);
+        let corner_open =
+            dx == 0 || dy == 0 || (open((cell.0 + dx, cell.1)) && open((cell.0, cell.1 + dy)));
+        (open(next) && corner_open).then_some((next, cost))
+    })
+}
+
+// Flow field leading to the bases of each colony. Empty when pathfinding is disabled or the
+// map has no obstacles, as the straight line home is then always open.
+#[derive(Resource, Default)]
+pub struct FlowFields {
+    colonies: HashMap<Colony, FlowField>,
+}
+
+impl FlowFields {
+    pub fn get(&self, colony: Colony) -> Option<&FlowField> {
+        self.colonies.get(&colony)
+    }
+}
+
+// Rebuilt whenever obstacles, bases or the config change, which is rare
+pub fn build_flow_fields(
+    mut fields: ResMut<FlowFields>,
+    obstacles: Res<Obstacles>,
+    bases: Query<(&Transform, &Colony), With<Base>>,
+    changed_bases: Query<(), Changed<Base>>,
+    mut removed_bases: RemovedComponents<Base>,
+    config: Res<Config>,
+) {
+    let bases_removed = removed_bases.read().count() > 0;
+    if !obstacles.is_changed() && !config.is_changed() && changed_bases.is_empty() && !bases_removed
+    {
+        return;
+    }
+
+    fields.colonies.clear();
+    if !config.pathfinding.enabled || obstacles.is_empty() {
+        return;
+    }
+    let mut targets: HashMap<Colony, Vec<(i32, i32)>> = HashMap::new();
+    for (transform, colony) in bases.iter() {
+        targets
+            .entry(*colony)
+            .or_default()
+            .push(world_to_grid(transform.translation.truncate()));
+    }
+    for (colony, cells) in targets {
+        fields
+            .colonies
+            .insert(colony, FlowField::build(&obstacles, &cells));
+    }
+}
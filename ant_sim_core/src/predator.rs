@@ -0,0 +1,178 @@
+use crate::ant::{Ant, Caste};
+use crate::colony::Colony;
+use crate::config::Config;
+use crate::events::AntKilled;
+use crate::grid::{grid_to_world, GridMap, GRID_CELL_SIZE};
+use crate::marker::Marker;
+use crate::nest::Underground;
+use crate::obstacle::Obstacles;
+use crate::rng::SimRng;
+use crate::spatial::SpatialIndex;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Radians per second a wandering predator may turn by
+const WANDER_TURN_RATE: f32 = 2.0;
+
+#[derive(Component, Debug, Clone)]
+pub struct Predator {
+    pub velocity: Vec2,
+    // Seconds still to stay still after a catch
+    pub digesting: f32,
+}
+
+// Spawn a predator on a cell of the config, which like base and food locations is a square
+// map cell whatever the grid type
+pub fn spawn_predator(commands: &mut Commands, rng: &mut impl Rng, location: (u32, u32)) -> Entity {
+    let position = grid_to_world((location.0 as i32, location.1 as i32));
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    commands
+        .spawn((
+            Predator {
+                velocity: Vec2::from_angle(angle),
+                digesting: 0.0,
+            },
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        ))
+        .id()
+}
+
+// Ants above ground near a point, found through the ant index
+#[derive(SystemParam)]
+pub struct Prey<'w, 's> {
+    ant_index: Res<'w, SpatialIndex<Ant>>,
+    ants: Query<'w, 's, (&'static Ant, &'static Colony), Without<Underground>>,
+}
+
+impl Prey<'_, '_> {
+    pub fn near(
+        &self,
+        pos: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2, &Ant, Colony)> + '_ {
+        self.ant_index
+            .within_radius(pos, radius)
+            .filter_map(|(entity, ant_pos)| {
+                let (ant, colony) = self.ants.get(entity).ok()?;
+                Some((entity, ant_pos, ant, *colony))
+            })
+    }
+}
+
+// Head for the strongest food marker in range and wait on it once there if it is strong
+// enough. Without a trail in range predators wander.
+pub fn move_predators(
+    mut predators: Query<(&mut Transform, &mut Predator)>,
+    markers: Query<&Marker>,
+    grid_map: Res<GridMap>,
+    obstacles: Res<Obstacles>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+    config: Res<Config>,
+) {
+    let dt = time.delta_seconds();
+    let settings = &config.predators;
+    let map_size = Vec2::new(config.map_size.0 as f32, config.map_size.1 as f32) * GRID_CELL_SIZE;
+
+    for (mut transform, mut predator) in predators.iter_mut() {
+        if predator.digesting > 0.0 {
+            predator.digesting -= dt;
+            continue;
+        }
+        let pos = transform.translation.truncate();
+
+        let mut strongest: Option<(Vec2, f32)> = None;
+        let cells = grid_map.get_nearby_cells(pos, settings.sense_radius);
+        for (_, cell_data) in grid_map.iter_cells(cells) {
+            let Some(marker) = cell_data
+                .food_marker
+                .and_then(|entity| markers.get(entity).ok())
+            else {
+                continue;
+            };
+            if strongest.is_none_or(|(_, intensity)| marker.intensity > intensity) {
+                strongest = Some((
+                    config.grid_type.grid_to_world(marker.grid_cell),
+                    marker.intensity,
+                ));
+            }
+        }
+
+        match strongest {
+            Some((target, _)) if pos.distance(target) > GRID_CELL_SIZE / 2.0 => {
+                predator.velocity = (target - pos).normalize();
+            }
+            Some((_, intensity)) if intensity >= settings.ambush_intensity => continue,
+            _ => {
+                let turn = rng.gen_range(-1.0..1.0) * WANDER_TURN_RATE * dt;
+                predator.velocity = Vec2::from_angle(turn).rotate(predator.velocity);
+            }
+        }
+
+        let step = predator.velocity * settings.speed * dt;
+        let (mut next, stopped) = obstacles.slide(pos, step);
+        // Turn back from walls and the map edge
+        if stopped.x || next.x < 0.0 || next.x > map_size.x {
+            predator.velocity.x = -predator.velocity.x;
+        }
+        if stopped.y || next.y < 0.0 || next.y > map_size.y {
+            predator.velocity.y = -predator.velocity.y;
+        }
+        next = next.clamp(Vec2::ZERO, map_size);
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+        if predator.velocity.length_squared() > 0.0 {
+            let angle = predator.velocity.y.atan2(predator.velocity.x);
+            transform.rotation = Quat::from_rotation_z(angle);
+        }
+    }
+}
+
+// Predators catch ants that come within reach, then rest while they digest. Soldiers in reach
+// fight back first and may kill the predator. Chances are per second, scaled by frame time.
+pub fn hunt_ants(
+    mut commands: Commands,
+    mut predators: Query<(Entity, &Transform, &mut Predator)>,
+    prey: Prey,
+    config: Res<Config>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+    mut killed_events: EventWriter<AntKilled>,
+) {
+    let dt = time.delta_seconds();
+    let settings = &config.predators;
+    let mut dead = HashSet::new();
+
+    for (entity, transform, mut predator) in predators.iter_mut() {
+        if predator.digesting > 0.0 {
+            continue;
+        }
+        let pos = transform.translation.truncate();
+
+        for (entity_hit, ant_pos, ant, colony) in prey.near(pos, settings.strike_radius) {
+            if dead.contains(&entity_hit) {
+                continue;
+            }
+            if ant.caste == Caste::Soldier
+                && rng.gen::<f32>() < settings.soldier_kills_predator * dt
+            {
+                commands.entity(entity).despawn();
+                break;
+            }
+            if rng.gen::<f32>() < settings.kill_chance * dt {
+                dead.insert(entity_hit);
+                commands.entity(entity_hit).despawn();
+                killed_events.send(AntKilled {
+                    position: ant_pos,
+                    victim_colony: colony,
+                    killer_colony: None,
+                    carried_food: ant.has_food,
+                });
+                predator.digesting = settings.digest_secs;
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics, RegisterDiagnostic};
+use bevy::ecs::system::{Adapt, AdapterSystem};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const MOVE_ANTS: DiagnosticId =
+    DiagnosticId::from_u128(0x7a1e_0f3c_2b4d_4e8a_9c61_d5a0_b3f2_0001);
+pub const FOLLOW_MARKERS: DiagnosticId =
+    DiagnosticId::from_u128(0x7a1e_0f3c_2b4d_4e8a_9c61_d5a0_b3f2_0002);
+pub const SPAWN_MARKERS: DiagnosticId =
+    DiagnosticId::from_u128(0x7a1e_0f3c_2b4d_4e8a_9c61_d5a0_b3f2_0003);
+pub const COLLISIONS: DiagnosticId =
+    DiagnosticId::from_u128(0x7a1e_0f3c_2b4d_4e8a_9c61_d5a0_b3f2_0004);
+
+// Profiled systems in display order, with their diagnostic names
+pub const PROFILED_SYSTEMS: [(DiagnosticId, &str); 4] = [
+    (MOVE_ANTS, "move_ants"),
+    (FOLLOW_MARKERS, "follow_markers"),
+    (SPAWN_MARKERS, "spawn_markers"),
+    (COLLISIONS, "collisions"),
+];
+
+const HISTORY_LENGTH: usize = 120;
+
+// Time spent in each profiled system during the current frame. Shared with the
+// timed systems themselves since they run without access to any resource.
+#[derive(Resource, Clone, Default)]
+pub struct SystemTimings(Arc<Mutex<HashMap<DiagnosticId, Duration>>>);
+
+impl SystemTimings {
+    fn add(&self, id: DiagnosticId, elapsed: Duration) {
+        let mut timings = self.0.lock().unwrap();
+        *timings.entry(id).or_default() += elapsed;
+    }
+
+    fn take(&self, id: DiagnosticId) -> Duration {
+        self.0.lock().unwrap().remove(&id).unwrap_or_default()
+    }
+}
+
+// Adapter measuring how long the wrapped system takes to run
+pub struct Timed {
+    id: DiagnosticId,
+    timings: SystemTimings,
+}
+
+impl<S: System<In = (), Out = ()>> Adapt<S> for Timed {
+    type In = ();
+    type Out = ();
+
+    fn adapt(&mut self, input: (), run_system: impl FnOnce(())) {
+        let start = Instant::now();
+        run_system(input);
+        self.timings.add(self.id, start.elapsed());
+    }
+}
+
+// Wrap a system so its run time counts towards the given diagnostic. Several
+// systems may share a diagnostic, their times are summed per frame.
+pub fn timed<M, S: System<In = (), Out = ()>>(
+    system: impl IntoSystem<(), (), M, System = S>,
+    id: DiagnosticId,
+    timings: &SystemTimings,
+) -> AdapterSystem<Timed, S> {
+    let system = IntoSystem::into_system(system);
+    let name = system.name();
+    AdapterSystem::new(
+        Timed {
+            id,
+            timings: timings.clone(),
+        },
+        system,
+        name,
+    )
+}
+
+// Push this frame's per-system times (in milliseconds) to the diagnostics store
+pub fn record_system_timings(timings: Res<SystemTimings>, mut diagnostics: Diagnostics) {
+    for (id, _) in PROFILED_SYSTEMS {
+        let elapsed = timings.take(id);
+        diagnostics.add_measurement(id, || elapsed.as_secs_f64() * 1000.0);
+    }
+}
+
+// Register the per-system diagnostics and record them at the end of every frame
+pub fn register_system_diagnostics(app: &mut App, timings: SystemTimings) {
+    for (id, name) in PROFILED_SYSTEMS {
+        app.register_diagnostic(Diagnostic::new(id, name, HISTORY_LENGTH).with_suffix("ms"));
+    }
+    app.insert_resource(timings)
+        .add_systems(Last, record_system_timings);
+}
@@ -0,0 +1,221 @@
+use crate::ant::{follow_markers, keep_ants_in_bounds, move_ants, orient_ants, Caste};
+use crate::base::{check_base_collision, recruit_at_base, wake_resting_ants};
+use crate::brood::{develop_brood, lay_eggs, track_brood_stats, Brood, BroodStats, Queen};
+use crate::clock::{advance_sim_clock, SimClock};
+use crate::colony::{track_colony_stats, Colony, ColonyStats, TripDurations};
+use crate::combat::resolve_combat;
+use crate::config::Config;
+use crate::corpse::{carry_corpses, pick_up_corpses, spawn_corpses, update_corpses};
+use crate::events::{AntKilled, FoodDelivered, FoodPickedUp};
+use crate::food::{check_food_collision, drop_carried_food, respawn_food, spawn_food_source};
+use crate::grid::{GridMap, GRID_CELL_SIZE};
+use crate::marker::{spawn_markers, update_marker_lifetimes};
+use crate::milestone::{track_milestones, MilestoneReached, MilestoneTracker};
+use crate::nest::{enter_nests, leave_nests, track_nest_stats, walk_underground, Nest, NestStats};
+use crate::obstacle::{build_flow_fields, FlowFields, Obstacles};
+use crate::predator::{hunt_ants, move_predators, spawn_predator};
+use crate::profiling::{
+    register_system_diagnostics, timed, SystemTimings, COLLISIONS, FOLLOW_MARKERS, MOVE_ANTS,
+    SPAWN_MARKERS,
+};
+use crate::rng::SimRng;
+use crate::spatial::SpatialIndexPlugin;
+use crate::summary::{
+    check_end_conditions, handle_simulation_end, track_run_stats, RunStats, SimulationEnded,
+};
+use crate::task::{assign_tasks, steer_nest_tasks, update_task_stimuli, TaskStimuli};
+use crate::timeline::{run_timeline, ConfiguredFood, Timeline};
+use crate::trail::{detect_trails, TrailStats};
+use crate::transport::{
+    carry_heavy_food, hold_heavy_food, latch_onto_heavy_food, release_idle_carriers,
+    spawn_heavy_food,
+};
+use bevy::prelude::*;
+
+pub fn setup_simulation(mut commands: Commands, config: Res<Config>) {
+    let mut rng = SimRng::new(config.seed);
+
+    // Spawn bases (2x2 grid cells = 64x64 pixels) for every colony
+    // base_locations in config are the grid cell coordinates of each base's bottom-left corner
+    let colony_configs = config.colony_configs();
+    let mut colony_base_centers = Vec::new();
+    for (colony_id, colony_config) in colony_configs.iter().enumerate() {
+        let colony = Colony(colony_id as u32);
+        let mut base_centers = Vec::new();
+        for (base_cell_x, base_cell_y) in &colony_config.base_locations {
+            // Calculate bottom-left corner of the cell in world coordinates
+            // Convert grid coordinates to world coordinates by multiplying by GRID_CELL_SIZE
+            let base_bottom_left_world = Vec2::new(
+                *base_cell_x as f32 * GRID_CELL_SIZE,
+                *base_cell_y as f32 * GRID_CELL_SIZE,
+            );
+            // Center of 2x2 grid is at bottom-left + 1 cell in both directions
+            let base_center = base_bottom_left_world + Vec2::new(GRID_CELL_SIZE, GRID_CELL_SIZE);
+            base_centers.push(base_center);
+
+            commands.spawn((
+                crate::base::Base,
+                Nest::new(&config.nest),
+                Queen::default(),
+                Brood::default(),
+                colony,
+                TransformBundle::from_transform(Transform::from_translation(
+                    base_center.extend(0.0),
+                )),
+            ));
+        }
+        colony_base_centers.push(base_centers);
+    }
+
+    // Spawn food sources
+    for (index, food_config) in config.food_locations.iter().enumerate() {
+        let food = spawn_food_source(&mut commands, food_config, &config);
+        commands.entity(food).insert(ConfiguredFood(index));
+    }
+    for item_config in &config.transport.items {
+        spawn_heavy_food(&mut commands, item_config);
+    }
+
+    // Spawn each colony's initial ants, shared evenly between its bases
+    for (colony_id, colony_config) in colony_configs.iter().enumerate() {
+        let base_centers = &colony_base_centers[colony_id];
+        if base_centers.is_empty() {
+            continue;
+        }
+        for i in 0..colony_config.initial_ant_count as usize {
+            let base_spawn_pos = base_centers[i % base_centers.len()];
+            let caste = Caste::roll(colony_config.soldier_ratio, &mut rng);
+            crate::ant::spawn_ant(
+                &mut commands,
+                &config,
+                &mut rng,
+                Colony(colony_id as u32),
+                caste,
+                base_spawn_pos.extend(0.0),
+            );
+        }
+    }
+
+    for location in &config.predators.locations {
+        spawn_predator(&mut commands, &mut rng, *location);
+    }
+
+    // Initialize per-colony totals
+    commands.insert_resource(ColonyStats::new(colony_configs.len()));
+    commands.insert_resource(BroodStats::new(colony_configs.len()));
+    commands.insert_resource(NestStats::new(
+        colony_configs.len(),
+        config.nest.food_chambers,
+    ));
+
+    // Initialize grid map
+    commands.insert_resource(GridMap::new(config.map_size, config.grid_type));
+    commands.insert_resource(Obstacles::new(config.map_size, &config.obstacles));
+    commands.insert_resource(Timeline::new(&config.timeline));
+
+    commands.insert_resource(rng);
+}
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        // The hot systems are timed individually so spikes can be traced to them
+        let timings = SystemTimings::default();
+        register_system_diagnostics(app, timings.clone());
+        // Frontends may have added the indexes already
+        if !app.is_plugin_added::<SpatialIndexPlugin>() {
+            app.add_plugins(SpatialIndexPlugin);
+        }
+
+        app.add_event::<FoodDelivered>()
+            .add_event::<FoodPickedUp>()
+            .add_event::<AntKilled>()
+            .add_event::<SimulationEnded>()
+            .add_event::<MilestoneReached>()
+            .init_resource::<RunStats>()
+            .init_resource::<SimClock>()
+            .init_resource::<TaskStimuli>()
+            .init_resource::<TripDurations>()
+            .init_resource::<FlowFields>()
+            .init_resource::<MilestoneTracker>()
+            .init_resource::<TrailStats>()
+            .add_systems(Startup, setup_simulation)
+            .add_systems(PreUpdate, advance_sim_clock)
+            // Systems run one after another in this order. Most share SimRng or the ants, and
+            // left unordered they would run in an order that changes from process to process,
+            // so runs with the same seed would play out differently.
+            .add_systems(
+                Update,
+                (
+                    (
+                        // Walls added by the timeline reroute ants on the same frame
+                        run_timeline,
+                        build_flow_fields,
+                        update_task_stimuli,
+                        assign_tasks,
+                        timed(follow_markers, FOLLOW_MARKERS, &timings),
+                        latch_onto_heavy_food,
+                        steer_nest_tasks,
+                        carry_corpses,
+                        release_idle_carriers,
+                    )
+                        .chain(),
+                    (
+                        timed(move_ants, MOVE_ANTS, &timings),
+                        // Wrap after moving so ants are always inside the map between frames
+                        keep_ants_in_bounds,
+                        orient_ants,
+                        carry_heavy_food,
+                        // Carriers are pulled back onto their item after moving like any ant
+                        hold_heavy_food,
+                        move_predators,
+                    )
+                        .chain(),
+                    (
+                        timed(spawn_markers, SPAWN_MARKERS, &timings),
+                        update_marker_lifetimes,
+                        timed(check_food_collision, COLLISIONS, &timings),
+                        respawn_food,
+                        timed(check_base_collision, COLLISIONS, &timings),
+                        recruit_at_base,
+                        wake_resting_ants,
+                        resolve_combat,
+                        // Kills reach the colony totals and leave corpses on the same frame
+                        hunt_ants,
+                        spawn_corpses,
+                        drop_carried_food,
+                        update_corpses,
+                        pick_up_corpses,
+                    )
+                        .chain(),
+                    (
+                        leave_nests,
+                        // After every system that may put an ant to rest
+                        enter_nests,
+                        walk_underground,
+                        lay_eggs,
+                        develop_brood,
+                    )
+                        .chain(),
+                    (
+                        track_colony_stats,
+                        track_nest_stats,
+                        track_brood_stats,
+                        detect_trails,
+                        track_milestones,
+                    )
+                        .chain(),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    track_run_stats,
+                    check_end_conditions.after(track_run_stats),
+                    handle_simulation_end.after(check_end_conditions),
+                ),
+            );
+    }
+}
@@ -0,0 +1,255 @@
+use crate::ant::Ant;
+use crate::base::Base;
+use crate::colony::Colony;
+use crate::food::{FoodQuantity, FoodSource};
+use crate::grid::GRID_CELL_SIZE;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::StableHashMap;
+use std::marker::PhantomData;
+
+// Positions of every entity with component T, bucketed by square cells of GRID_CELL_SIZE
+// so nearby entities are found without scanning them all. Rebuilt at the start of each
+// frame: entities moved, spawned or despawned during the frame show up on the next one.
+// Buckets are hashed with fixed keys so searches break ties the same way on every run.
+#[derive(Resource)]
+pub struct SpatialIndex<T> {
+    buckets: StableHashMap<(i32, i32), Vec<(Entity, Vec2)>>,
+    // Range of occupied buckets, which bounds the nearest neighbour search
+    min_bucket: (i32, i32),
+    max_bucket: (i32, i32),
+    len: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for SpatialIndex<T> {
+    fn default() -> Self {
+        Self {
+            buckets: StableHashMap::default(),
+            min_bucket: (i32::MAX, i32::MAX),
+            max_bucket: (i32::MIN, i32::MIN),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+fn bucket(point: Vec2) -> (i32, i32) {
+    (
+        (point.x / GRID_CELL_SIZE).floor() as i32,
+        (point.y / GRID_CELL_SIZE).floor() as i32,
+    )
+}
+
+impl<T> SpatialIndex<T> {
+    pub fn clear(&mut self) {
+        // Keep the buckets' allocations for the next rebuild
+        for entries in self.buckets.values_mut() {
+            entries.clear();
+        }
+        self.min_bucket = (i32::MAX, i32::MAX);
+        self.max_bucket = (i32::MIN, i32::MIN);
+        self.len = 0;
+    }
+
+    pub fn insert(&mut self, entity: Entity, point: Vec2) {
+        let key = bucket(point);
+        self.buckets.entry(key).or_default().push((entity, point));
+        self.min_bucket = (self.min_bucket.0.min(key.0), self.min_bucket.1.min(key.1));
+        self.max_bucket = (self.max_bucket.0.max(key.0), self.max_bucket.1.max(key.1));
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn entries_in(
+        &self,
+        min: (i32, i32),
+        max: (i32, i32),
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = (min.0.max(self.min_bucket.0), min.1.max(self.min_bucket.1));
+        let max = (max.0.min(self.max_bucket.0), max.1.min(self.max_bucket.1));
+        (min.0..=max.0)
+            .flat_map(move |x| (min.1..=max.1).map(move |y| (x, y)))
+            .filter_map(|key| self.buckets.get(&key))
+            .flatten()
+            .copied()
+    }
+
+    // Entities within radius of point, in no particular order
+    pub fn within_radius(
+        &self,
+        point: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = bucket(point - Vec2::splat(radius));
+        let max = bucket(point + Vec2::splat(radius));
+        self.entries_in(min, max)
+            .filter(move |(_, pos)| pos.distance_squared(point) <= radius * radius)
+    }
+
+    // Entities inside rect, edges included
+    pub fn within_rect(&self, rect: Rect) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        self.entries_in(bucket(rect.min), bucket(rect.max))
+            .filter(move |(_, pos)| rect.contains(*pos))
+    }
+
+    // Closest entity to point that passes filter. Searches rings of buckets outward and
+    // stops once no unsearched bucket can hold anything closer, or falls back to checking
+    // every entity once that would take fewer lookups, as for a handful of far-off bases.
+    pub fn nearest(&self, point: Vec2, filter: impl Fn(Entity) -> bool) -> Option<(Entity, Vec2)> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut best: Option<(Entity, Vec2, f32)> = None;
+        let consider = |best: &mut Option<(Entity, Vec2, f32)>, entity: Entity, pos: Vec2| {
+            let distance = pos.distance_squared(point);
+            let closer = best.is_none_or(|(_, _, best_distance)| distance < best_distance);
+            if closer && filter(entity) {
+                *best = Some((entity, pos, distance));
+            }
+        };
+
+        let center = bucket(point);
+        let mut lookups = 0;
+        for ring in 0.. {
+            // Everything in this ring or beyond is at least this far away
+            let ring_distance = (ring - 1).max(0) as f32 * GRID_CELL_SIZE;
+            if best
+                .is_some_and(|(_, _, best_distance)| ring_distance * ring_distance > best_distance)
+            {
+                break;
+            }
+            let ring_keys = ring_buckets(center, ring);
+            lookups += ring_keys.len();
+            if lookups > self.len {
+                best = None;
+                for &(entity, pos) in self.buckets.values().flatten() {
+                    consider(&mut best, entity, pos);
+                }
+                break;
+            }
+            for key in ring_keys {
+                for &(entity, pos) in self.buckets.get(&key).into_iter().flatten() {
+                    consider(&mut best, entity, pos);
+                }
+            }
+            // Every occupied bucket has been searched
+            if center.0 - ring <= self.min_bucket.0
+                && center.1 - ring <= self.min_bucket.1
+                && center.0 + ring >= self.max_bucket.0
+                && center.1 + ring >= self.max_bucket.1
+            {
+                break;
+            }
+        }
+        best.map(|(entity, pos, _)| (entity, pos))
+    }
+}
+
+// Buckets at exactly ring steps from center, going around the square
+fn ring_buckets(center: (i32, i32), ring: i32) -> Vec<(i32, i32)> {
+    if ring == 0 {
+        return vec![center];
+    }
+    let mut keys = Vec::with_capacity(8 * ring as usize);
+    for dx in -ring..=ring {
+        keys.push((center.0 + dx, center.1 - ring));
+        keys.push((center.0 + dx, center.1 + ring));
+    }
+    for dy in (1 - ring)..ring {
+        keys.push((center.0 - ring, center.1 + dy));
+        keys.push((center.0 + ring, center.1 + dy));
+    }
+    keys
+}
+
+pub fn index_positions<T: Component>(
+    mut index: ResMut<SpatialIndex<T>>,
+    entities: Query<(Entity, &Transform), With<T>>,
+) {
+    index.clear();
+    for (entity, transform) in entities.iter() {
+        index.insert(entity, transform.translation.truncate());
+    }
+}
+
+// Bases and food sources, found through their spatial indexes
+#[derive(SystemParam)]
+pub struct Landmarks<'w, 's> {
+    base_index: Res<'w, SpatialIndex<Base>>,
+    bases: Query<'w, 's, &'static Colony, With<Base>>,
+    food_index: Res<'w, SpatialIndex<FoodSource>>,
+    food: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static FoodSource,
+            &'static FoodQuantity,
+        ),
+        Without<Ant>,
+    >,
+}
+
+impl Landmarks<'_, '_> {
+    // Position and colony of the closest base, only looking at one colony's bases if given
+    pub fn nearest_base(&self, pos: Vec2, colony: Option<Colony>) -> Option<(Vec2, Colony)> {
+        let (entity, base_pos) = self.base_index.nearest(pos, |entity| {
+            self.bases
+                .get(entity)
+                .is_ok_and(|base_colony| colony.is_none_or(|colony| *base_colony == colony))
+        })?;
+        let base_colony = self.bases.get(entity).ok()?;
+        Some((base_pos, *base_colony))
+    }
+
+    // Food sources are indexed by their centers, so lookups reach this much further
+    pub fn largest_food_radius(&self) -> f32 {
+        self.food
+            .iter()
+            .map(|(_, source, _)| source.radius)
+            .fold(0.0, f32::max)
+    }
+
+    // Center and shape of the sources with food left whose edge may be within range of pos.
+    // largest_radius comes from largest_food_radius, looked up once for many calls.
+    pub fn food_near(
+        &self,
+        pos: Vec2,
+        range: f32,
+        largest_radius: f32,
+    ) -> impl Iterator<Item = (Vec2, &FoodSource)> + '_ {
+        self.food_index
+            .within_radius(pos, range + largest_radius)
+            .filter_map(|(entity, _)| self.food.get(entity).ok())
+            .filter(|(_, _, quantity)| quantity.quantity > 0)
+            .map(|(transform, source, _)| (transform.translation.truncate(), source))
+    }
+}
+
+// Spatial indexes of ants, bases and food sources. Added by SimulationPlugin, and by
+// frontends that query entities without running the simulation.
+pub struct SpatialIndexPlugin;
+
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex<Ant>>()
+            .init_resource::<SpatialIndex<Base>>()
+            .init_resource::<SpatialIndex<FoodSource>>()
+            .add_systems(
+                PreUpdate,
+                (
+                    index_positions::<Ant>,
+                    index_positions::<Base>,
+                    index_positions::<FoodSource>,
+                ),
+            );
+    }
+}
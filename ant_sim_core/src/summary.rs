@@ -0,0 +1,196 @@
+use crate::ant::Ant;
+use crate::clock::SimClock;
+use crate::config::{Config, EndConditionsConfig};
+use crate::events::FoodDelivered;
+use crate::food::{FoodQuantity, FoodSource};
+use crate::milestone::MilestoneReached;
+use crate::rng::SimRng;
+use crate::trail::TrailStats;
+use crate::transport::HeavyFood;
+use bevy::app::AppExit;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndReason {
+    AllFoodDelivered,
+    MaxSimTime,
+    Extinction,
+}
+
+// Sent once when an end condition is met, after the summary is written
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SimulationEnded {
+    pub reason: EndReason,
+}
+
+// Figures gathered over the whole run for the summary report
+#[derive(Resource, Default)]
+pub struct RunStats {
+    food_delivered: u32,
+    first_delivery_secs: Option<f32>,
+    first_stable_trail_secs: Option<f32>,
+    peak_ant_count: usize,
+    milestones: Vec<MilestoneReached>,
+    // Wall clock time between frames, which Time<Real> doesn't give when time is stepped manually
+    last_frame: Option<Instant>,
+    frame_time_sum: Duration,
+    frames: u32,
+    ended: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub end_reason: EndReason,
+    pub seed: u64,
+    pub sim_secs: f32,
+    pub total_food_delivered: u32,
+    /// Simulated seconds until the first food reached a base, None if none did
+    #[serde(alias = "time_to_first_trail_secs")]
+    pub time_to_first_delivery_secs: Option<f32>,
+    /// Simulated seconds until the trail analysis first found stable trails, None if it didn't
+    #[serde(default)]
+    pub time_to_stable_trail_secs: Option<f32>,
+    pub peak_ant_count: usize,
+    pub average_frame_time_ms: f64,
+    /// Milestones in the order they were reached, with the simulated seconds they were reached at
+    #[serde(default)]
+    pub milestones: Vec<MilestoneReached>,
+}
+
+// Food not yet brought in, in sources or heavy items
+#[derive(SystemParam)]
+pub struct FoodOnMap<'w, 's> {
+    sources: Query<'w, 's, (&'static FoodSource, &'static FoodQuantity)>,
+    heavy_items: Query<'w, 's, (), With<HeavyFood>>,
+}
+
+impl FoodOnMap<'_, '_> {
+    pub fn any_left(&self) -> bool {
+        // Sources that refill never run out for good
+        self.sources
+            .iter()
+            .any(|(source, quantity)| quantity.quantity > 0 || source.respawn.is_some())
+            || !self.heavy_items.is_empty()
+    }
+}
+
+// First enabled end condition that is met, checked in declaration order
+fn end_reason(
+    conditions: &EndConditionsConfig,
+    sim_secs: f32,
+    ant_count: usize,
+    food_left: bool,
+) -> Option<EndReason> {
+    if conditions.all_food_delivered && !food_left {
+        Some(EndReason::AllFoodDelivered)
+    } else if conditions
+        .max_sim_secs
+        .is_some_and(|max_secs| sim_secs >= max_secs)
+    {
+        Some(EndReason::MaxSimTime)
+    } else if conditions.extinction && ant_count == 0 {
+        Some(EndReason::Extinction)
+    } else {
+        None
+    }
+}
+
+pub fn track_run_stats(
+    mut stats: ResMut<RunStats>,
+    mut delivered_events: EventReader<FoodDelivered>,
+    mut milestone_events: EventReader<MilestoneReached>,
+    trails: Res<TrailStats>,
+    ants: Query<(), With<Ant>>,
+    clock: Res<SimClock>,
+) {
+    let delivered = delivered_events.read().count() as u32;
+    if delivered > 0 && stats.first_delivery_secs.is_none() {
+        stats.first_delivery_secs = Some(clock.elapsed_secs() as f32);
+    }
+    stats.food_delivered += delivered;
+    stats.milestones.extend(milestone_events.read());
+    stats.first_stable_trail_secs = trails.first_stable_secs;
+    stats.peak_ant_count = stats.peak_ant_count.max(ants.iter().count());
+    let now = Instant::now();
+    if let Some(last_frame) = stats.last_frame {
+        stats.frame_time_sum += now - last_frame;
+        stats.frames += 1;
+    }
+    stats.last_frame = Some(now);
+}
+
+pub fn check_end_conditions(
+    mut stats: ResMut<RunStats>,
+    config: Res<Config>,
+    clock: Res<SimClock>,
+    ants: Query<&Ant>,
+    food: FoodOnMap,
+    rng: Res<SimRng>,
+    mut ended_events: EventWriter<SimulationEnded>,
+) {
+    if stats.ended {
+        return;
+    }
+
+    let food_left = food.any_left() || ants.iter().any(|ant| ant.has_food);
+    let sim_secs = clock.elapsed_secs() as f32;
+    let Some(reason) = end_reason(
+        &config.end_conditions,
+        sim_secs,
+        ants.iter().count(),
+        food_left,
+    ) else {
+        return;
+    };
+    stats.ended = true;
+
+    let summary = RunSummary {
+        end_reason: reason,
+        seed: rng.seed(),
+        sim_secs,
+        total_food_delivered: stats.food_delivered,
+        time_to_first_delivery_secs: stats.first_delivery_secs,
+        time_to_stable_trail_secs: stats.first_stable_trail_secs,
+        peak_ant_count: stats.peak_ant_count,
+        average_frame_time_ms: if stats.frames > 0 {
+            (stats.frame_time_sum / stats.frames).as_secs_f64() * 1000.0
+        } else {
+            0.0
+        },
+        milestones: stats.milestones.clone(),
+    };
+    let path = &config.end_conditions.summary_path;
+    match serde_json::to_string_pretty(&summary) {
+        Ok(json) => match std::fs::write(path, json) {
+            Ok(()) => println!(
+                "Simulation ended ({:?}), summary written to {}",
+                reason, path
+            ),
+            Err(e) => eprintln!("Error writing summary to {}: {}", path, e),
+        },
+        Err(e) => eprintln!("Error serializing summary: {}", e),
+    }
+
+    ended_events.send(SimulationEnded { reason });
+}
+
+// Exit, or freeze the simulation clock so the final state stays on screen
+pub fn handle_simulation_end(
+    mut ended_events: EventReader<SimulationEnded>,
+    config: Res<Config>,
+    mut time: ResMut<Time<Virtual>>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if ended_events.read().next().is_none() {
+        return;
+    }
+    if config.end_conditions.exit {
+        exit_events.send(AppExit);
+    } else {
+        time.pause();
+    }
+}
@@ -0,0 +1,257 @@
+use crate::ant::{Ant, AntState, Caste};
+use crate::base::Base;
+use crate::clock::SimClock;
+use crate::colony::Colony;
+use crate::config::{Config, TaskThresholds};
+use crate::corpse::Corpse;
+use crate::events::FoodDelivered;
+use crate::rng::SimRng;
+use crate::spatial::{Landmarks, SpatialIndex};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// Jobs a searching ant can be doing. Ants take tasks up with the response-threshold model:
+// the stronger a colony's stimulus for a task is compared to an ant's threshold for it,
+// the likelier the ant is to switch to it when reconsidering.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Task {
+    // Bring food home, following food trails
+    #[default]
+    Forage,
+    // Patrol around the nest, where intruders are fought
+    Guard,
+    // Look for corpses near the nest and carry them to the refuse zone
+    CorpseRemoval,
+    // Search for new food without following trails
+    Scout,
+}
+
+impl Task {
+    pub const ALL: [Task; 4] = [Task::Forage, Task::Guard, Task::CorpseRemoval, Task::Scout];
+
+    // Task ants of a caste start with, before any stimulus is felt
+    pub fn initial(caste: Caste) -> Self {
+        match caste {
+            Caste::Worker => Task::Forage,
+            Caste::Soldier => Task::Guard,
+        }
+    }
+
+    // Only foragers and scouts pick up food
+    pub fn collects_food(self) -> bool {
+        matches!(self, Task::Forage | Task::Scout)
+    }
+}
+
+impl TaskThresholds {
+    pub fn threshold(&self, task: Task) -> Option<f32> {
+        match task {
+            Task::Forage => self.forage,
+            Task::Guard => self.guard,
+            Task::CorpseRemoval => self.corpse_removal,
+            Task::Scout => self.scout,
+        }
+    }
+}
+
+// Probability of responding to a stimulus: s² / (s² + θ²). Half of the ants with threshold θ
+// respond to a stimulus of θ; a missing threshold means the task is never taken up.
+pub fn response(stimulus: f32, threshold: Option<f32>) -> f32 {
+    let Some(threshold) = threshold else {
+        return 0.0;
+    };
+    let stimulus_squared = stimulus.max(0.0).powi(2);
+    if stimulus_squared == 0.0 {
+        return 0.0;
+    }
+    stimulus_squared / (stimulus_squared + threshold.powi(2))
+}
+
+// Current stimulus of every task, per colony
+#[derive(Resource, Default, Debug)]
+pub struct TaskStimuli {
+    colonies: HashMap<Colony, [f32; 4]>,
+}
+
+impl TaskStimuli {
+    pub fn get(&self, colony: Colony, task: Task) -> f32 {
+        self.colonies
+            .get(&colony)
+            .map_or(0.0, |stimuli| stimuli[task as usize])
+    }
+}
+
+// What goes on around the nests, for the stimuli of nest tasks
+#[derive(SystemParam)]
+pub struct NestSurroundings<'w, 's> {
+    ant_index: Res<'w, SpatialIndex<Ant>>,
+    ant_colonies: Query<'w, 's, &'static Colony, With<Ant>>,
+    bases: Query<'w, 's, (&'static Transform, &'static Colony), With<Base>>,
+    corpses: Query<'w, 's, (&'static Transform, &'static Corpse)>,
+}
+
+// Stimuli felt by each colony:
+// - forage: constant, a colony always needs food
+// - guard: ants of other colonies within guard_radius of the colony's bases
+// - corpse_removal: corpses left within necrophoresis_radius of the colony's bases
+// - scout: minutes since the colony last had food delivered, rising as trails dry up
+pub fn update_task_stimuli(
+    nests: NestSurroundings,
+    mut delivered_events: EventReader<FoodDelivered>,
+    mut last_delivery_secs: Local<HashMap<Colony, f64>>,
+    clock: Res<SimClock>,
+    config: Res<Config>,
+    mut stimuli: ResMut<TaskStimuli>,
+) {
+    for event in delivered_events.read() {
+        last_delivery_secs.insert(event.colony, clock.elapsed_secs());
+    }
+
+    let bases: Vec<(Vec2, Colony)> = nests
+        .bases
+        .iter()
+        .map(|(transform, colony)| (transform.translation.truncate(), *colony))
+        .collect();
+    let near_base = |pos: Vec2, colony: Colony, radius: f32| {
+        bases.iter().any(|(base_pos, base_colony)| {
+            *base_colony == colony && base_pos.distance(pos) <= radius
+        })
+    };
+
+    stimuli.colonies.clear();
+    for &(_, colony) in &bases {
+        if stimuli.colonies.contains_key(&colony) {
+            continue;
+        }
+        // Bases of a colony may be close together, so an intruder is only counted once
+        let intruders: HashSet<Entity> = bases
+            .iter()
+            .filter(|(_, base_colony)| *base_colony == colony)
+            .flat_map(|(base_pos, _)| {
+                nests
+                    .ant_index
+                    .within_radius(*base_pos, config.tasks.guard_radius)
+            })
+            .filter(|(entity, _)| {
+                nests
+                    .ant_colonies
+                    .get(*entity)
+                    .is_ok_and(|ant_colony| *ant_colony != colony)
+            })
+            .map(|(entity, _)| entity)
+            .collect();
+        let corpses = nests
+            .corpses
+            .iter()
+            .filter(|(transform, corpse)| {
+                !corpse.disposed
+                    && near_base(
+                        transform.translation.truncate(),
+                        colony,
+                        config.necrophoresis_radius,
+                    )
+            })
+            .count();
+        let since_delivery_secs =
+            clock.elapsed_secs() - last_delivery_secs.get(&colony).copied().unwrap_or(0.0);
+
+        let mut colony_stimuli = [0.0; 4];
+        colony_stimuli[Task::Forage as usize] = 1.0;
+        colony_stimuli[Task::Guard as usize] = intruders.len() as f32;
+        colony_stimuli[Task::CorpseRemoval as usize] = corpses as f32;
+        colony_stimuli[Task::Scout as usize] = (since_delivery_secs / 60.0) as f32;
+        stimuli.colonies.insert(colony, colony_stimuli);
+    }
+}
+
+// Searching ants reconsider their task every reassess_secs on average, picking a task with
+// a probability proportional to their response to it. Ants responding to nothing carry on.
+pub fn assign_tasks(
+    mut ants: Query<(&mut Ant, &Colony)>,
+    stimuli: Res<TaskStimuli>,
+    config: Res<Config>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+) {
+    let reassess_chance = time.delta_seconds() / config.tasks.reassess_secs.max(f32::EPSILON);
+
+    for (mut ant, colony) in ants.iter_mut() {
+        if ant.state != AntState::Searching || ant.has_food {
+            continue;
+        }
+        if rng.gen::<f32>() >= reassess_chance {
+            continue;
+        }
+
+        let thresholds = config.tasks.thresholds(ant.caste);
+        let responses =
+            Task::ALL.map(|task| response(stimuli.get(*colony, task), thresholds.threshold(task)));
+        let total: f32 = responses.iter().sum();
+        if total <= 0.0 {
+            continue;
+        }
+
+        let mut roll = rng.gen::<f32>() * total;
+        for (task, response) in Task::ALL.into_iter().zip(responses) {
+            if roll < response {
+                ant.task = task;
+                break;
+            }
+            roll -= response;
+        }
+    }
+}
+
+// Keep guards and corpse removers around their nest. Corpse removers head for the nearest
+// corpse near it, guards wander within guard_radius.
+pub fn steer_nest_tasks(
+    mut ants: Query<(&Transform, &mut Ant, &Colony)>,
+    landmarks: Landmarks,
+    corpses: Query<(&Transform, &Corpse)>,
+    config: Res<Config>,
+) {
+    for (transform, mut ant, colony) in ants.iter_mut() {
+        if ant.state != AntState::Searching
+            || !matches!(ant.task, Task::Guard | Task::CorpseRemoval)
+        {
+            continue;
+        }
+        let ant_pos = transform.translation.truncate();
+        let Some((base_pos, _)) = landmarks.nearest_base(ant_pos, Some(*colony)) else {
+            continue;
+        };
+
+        if ant.task == Task::CorpseRemoval {
+            let corpse = corpses
+                .iter()
+                .filter(|(_, corpse)| !corpse.disposed)
+                .map(|(t, _)| t.translation.truncate())
+                .filter(|pos| pos.distance(base_pos) <= config.necrophoresis_radius)
+                .min_by(|a, b| {
+                    ant_pos
+                        .distance_squared(*a)
+                        .total_cmp(&ant_pos.distance_squared(*b))
+                });
+            let direction = corpse.map_or(Vec2::ZERO, |pos| (pos - ant_pos).normalize_or_zero());
+            if direction != Vec2::ZERO {
+                ant.velocity = direction;
+                continue;
+            }
+        }
+
+        // Turn back toward the nest once too far out
+        if ant_pos.distance(base_pos) > config.tasks.guard_radius {
+            let to_base = (base_pos - ant_pos).normalize_or_zero();
+            let blended = (ant.velocity * 0.7 + to_base * 0.3).normalize_or_zero();
+            ant.velocity = if blended == Vec2::ZERO {
+                to_base
+            } else {
+                blended
+            };
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::ant::{spawn_ant, Caste};
+use crate::clock::SimClock;
+use crate::colony::Colony;
+use crate::config::{Config, TimelineAction, TimelineEvent};
+use crate::food::{spawn_food_source, FoodSource};
+use crate::grid::grid_to_world;
+use crate::obstacle::Obstacles;
+use crate::predator::spawn_predator;
+use crate::rng::SimRng;
+use bevy::prelude::*;
+
+// Index in Config::food_locations of a source placed at startup, for the timeline to find it by
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ConfiguredFood(pub usize);
+
+// Timeline events still to come, the next one last
+#[derive(Resource, Debug, Default)]
+pub struct Timeline {
+    pending: Vec<TimelineEvent>,
+}
+
+impl Timeline {
+    pub fn new(events: &[TimelineEvent]) -> Self {
+        let mut pending = events.to_vec();
+        // Events at the same time keep the order they are listed in
+        pending.sort_by(|a, b| a.at_secs.total_cmp(&b.at_secs));
+        pending.reverse();
+        Self { pending }
+    }
+}
+
+// Run each scripted event once the simulation clock reaches its time. Events due on the same
+// frame run in the order of their times, then of the timeline.
+pub fn run_timeline(
+    mut commands: Commands,
+    mut timeline: ResMut<Timeline>,
+    clock: Res<SimClock>,
+    food: Query<(Entity, &ConfiguredFood), With<FoodSource>>,
+    mut obstacles: ResMut<Obstacles>,
+    config: Res<Config>,
+    mut rng: ResMut<SimRng>,
+) {
+    while timeline
+        .pending
+        .last()
+        .is_some_and(|event| event.at_secs as f64 <= clock.elapsed_secs())
+    {
+        let Some(event) = timeline.pending.pop() else {
+            break;
+        };
+        match event.action {
+            TimelineAction::RemoveFoodSource { index } => {
+                for (entity, configured) in food.iter() {
+                    if configured.0 == index {
+                        commands.entity(entity).despawn();
+                    }
+                }
+            }
+            TimelineAction::AddFoodSource(food_config) => {
+                spawn_food_source(&mut commands, &food_config, &config);
+            }
+            TimelineAction::AddObstacle(rect) => obstacles.add(&rect),
+            TimelineAction::SpawnAnts {
+                colony,
+                count,
+                location,
+            } => {
+                let soldier_ratio = config
+                    .colony_configs()
+                    .get(colony as usize)
+                    .map_or(0.0, |colony_config| colony_config.soldier_ratio);
+                let position = grid_to_world((location.0 as i32, location.1 as i32));
+                for _ in 0..count {
+                    let caste = Caste::roll(soldier_ratio, &mut *rng);
+                    spawn_ant(
+                        &mut commands,
+                        &config,
+                        &mut *rng,
+                        Colony(colony),
+                        caste,
+                        position.extend(0.0),
+                    );
+                }
+            }
+            TimelineAction::SpawnPredators { count, location } => {
+                for _ in 0..count {
+                    spawn_predator(&mut commands, &mut *rng, location);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::clock::SimClock;
+use crate::config::Config;
+use crate::grid::{GridType, GRID_CELL_SIZE};
+use crate::marker::{Marker, MarkerType};
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// A chain of connected food marker cells strong enough to count as a trail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trail {
+    pub cells: usize,
+    // Pixels along the trail from one end to the other, following its cells
+    pub length: f32,
+}
+
+// Trails found by the latest analysis and how steady they have been
+#[derive(Resource, Debug, Default)]
+pub struct TrailStats {
+    // Longest first
+    pub trails: Vec<Trail>,
+    // Share of trail cells the latest analysis has in common with the one before, over the
+    // cells either of them found
+    pub stability: f32,
+    // Simulated seconds the trails have stayed stable so far, 0 while they aren't
+    pub stable_secs: f32,
+    // Simulated seconds at which the trails first stayed stable for TrailConfig::stable_secs
+    pub first_stable_secs: Option<f32>,
+    cells: HashSet<(i32, i32)>,
+    last_analysis: Option<f64>,
+}
+
+impl TrailStats {
+    pub fn total_length(&self) -> f32 {
+        self.trails.iter().map(|trail| trail.length).sum()
+    }
+
+    pub fn longest(&self) -> f32 {
+        self.trails.first().map_or(0.0, |trail| trail.length)
+    }
+}
+
+// Cells reachable from start within cells, with their distance in steps
+fn distances(
+    start: (i32, i32),
+    cells: &HashSet<(i32, i32)>,
+    grid_type: GridType,
+) -> HashMap<(i32, i32), usize> {
+    let mut distances = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(cell) = queue.pop_front() {
+        let distance = distances[&cell];
+        for neighbor in grid_type.neighbors(cell) {
+            if cells.contains(&neighbor) && !distances.contains_key(&neighbor) {
+                distances.insert(neighbor, distance + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    distances
+}
+
+fn farthest(distances: &HashMap<(i32, i32), usize>) -> ((i32, i32), usize) {
+    distances
+        .iter()
+        .map(|(&cell, &distance)| (cell, distance))
+        .max_by_key(|&(cell, distance)| (distance, cell))
+        .unwrap_or(((0, 0), 0))
+}
+
+// Split cells into connected chains, keeping those of at least min_cells. A chain's length
+// is its longest path, found by walking to the farthest cell from any cell and then to the
+// farthest cell from there.
+pub fn find_trails(
+    cells: &HashSet<(i32, i32)>,
+    grid_type: GridType,
+    min_cells: usize,
+) -> (Vec<Trail>, HashSet<(i32, i32)>) {
+    let mut trails = Vec::new();
+    let mut trail_cells = HashSet::new();
+    let mut seen: HashSet<(i32, i32)> = HashSet::new();
+    // Sorted so the same markers always give the same trails
+    let mut starts: Vec<(i32, i32)> = cells.iter().copied().collect();
+    starts.sort_unstable();
+    for start in starts {
+        if seen.contains(&start) {
+            continue;
+        }
+        let chain = distances(start, cells, grid_type);
+        seen.extend(chain.keys());
+        if chain.len() < min_cells.max(1) {
+            continue;
+        }
+        let (end, _) = farthest(&chain);
+        let (_, steps) = farthest(&distances(end, cells, grid_type));
+        trails.push(Trail {
+            cells: chain.len(),
+            length: (steps + 1) as f32 * GRID_CELL_SIZE,
+        });
+        trail_cells.extend(chain.keys());
+    }
+    trails.sort_by(|a, b| b.length.total_cmp(&a.length));
+    (trails, trail_cells)
+}
+
+// Every interval_secs of simulated time, pick the trails out of the strong food markers and
+// compare them with the previous analysis. The trails are stable while most of their cells
+// stay the same.
+pub fn detect_trails(
+    mut stats: ResMut<TrailStats>,
+    markers: Query<&Marker>,
+    clock: Res<SimClock>,
+    config: Res<Config>,
+) {
+    let now = clock.elapsed_secs();
+    let since_last = match stats.last_analysis {
+        Some(last) if now - last < config.trails.interval_secs as f64 => return,
+        Some(last) => (now - last) as f32,
+        None => 0.0,
+    };
+    stats.last_analysis = Some(now);
+
+    let strong: HashSet<(i32, i32)> = markers
+        .iter()
+        .filter(|marker| {
+            marker.marker_type == MarkerType::Food
+                && marker.intensity >= config.trails.min_intensity
+        })
+        .map(|marker| marker.grid_cell)
+        .collect();
+    let (trails, cells) = find_trails(&strong, config.grid_type, config.trails.min_cells as usize);
+
+    let shared = cells.intersection(&stats.cells).count();
+    let either = cells.union(&stats.cells).count();
+    stats.stability = if either > 0 {
+        shared as f32 / either as f32
+    } else {
+        0.0
+    };
+    if !trails.is_empty() && stats.stability >= config.trails.stable_overlap {
+        stats.stable_secs += since_last;
+    } else {
+        stats.stable_secs = 0.0;
+    }
+    if stats.first_stable_secs.is_none() && stats.stable_secs >= config.trails.stable_secs {
+        stats.first_stable_secs = Some(now as f32);
+    }
+    stats.trails = trails;
+    stats.cells = cells;
+}
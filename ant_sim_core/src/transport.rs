@@ -0,0 +1,270 @@
+use crate::ant::{in_view, Ant, AntState, ANT_SPEED};
+use crate::base::{nearest_base, Base};
+use crate::colony::Colony;
+use crate::config::{Config, HeavyFoodConfig};
+use crate::events::FoodDelivered;
+use crate::grid::{grid_to_world, GRID_CELL_SIZE};
+use crate::obstacle::Obstacles;
+use crate::rng::SimRng;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::StableHashMap;
+
+const DELIVERY_THRESHOLD: f32 = 10.0;
+
+// Food too heavy for one ant. It only moves once enough carriers are latched on.
+#[derive(Component)]
+pub struct HeavyFood {
+    pub carriers_needed: u32,
+    pub value: u32,
+    // Radius in pixels; carriers hold on around its edge
+    pub radius: f32,
+}
+
+// Added to an ant while it holds on to a heavy food item
+#[derive(Component)]
+pub struct Latched {
+    pub item: Entity,
+    // Where the ant holds the item, relative to its center
+    pub offset: Vec2,
+}
+
+// Left on an ant that gave up on an item, which it then ignores for a while
+#[derive(Component)]
+pub struct GaveUp {
+    pub item: Entity,
+    // Sim time (seconds) until which the item is ignored
+    pub until_secs: f32,
+}
+
+// Spawn a heavy food item centered on its grid cell
+pub fn spawn_heavy_food(commands: &mut Commands, item_config: &HeavyFoodConfig) -> Entity {
+    let cell = (item_config.location.0 as i32, item_config.location.1 as i32);
+    commands
+        .spawn((
+            HeavyFood {
+                carriers_needed: item_config.carriers_needed.max(1),
+                value: item_config.value,
+                // Bigger items need more carriers, up to a cell across
+                radius: (4.0 + 2.0 * item_config.carriers_needed as f32).min(GRID_CELL_SIZE / 2.0),
+            },
+            TransformBundle::from_transform(Transform::from_translation(
+                grid_to_world(cell).extend(0.0),
+            )),
+        ))
+        .id()
+}
+
+// Carriers of each item, and the colony they belong to
+fn carriers_by_item<'a>(
+    carriers: impl Iterator<Item = (&'a Latched, &'a Colony)>,
+) -> StableHashMap<Entity, (u32, Colony)> {
+    let mut counts: StableHashMap<Entity, (u32, Colony)> = StableHashMap::default();
+    for (latched, colony) in carriers {
+        counts.entry(latched.item).or_insert((0, *colony)).0 += 1;
+    }
+    counts
+}
+
+// Ants holding on to items, and ants that recently let go of one
+#[derive(SystemParam)]
+pub struct ItemClaims<'w, 's> {
+    carriers: Query<'w, 's, (&'static Latched, &'static Colony)>,
+    gave_up: Query<'w, 's, &'static GaveUp>,
+}
+
+// Searching workers head for heavy items they sense and latch on once they reach one.
+// Latched ants count as returning with the item, so they lay food markers that bring
+// in more help.
+pub fn latch_onto_heavy_food(
+    mut commands: Commands,
+    mut ants: Query<(Entity, &Transform, &mut Ant, &Colony), Without<Latched>>,
+    items: Query<(Entity, &Transform, &HeavyFood), Without<Ant>>,
+    claims: ItemClaims,
+    obstacles: Res<Obstacles>,
+    config: Res<Config>,
+    time: Res<Time>,
+) {
+    if items.is_empty() {
+        return;
+    }
+    let mut carrier_counts = carriers_by_item(claims.carriers.iter());
+    let now = time.elapsed_seconds();
+
+    for (ant_entity, transform, mut ant, colony) in ants.iter_mut() {
+        if ant.state != AntState::Searching || !ant.task.collects_food() || ant.has_food {
+            continue;
+        }
+        let ant_pos = transform.translation.truncate();
+
+        for (item_entity, item_transform, item) in items.iter() {
+            let ignored = claims
+                .gave_up
+                .get(ant_entity)
+                .is_ok_and(|gave_up| gave_up.item == item_entity && now < gave_up.until_secs);
+            if ignored {
+                continue;
+            }
+
+            // Items already fully manned, or claimed by another colony, are left alone
+            let (count, item_colony) = carrier_counts
+                .get(&item_entity)
+                .copied()
+                .unwrap_or((0, *colony));
+            if count >= item.carriers_needed || item_colony != *colony {
+                continue;
+            }
+
+            let item_pos = item_transform.translation.truncate();
+            let to_item = item_pos - ant_pos;
+            if to_item.length() <= item.radius + config.transport.latch_radius {
+                let offset = -to_item.normalize_or_zero() * item.radius;
+                commands.entity(ant_entity).insert(Latched {
+                    item: item_entity,
+                    offset,
+                });
+                ant.state = AntState::Returning;
+                ant.state_timer = 0.0;
+                ant.marker_timer = 0.0; // Start leaving food markers right away
+                carrier_counts.insert(item_entity, (count + 1, *colony));
+                break;
+            }
+
+            let edge = item_pos - to_item.normalize_or_zero() * item.radius;
+            if in_view(ant_pos, ant.velocity, edge, &config.vision, &obstacles) {
+                ant.velocity = to_item.normalize_or_zero();
+                break;
+            }
+        }
+    }
+}
+
+// Move manned items toward their carriers' nearest base. On arrival the item's food is
+// credited to the colony and the carriers rest like after any delivery.
+pub fn carry_heavy_food(
+    mut commands: Commands,
+    mut items: Query<(Entity, &mut Transform, &HeavyFood), Without<Base>>,
+    mut carriers: Query<(Entity, &mut Ant, &Colony, &Latched)>,
+    bases: Query<(&Transform, &Colony), With<Base>>,
+    mut delivered_events: EventWriter<FoodDelivered>,
+    time: Res<Time>,
+    config: Res<Config>,
+) {
+    let carrier_counts = carriers_by_item(
+        carriers
+            .iter()
+            .map(|(_, _, colony, latched)| (latched, colony)),
+    );
+    let speed = ANT_SPEED * config.transport.speed_factor;
+
+    // Heading of each moving item, None once it was delivered
+    let mut headings: StableHashMap<Entity, Option<Vec2>> = StableHashMap::default();
+    for (item_entity, mut item_transform, item) in items.iter_mut() {
+        let Some(&(count, colony)) = carrier_counts.get(&item_entity) else {
+            continue;
+        };
+        if count < item.carriers_needed {
+            continue;
+        }
+
+        let item_pos = item_transform.translation.truncate();
+        let own_bases = bases
+            .iter()
+            .filter(|(_, base_colony)| **base_colony == colony)
+            .map(|(t, _)| t.translation.truncate());
+        let Some(base_pos) = nearest_base(item_pos, own_bases) else {
+            continue;
+        };
+
+        let to_base = base_pos - item_pos;
+        if to_base.length() < DELIVERY_THRESHOLD {
+            // Credit the item's food to its carriers in turn
+            let delivering: Vec<(Entity, Vec2, f32)> = carriers
+                .iter()
+                .filter(|(_, _, _, latched)| latched.item == item_entity)
+                .map(|(entity, ant, _, _)| (entity, ant.velocity, ant.trip_timer))
+                .collect();
+            for i in 0..item.value as usize {
+                let (ant, arrival_direction, trip_secs) = delivering[i % delivering.len()];
+                delivered_events.send(FoodDelivered {
+                    ant,
+                    colony,
+                    position: item_pos,
+                    arrival_direction,
+                    trip_secs,
+                });
+            }
+            commands.entity(item_entity).despawn();
+            headings.insert(item_entity, None);
+            continue;
+        }
+
+        let heading = to_base.normalize();
+        let step = (speed * time.delta_seconds()).min(to_base.length());
+        item_transform.translation += (heading * step).extend(0.0);
+        headings.insert(item_entity, Some(heading));
+    }
+
+    for (entity, mut ant, _, latched) in carriers.iter_mut() {
+        match headings.get(&latched.item) {
+            Some(None) => {
+                commands.entity(entity).remove::<Latched>();
+                ant.pheromone = config.pheromone_reserve.full();
+                ant.state = AntState::Resting;
+                ant.state_timer = 0.0;
+                ant.velocity = -ant.velocity;
+            }
+            Some(Some(heading)) => ant.velocity = *heading,
+            None => {}
+        }
+    }
+}
+
+// Keep carriers holding on to their item, wherever move_ants took them this frame
+pub fn hold_heavy_food(
+    mut carriers: Query<(&mut Transform, &Latched)>,
+    items: Query<&Transform, Without<Latched>>,
+) {
+    for (mut transform, latched) in carriers.iter_mut() {
+        if let Ok(item_transform) = items.get(latched.item) {
+            let hold = item_transform.translation.truncate() + latched.offset;
+            transform.translation = hold.extend(transform.translation.z);
+        }
+    }
+}
+
+// Carriers let go of items that never got enough help, or that are gone. They leave
+// the item alone for as long as they waited on it, so others get a chance to find it.
+pub fn release_idle_carriers(
+    mut commands: Commands,
+    mut carriers: Query<(Entity, &mut Ant, &Colony, &Latched)>,
+    items: Query<&HeavyFood>,
+    config: Res<Config>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+) {
+    let carrier_counts = carriers_by_item(
+        carriers
+            .iter()
+            .map(|(_, _, colony, latched)| (latched, colony)),
+    );
+
+    for (entity, mut ant, _, latched) in carriers.iter_mut() {
+        let waiting_too_long = match items.get(latched.item) {
+            Ok(item) => {
+                let count = carrier_counts
+                    .get(&latched.item)
+                    .map_or(0, |(count, _)| *count);
+                count < item.carriers_needed && ant.state_timer >= config.transport.give_up_secs
+            }
+            Err(_) => true,
+        };
+        if waiting_too_long && ant.state == AntState::Returning {
+            commands.entity(entity).remove::<Latched>().insert(GaveUp {
+                item: latched.item,
+                until_secs: time.elapsed_seconds() + config.transport.give_up_secs,
+            });
+            ant.start_searching(&config, &mut *rng);
+        }
+    }
+}
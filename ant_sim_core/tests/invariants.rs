@@ -0,0 +1,658 @@
+// Headless runs of the simulation checking invariants that must hold after every tick
+use ant_sim_core::ant::{Ant, AntState, Caste};
+use ant_sim_core::brood::Brood;
+use ant_sim_core::clock::SimClock;
+use ant_sim_core::colony::ColonyStats;
+use ant_sim_core::config::{Config, HeavyFoodConfig, ObstacleRect};
+use ant_sim_core::events::AntKilled;
+use ant_sim_core::food::FoodQuantity;
+use ant_sim_core::grid::{GridMap, GridType, GRID_CELL_SIZE};
+use ant_sim_core::marker::{Marker, MarkerLifetime};
+use ant_sim_core::milestone::{Milestone, MilestoneReached};
+use ant_sim_core::nest::Nest;
+use ant_sim_core::obstacle::Obstacles;
+use ant_sim_core::predator::Predator;
+use ant_sim_core::simulation::SimulationPlugin;
+use ant_sim_core::spatial::SpatialIndex;
+use ant_sim_core::summary::{EndReason, RunSummary};
+use ant_sim_core::timeline::ConfiguredFood;
+use ant_sim_core::trail::find_trails;
+use ant_sim_core::transport::{HeavyFood, Latched};
+use bevy::ecs::event::ManualEventReader;
+use bevy::ecs::schedule::{LogLevel, ScheduleBuildSettings};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(16);
+const TICKS: usize = 2000;
+
+fn test_config(seed: u64) -> Config {
+    serde_json::from_value(serde_json::json!({
+        "map_size": [40, 30],
+        "base_locations": [[18, 13]],
+        "food_locations": [
+            [24, 14],
+            [12, 20],
+            { "location": [30, 8], "quantity": 40, "radius": 1 }
+        ],
+        "spawn_rate": 0.0,
+        "marker_spawn_interval": 0.15,
+        "marker_lifetime": 10.0,
+        "initial_ant_count": 200,
+        "food_quantity": 50,
+        "seed": seed
+    }))
+    .expect("test config should deserialize")
+}
+
+fn headless_app(config: Config) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .insert_resource(config)
+        .add_plugins(SimulationPlugin);
+    app
+}
+
+// Food left in sources, carried by ants and delivered to bases
+fn food_totals(app: &mut App) -> (u32, u32, u32) {
+    let world = &mut app.world;
+    let remaining = world
+        .query::<&FoodQuantity>()
+        .iter(world)
+        .map(|food| food.quantity)
+        .sum();
+    let in_transit = world
+        .query::<&Ant>()
+        .iter(world)
+        .filter(|ant| ant.has_food)
+        .count() as u32;
+    let delivered = world
+        .resource::<ColonyStats>()
+        .colonies
+        .iter()
+        .map(|totals| totals.food_delivered)
+        .sum();
+    (remaining, in_transit, delivered)
+}
+
+#[test]
+fn ants_stay_in_bounds() {
+    let config = test_config(1);
+    let map_width = config.map_size.0 as f32 * GRID_CELL_SIZE;
+    let map_height = config.map_size.1 as f32 * GRID_CELL_SIZE;
+    let mut app = headless_app(config);
+
+    for tick in 0..TICKS {
+        app.update();
+        let world = &mut app.world;
+        for (transform, _) in world.query::<(&Transform, &Ant)>().iter(world) {
+            let pos = transform.translation;
+            assert!(
+                (0.0..=map_width).contains(&pos.x) && (0.0..=map_height).contains(&pos.y),
+                "ant out of bounds at {:?} on tick {}",
+                pos,
+                tick
+            );
+        }
+    }
+}
+
+#[test]
+fn ants_never_enter_walls() {
+    let mut config = test_config(9);
+    // Between the base and the nearest food source
+    config.obstacles = vec![ObstacleRect {
+        from: (22, 8),
+        to: (22, 20),
+    }];
+    let mut app = headless_app(config);
+
+    for tick in 0..TICKS {
+        app.update();
+        let world = &mut app.world;
+        let positions: Vec<Vec2> = world
+            .query_filtered::<&Transform, With<Ant>>()
+            .iter(world)
+            .map(|transform| transform.translation.truncate())
+            .collect();
+        let obstacles = world.resource::<Obstacles>();
+        for pos in positions {
+            assert!(
+                !obstacles.is_blocked(pos),
+                "ant inside a wall at {:?} on tick {}",
+                pos,
+                tick
+            );
+        }
+    }
+}
+
+#[test]
+fn markers_respect_lifetimes() {
+    let config = test_config(2);
+    let marker_lifetime = config.marker_lifetime;
+    let mut app = headless_app(config);
+
+    for tick in 0..TICKS {
+        app.update();
+        let world = &mut app.world;
+        let markers: Vec<(Entity, (i32, i32), _, f32)> = world
+            .query::<(Entity, &Marker, &MarkerLifetime)>()
+            .iter(world)
+            .map(|(entity, marker, lifetime)| {
+                (
+                    entity,
+                    marker.grid_cell,
+                    marker.marker_type,
+                    lifetime.timer.elapsed_secs(),
+                )
+            })
+            .collect();
+
+        let grid_map = world.resource::<GridMap>();
+        for (entity, cell, marker_type, age) in markers {
+            assert!(
+                age <= marker_lifetime,
+                "marker aged {}s past its {}s lifetime on tick {}",
+                age,
+                marker_lifetime,
+                tick
+            );
+            // Every live marker is the one registered for its cell
+            let registered = grid_map
+                .get_cell(cell)
+                .and_then(|cell_data| cell_data.marker(marker_type));
+            assert_eq!(
+                registered,
+                Some(entity),
+                "marker in cell {:?} missing from the grid map on tick {}",
+                cell,
+                tick
+            );
+        }
+    }
+}
+
+#[test]
+fn hex_markers_sit_in_their_cells() {
+    let mut config = test_config(6);
+    config.grid_type = GridType::Hex;
+    let mut app = headless_app(config);
+
+    for tick in 0..TICKS / 4 {
+        app.update();
+        let world = &mut app.world;
+        let markers: Vec<(Entity, Vec2, (i32, i32), _)> = world
+            .query::<(Entity, &Transform, &Marker)>()
+            .iter(world)
+            .map(|(entity, transform, marker)| {
+                (
+                    entity,
+                    transform.translation.truncate(),
+                    marker.grid_cell,
+                    marker.marker_type,
+                )
+            })
+            .collect();
+
+        let grid_map = world.resource::<GridMap>();
+        for (entity, position, cell, marker_type) in markers {
+            // Markers are placed at the center of their hex, which maps back to the same cell
+            assert_eq!(
+                GridType::Hex.world_to_grid(position),
+                cell,
+                "marker at {:?} outside its cell on tick {}",
+                position,
+                tick
+            );
+            let registered = grid_map
+                .get_cell(cell)
+                .and_then(|cell_data| cell_data.marker(marker_type));
+            assert_eq!(registered, Some(entity));
+        }
+    }
+}
+
+#[test]
+fn spatial_index_matches_scans() {
+    let mut app = headless_app(test_config(8));
+    let probes = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(600.0, 450.0),
+        Vec2::new(1270.0, 30.0),
+        Vec2::new(-200.0, 2000.0),
+    ];
+
+    for tick in 0..TICKS / 4 {
+        app.update();
+        // The index holds positions from the start of the frame, before anything moved
+        let world = &mut app.world;
+        let index = world.resource::<SpatialIndex<Ant>>();
+        let indexed: Vec<Vec2> = index
+            .within_radius(Vec2::ZERO, 1.0e6)
+            .map(|(_, pos)| pos)
+            .collect();
+        assert_eq!(indexed.len(), index.len());
+
+        for probe in probes {
+            let closest = indexed
+                .iter()
+                .map(|pos| pos.distance(probe))
+                .fold(f32::INFINITY, f32::min);
+            let (_, nearest) = index.nearest(probe, |_| true).expect("ants are indexed");
+            assert_eq!(
+                nearest.distance(probe),
+                closest,
+                "nearest ant on tick {}",
+                tick
+            );
+
+            let in_radius = indexed
+                .iter()
+                .filter(|pos| pos.distance(probe) <= 100.0)
+                .count();
+            assert_eq!(index.within_radius(probe, 100.0).count(), in_radius);
+        }
+    }
+}
+
+#[test]
+fn food_is_conserved() {
+    let mut config = test_config(3);
+    // Predators on the way to the nearest source catch ants carrying food
+    config.predators.locations = vec![(21, 13), (21, 14)];
+    config.predators.kill_chance = 2.0;
+    config.predators.soldier_kills_predator = 0.0;
+    config.predators.digest_secs = 1.0;
+    let mut app = headless_app(config);
+    app.update();
+    let (initial, _, _) = food_totals(&mut app);
+    let mut killed_reader = ManualEventReader::<AntKilled>::default();
+    let mut carriers_killed = 0;
+
+    for tick in 0..TICKS {
+        app.update();
+        carriers_killed += killed_reader
+            .read(app.world.resource::<Events<AntKilled>>())
+            .filter(|event| event.carried_food)
+            .count();
+        let (remaining, in_transit, delivered) = food_totals(&mut app);
+        // Depleted sources are despawned and food dropped by killed carriers is a new source, so
+        // picked up food is what is missing from the total
+        let picked_up = initial - remaining;
+        assert_eq!(
+            picked_up,
+            delivered + in_transit,
+            "food lost or created on tick {}",
+            tick
+        );
+    }
+
+    let (_, _, delivered) = food_totals(&mut app);
+    assert!(delivered > 0, "no food was delivered in {} ticks", TICKS);
+    assert!(carriers_killed > 0, "no ant carrying food was killed");
+}
+
+#[test]
+fn run_ends_at_max_sim_time() {
+    let summary_path = std::env::temp_dir().join("ant_sim_core_max_sim_time_summary.json");
+    let mut config = test_config(4);
+    config.end_conditions.max_sim_secs = Some(5.0);
+    config.end_conditions.summary_path = summary_path.to_string_lossy().into_owned();
+    let mut app = headless_app(config);
+
+    for _ in 0..TICKS / 4 {
+        app.update();
+    }
+
+    // The clock stops once the run is over
+    assert!(app.world.resource::<Time<Virtual>>().is_paused());
+    let summary: RunSummary =
+        serde_json::from_str(&std::fs::read_to_string(&summary_path).expect("summary written"))
+            .expect("summary should deserialize");
+    std::fs::remove_file(&summary_path).ok();
+    assert_eq!(summary.end_reason, EndReason::MaxSimTime);
+    assert_eq!(summary.seed, 4);
+    assert!((5.0..5.1).contains(&summary.sim_secs));
+    assert_eq!(summary.peak_ant_count, 200);
+}
+
+#[test]
+fn only_foraging_tasks_carry_food() {
+    let mut config = test_config(7);
+    config.colonies = serde_json::from_value(serde_json::json!([
+        { "base_locations": [[18, 13]], "initial_ant_count": 100, "soldier_ratio": 0.3 },
+        { "base_locations": [[26, 15]], "initial_ant_count": 100, "soldier_ratio": 0.3 }
+    ]))
+    .expect("colonies should deserialize");
+    let mut app = headless_app(config);
+
+    for tick in 0..TICKS / 2 {
+        app.update();
+        let world = &mut app.world;
+        for ant in world.query::<&Ant>().iter(world) {
+            if ant.has_food {
+                // Soldiers have no forage or scout threshold by default
+                assert_eq!(
+                    ant.caste,
+                    Caste::Worker,
+                    "soldier with food on tick {}",
+                    tick
+                );
+                assert!(
+                    ant.task.collects_food(),
+                    "{:?} ant with food on tick {}",
+                    ant.task,
+                    tick
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn heavy_food_moves_only_when_manned() {
+    let mut config = test_config(5);
+    config.transport.items = vec![
+        HeavyFoodConfig {
+            location: (21, 14),
+            carriers_needed: 3,
+            value: 10,
+        },
+        // More carriers than there are ants
+        HeavyFoodConfig {
+            location: (15, 15),
+            carriers_needed: 500,
+            value: 10,
+        },
+    ];
+    let mut app = headless_app(config);
+    app.update();
+
+    let mut positions: HashMap<Entity, Vec3> = HashMap::new();
+    for _ in 0..TICKS {
+        // Carriers latched before the tick are the ones moving the item during it
+        let world = &mut app.world;
+        let mut carriers: HashMap<Entity, u32> = HashMap::new();
+        for latched in world.query::<&Latched>().iter(world) {
+            *carriers.entry(latched.item).or_default() += 1;
+        }
+
+        app.update();
+
+        let world = &mut app.world;
+        for (entity, transform, item) in world
+            .query::<(Entity, &Transform, &HeavyFood)>()
+            .iter(world)
+        {
+            let manned = carriers.get(&entity).copied().unwrap_or(0) >= item.carriers_needed;
+            if let Some(previous) = positions.insert(entity, transform.translation) {
+                assert!(
+                    manned || previous == transform.translation,
+                    "item moved without enough carriers"
+                );
+            }
+        }
+    }
+
+    let world = &mut app.world;
+    let items: Vec<u32> = world
+        .query::<&HeavyFood>()
+        .iter(world)
+        .map(|item| item.carriers_needed)
+        .collect();
+    assert_eq!(items, vec![500], "only the unmovable item should be left");
+    let delivered: u32 = world
+        .resource::<ColonyStats>()
+        .colonies
+        .iter()
+        .map(|totals| totals.food_delivered)
+        .sum();
+    assert!(delivered >= 10, "the light item's food was not delivered");
+}
+
+#[test]
+fn delivered_food_feeds_the_brood() {
+    let mut config = test_config(4);
+    config.spawn_rate = 0.5;
+    config.brood.egg_secs = 2.0;
+    config.brood.larva_secs = 2.0;
+    config.brood.pupa_secs = 2.0;
+    let food_per_egg = config.brood.food_per_egg;
+    let initial_ants = config.initial_ant_count;
+    let mut app = headless_app(config);
+
+    for tick in 0..TICKS {
+        app.update();
+        let (_, _, delivered) = food_totals(&mut app);
+        let world = &mut app.world;
+        let stored: u32 = world
+            .query::<&Nest>()
+            .iter(world)
+            .map(Nest::stored_food)
+            .sum();
+        let developing = world
+            .query::<&Brood>()
+            .iter(world)
+            .map(|brood| brood.ages.len() as u32)
+            .sum::<u32>();
+        // A single colony has no one to fight, so every ant past the first ones hatched
+        let hatched = world.query::<&Ant>().iter(world).count() as u32 - initial_ants;
+        assert_eq!(
+            delivered,
+            stored + food_per_egg * (developing + hatched),
+            "delivered food neither stored nor laid as eggs on tick {}",
+            tick
+        );
+    }
+
+    let world = &mut app.world;
+    let ants = world.query::<&Ant>().iter(world).count() as u32;
+    assert!(ants > initial_ants, "no ant hatched in {} ticks", TICKS);
+}
+
+#[test]
+fn timeline_events_run_on_schedule() {
+    let mut config = test_config(6);
+    config.timeline = serde_json::from_value(serde_json::json!([
+        { "at_secs": 15.0, "action": "spawn_ants", "colony": 0, "count": 20, "location": [5, 5] },
+        { "at_secs": 5.0, "action": "remove_food_source", "index": 0 },
+        { "at_secs": 10.0, "action": "add_obstacle", "from": [2, 25], "to": [8, 25] }
+    ]))
+    .expect("timeline should deserialize");
+    let initial_ants = config.initial_ant_count as usize;
+    let mut app = headless_app(config);
+
+    for tick in 0..TICKS {
+        app.update();
+        let world = &mut app.world;
+        let secs = world.resource::<SimClock>().elapsed_secs();
+        let first_source = world
+            .query::<&ConfiguredFood>()
+            .iter(world)
+            .any(|food| food.0 == 0);
+        let walled = world.resource::<Obstacles>().is_blocked_cell((5, 25));
+        let ants = world.query::<&Ant>().iter(world).count();
+
+        if secs >= 5.0 {
+            assert!(!first_source, "food source 0 left at {:.2}s", secs);
+        }
+        assert_eq!(walled, secs >= 10.0, "wall wrong at {:.2}s", secs);
+        let expected = initial_ants + if secs >= 15.0 { 20 } else { 0 };
+        assert_eq!(ants, expected, "ant count wrong on tick {}", tick);
+    }
+}
+
+#[test]
+fn milestones_are_reached_once_in_order() {
+    let mut app = headless_app(test_config(8));
+    let mut reader = ManualEventReader::<MilestoneReached>::default();
+    let mut reached: Vec<MilestoneReached> = Vec::new();
+
+    for _ in 0..TICKS {
+        app.update();
+        let events = app.world.resource::<Events<MilestoneReached>>();
+        reached.extend(reader.read(events).copied());
+    }
+
+    let order: Vec<Milestone> = reached.iter().map(|reached| reached.milestone).collect();
+    let first_food = order.iter().position(|m| *m == Milestone::FirstFoodFound);
+    let stable_trail = order.iter().position(|m| *m == Milestone::FirstStableTrail);
+    assert!(first_food.is_some(), "no food found in {} ticks", TICKS);
+    assert!(
+        stable_trail.is_some_and(|trail| Some(trail) > first_food),
+        "no stable trail after the first food: {:?}",
+        order
+    );
+    for (i, milestone) in order.iter().enumerate() {
+        assert!(
+            !order[..i].contains(milestone),
+            "{:?} reached twice",
+            milestone
+        );
+    }
+    assert!(
+        reached
+            .windows(2)
+            .all(|pair| pair[0].sim_secs <= pair[1].sim_secs),
+        "milestones out of order: {:?}",
+        reached
+    );
+    // No queen lays in the test config
+    assert!(!order.contains(&Milestone::PopulationDoubled));
+}
+
+#[test]
+fn trails_are_connected_marker_chains() {
+    // An L-shaped chain of 12 cells, a short chain and a lone cell
+    let mut cells: HashSet<(i32, i32)> = (0..8).map(|x| (x, 3)).collect();
+    cells.extend((4..8).map(|y| (7, y)));
+    cells.extend([(20, 20), (21, 20), (30, 5)]);
+
+    for grid_type in [GridType::Square, GridType::Hex] {
+        let (trails, trail_cells) = find_trails(&cells, grid_type, 5);
+        assert_eq!(trails.len(), 1, "{:?}: {:?}", grid_type, trails);
+        assert_eq!(trails[0].cells, 12);
+        assert!(trail_cells.iter().all(|cell| cell.1 < 10));
+        // Both ends of the L are at least as far apart as its longer leg
+        assert!(trails[0].length >= 8.0 * GRID_CELL_SIZE);
+        assert!(trails[0].length <= 12.0 * GRID_CELL_SIZE);
+    }
+}
+
+#[test]
+fn predators_hunt_and_rest_in_place() {
+    let mut config = test_config(12);
+    // On the way from the base to the nearest food source
+    config.predators.locations = vec![(21, 13), (21, 14)];
+    config.predators.kill_chance = 4.0;
+    config.predators.soldier_kills_predator = 0.0;
+    let map_size = Vec2::new(config.map_size.0 as f32, config.map_size.1 as f32) * GRID_CELL_SIZE;
+    let mut app = headless_app(config);
+    let mut digesting: HashMap<Entity, Vec2> = HashMap::new();
+
+    for tick in 0..TICKS {
+        app.update();
+        let world = &mut app.world;
+        let predators: Vec<(Entity, Vec2, f32)> = world
+            .query::<(Entity, &Transform, &Predator)>()
+            .iter(world)
+            .map(|(entity, transform, predator)| {
+                (entity, transform.translation.truncate(), predator.digesting)
+            })
+            .collect();
+        assert_eq!(predators.len(), 2, "predator lost on tick {}", tick);
+        for &(entity, pos, _) in &predators {
+            assert!(
+                pos.cmpge(Vec2::ZERO).all() && pos.cmple(map_size).all(),
+                "predator out of bounds at {:?} on tick {}",
+                pos,
+                tick
+            );
+            if let Some(rest_pos) = digesting.get(&entity) {
+                assert_eq!(pos, *rest_pos, "digesting predator moved on tick {}", tick);
+            }
+        }
+        digesting = predators
+            .into_iter()
+            .filter(|&(_, _, secs)| secs > 0.0)
+            .map(|(entity, pos, _)| (entity, pos))
+            .collect();
+    }
+
+    let totals = &app.world.resource::<ColonyStats>().colonies[0];
+    assert!(totals.deaths > 0, "predators caught no ants");
+    // Predators aren't a colony, so their catches count for nobody
+    assert_eq!(totals.kills, 0);
+}
+
+// Where every ant and predator is, what the ants are doing and where the food is
+#[derive(PartialEq)]
+struct SimState {
+    ants: Vec<(Entity, Vec3, AntState, bool)>,
+    predators: Vec<Vec3>,
+    food: (u32, u32, u32),
+}
+
+fn sim_state(app: &mut App) -> SimState {
+    let world = &mut app.world;
+    let ants = world
+        .query::<(Entity, &Transform, &Ant)>()
+        .iter(world)
+        .map(|(entity, transform, ant)| (entity, transform.translation, ant.state, ant.has_food))
+        .collect();
+    let predators = world
+        .query_filtered::<&Transform, With<Predator>>()
+        .iter(world)
+        .map(|transform| transform.translation)
+        .collect();
+    SimState {
+        ants,
+        predators,
+        food: food_totals(app),
+    }
+}
+
+#[test]
+fn same_seed_runs_match() {
+    let config = || {
+        let mut config = test_config(11);
+        config.colonies = serde_json::from_value(serde_json::json!([
+            { "base_locations": [[18, 13]], "initial_ant_count": 100, "soldier_ratio": 0.3 },
+            { "base_locations": [[26, 15]], "initial_ant_count": 100, "soldier_ratio": 0.3 }
+        ]))
+        .expect("colonies should deserialize");
+        config.predators.locations = vec![(21, 13), (14, 18)];
+        config
+    };
+    let mut first = headless_app(config());
+    let mut second = headless_app(config());
+
+    for tick in 0..TICKS / 2 {
+        first.update();
+        second.update();
+        assert!(
+            sim_state(&mut first) == sim_state(&mut second),
+            "runs with the same seed differ on tick {}",
+            tick
+        );
+    }
+}
+
+// Unordered systems with conflicting access may run in another order in another process,
+// which same_seed_runs_match can't see
+#[test]
+fn simulation_systems_have_one_order() {
+    let mut app = headless_app(test_config(12));
+    app.edit_schedule(Update, |schedule| {
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Error,
+            ..default()
+        });
+    });
+    // Building the schedule panics on ambiguities
+    app.update();
+}
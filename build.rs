@@ -0,0 +1,17 @@
+use std::process::Command;
+
+// Record the commit the binaries are built from, for the metadata written with each run
+fn main() {
+    let hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    if let Some(hash) = hash {
+        println!("cargo:rustc-env=GIT_HASH={}", hash);
+    }
+    // Rebuild when a commit is checked out or made
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
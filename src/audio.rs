@@ -0,0 +1,139 @@
+use crate::gui::GuiSettings;
+use ant_sim_core::ant::Ant;
+use ant_sim_core::config::Config;
+use ant_sim_core::events::{FoodDelivered, FoodPickedUp};
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+use std::path::Path;
+
+// Seconds between two sounds of the same kind, so a busy colony clicks instead of roars
+const MIN_SOUND_INTERVAL: f32 = 0.08;
+const PICKUP_VOLUME: f32 = 0.4;
+const DELIVERY_VOLUME: f32 = 0.8;
+
+// Loaded sound handles, only present when audio is enabled and every file exists
+#[derive(Resource)]
+pub struct SoundAssets {
+    pickup: Handle<AudioSource>,
+    delivery: Handle<AudioSource>,
+    master_volume: f32,
+}
+
+// The looping ambient colony hum
+#[derive(Component)]
+pub struct AmbientHum;
+
+pub fn load_sound_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<Config>,
+) {
+    let audio = &config.audio;
+    if !audio.enabled {
+        return;
+    }
+
+    let missing: Vec<&String> = [&audio.pickup, &audio.delivery, &audio.ambient]
+        .into_iter()
+        .filter(|path| !Path::new("assets").join(path).exists())
+        .collect();
+    if !missing.is_empty() {
+        eprintln!("Sounds not found ({:?}), audio disabled", missing);
+        return;
+    }
+
+    commands.insert_resource(SoundAssets {
+        pickup: asset_server.load(audio.pickup.clone()),
+        delivery: asset_server.load(audio.delivery.clone()),
+        master_volume: audio.master_volume,
+    });
+    // Starts silent, its volume follows the population
+    commands.spawn((
+        AudioBundle {
+            source: asset_server.load(audio.ambient.clone()),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(0.0)),
+        },
+        AmbientHum,
+    ));
+}
+
+fn play_once(commands: &mut Commands, sound: &Handle<AudioSource>, volume: f32) {
+    commands.spawn(AudioBundle {
+        source: sound.clone(),
+        settings: PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::new_relative(volume),
+            ..default()
+        },
+    });
+}
+
+// Click on food pickups and chime on deliveries, at most one of each per MIN_SOUND_INTERVAL
+pub fn play_event_sounds(
+    mut commands: Commands,
+    sounds: Res<SoundAssets>,
+    mut picked_up_events: EventReader<FoodPickedUp>,
+    mut delivered_events: EventReader<FoodDelivered>,
+    mut last_played: Local<(f32, f32)>,
+    settings: Res<GuiSettings>,
+    time: Res<Time<Real>>,
+) {
+    let picked_up = picked_up_events.read().count() > 0;
+    let delivered = delivered_events.read().count() > 0;
+    if settings.mute_audio {
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    let master_volume = sounds.master_volume;
+    if picked_up && now - last_played.0 >= MIN_SOUND_INTERVAL {
+        play_once(&mut commands, &sounds.pickup, PICKUP_VOLUME * master_volume);
+        last_played.0 = now;
+    }
+    if delivered && now - last_played.1 >= MIN_SOUND_INTERVAL {
+        play_once(
+            &mut commands,
+            &sounds.delivery,
+            DELIVERY_VOLUME * master_volume,
+        );
+        last_played.1 = now;
+    }
+}
+
+// Scale the ambient hum with the number of ants
+pub fn update_ambient_hum(
+    hum: Query<&AudioSink, With<AmbientHum>>,
+    ants: Query<(), With<Ant>>,
+    settings: Res<GuiSettings>,
+    config: Res<Config>,
+) {
+    let Ok(sink) = hum.get_single() else {
+        return;
+    };
+    let volume = if settings.mute_audio {
+        0.0
+    } else {
+        let population = ants.iter().count() as f32;
+        let full_population = config.audio.ambient_full_population.max(1) as f32;
+        (population / full_population).min(1.0) * config.audio.master_volume
+    };
+    sink.set_volume(volume);
+}
+
+pub struct SimAudioPlugin;
+
+impl Plugin for SimAudioPlugin {
+    fn build(&self, app: &mut App) {
+        // Observers get no simulation events but still hear the colony
+        app.add_event::<FoodPickedUp>()
+            .add_event::<FoodDelivered>()
+            .add_systems(Startup, load_sound_assets)
+            .add_systems(
+                Update,
+                (
+                    play_event_sounds.run_if(resource_exists::<SoundAssets>()),
+                    update_ambient_hum,
+                ),
+            );
+    }
+}
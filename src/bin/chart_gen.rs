@@ -20,7 +20,7 @@ struct Args {
     #[arg(long, num_args = 1..)]
     compare: Option<Vec<PathBuf>>,
 
-    /// Use all CSV files in the logs/ directory
+    /// Use all CSV files in the logs/ directory, joining the segments of rotated logs
     #[arg(long)]
     all: bool,
 
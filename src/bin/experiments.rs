@@ -0,0 +1,404 @@
+use ant_sim::chart_data::{parse_multiple_csv_files, RunMetadata};
+use ant_sim::chart_generator::{generate_markdown, XAxisType};
+use ant_sim_core::ant::{Ant, AntState};
+use ant_sim_core::clock::SimClock;
+use ant_sim_core::config::Config;
+use ant_sim_core::marker::{Marker, MarkerType};
+use ant_sim_core::rng::SimRng;
+use ant_sim_core::simulation::SimulationPlugin;
+use ant_sim_core::summary::RunSummary;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use clap::Parser;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(name = "experiments")]
+#[command(about = "Run batches of headless simulations from an experiment manifest")]
+struct Args {
+    /// Experiment manifest (JSON)
+    manifest: PathBuf,
+}
+
+// Configurations to compare, each run a number of times:
+// {
+//   "output_dir": "experiments/recruitment",
+//   "repetitions": 3,
+//   "runs": [
+//     { "name": "baseline" },
+//     { "name": "more_recruits", "overrides": { "recruits_per_delivery": 6 } }
+//   ]
+// }
+#[derive(Deserialize)]
+struct Manifest {
+    output_dir: PathBuf,
+    #[serde(default = "default_repetitions")]
+    repetitions: u32,
+    /// Simulated milliseconds per tick
+    #[serde(default = "default_tick_ms")]
+    tick_ms: u64,
+    /// Longest any run may last; configs with a shorter max_sim_secs end earlier
+    #[serde(default = "default_max_sim_secs")]
+    max_sim_secs: f32,
+    /// Simulated seconds between rows of each run's log
+    #[serde(default = "default_sample_interval_secs")]
+    sample_interval_secs: f32,
+    runs: Vec<RunSpec>,
+}
+
+#[derive(Deserialize)]
+struct RunSpec {
+    name: String,
+    /// Base config file, the built-in default config when unset
+    config: Option<PathBuf>,
+    /// Config fields replaced for this run, merged into the base config
+    #[serde(default)]
+    overrides: serde_json::Value,
+    /// Overrides the manifest's repetitions
+    repetitions: Option<u32>,
+}
+
+fn default_repetitions() -> u32 {
+    1
+}
+
+fn default_tick_ms() -> u64 {
+    16
+}
+
+fn default_max_sim_secs() -> f32 {
+    600.0
+}
+
+fn default_sample_interval_secs() -> f32 {
+    1.0
+}
+
+// Per-second samples of a headless run, in the simulation log format chart_gen reads
+#[derive(Resource)]
+struct SampleLog {
+    writer: csv::Writer<std::fs::File>,
+    timer: Timer,
+    // Wall clock time of the previous tick, as frame times aren't real when time is stepped manually
+    last_tick: Instant,
+    // Sim time is written as timestamps from this start so the time axis is simulated seconds
+    start: chrono::NaiveDateTime,
+}
+
+fn record_samples(
+    mut log: ResMut<SampleLog>,
+    time: Res<Time>,
+    ants: Query<&Ant>,
+    markers: Query<&Marker>,
+    clock: Res<SimClock>,
+) {
+    let now = Instant::now();
+    let frame_time_ms = (now - log.last_tick).as_secs_f32() * 1000.0;
+    log.last_tick = now;
+    log.timer.tick(time.delta());
+    if !log.timer.just_finished() {
+        return;
+    }
+
+    let count = |state| ants.iter().filter(|ant| ant.state == state).count();
+    let marker_count = |marker_type| {
+        markers
+            .iter()
+            .filter(|marker| marker.marker_type == marker_type)
+            .count()
+    };
+    let timestamp = log.start + chrono::Duration::milliseconds(time.elapsed().as_millis() as i64);
+    let row = [
+        timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+        format!("{:.2}", frame_time_ms),
+        format!("{:.2}", frame_time_ms),
+        ants.iter().count().to_string(),
+        count(AntState::Searching).to_string(),
+        count(AntState::Returning).to_string(),
+        markers.iter().count().to_string(),
+        marker_count(MarkerType::Food).to_string(),
+        marker_count(MarkerType::Base).to_string(),
+        count(AntState::Resting).to_string(),
+        format!("{:.3}", clock.elapsed_secs()),
+        clock.tick().to_string(),
+    ];
+    if let Err(e) = log.writer.write_record(row) {
+        eprintln!("Error writing sample: {}", e);
+    }
+}
+
+// Deep merge of overrides into base: objects merge key by key, anything else is replaced
+fn merge_json(base: &mut serde_json::Value, overrides: &serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge_json(
+                    base.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    value,
+                );
+            }
+        }
+        // No overrides given
+        (_, serde_json::Value::Null) => {}
+        (base, value) => *base = value.clone(),
+    }
+}
+
+fn build_config(spec: &RunSpec) -> Result<Config, Box<dyn std::error::Error>> {
+    let mut config = match &spec.config {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        None => serde_json::to_value(Config::default())?,
+    };
+    merge_json(&mut config, &spec.overrides);
+    Ok(serde_json::from_value(config)?)
+}
+
+struct RunResult {
+    run: String,
+    repetition: u32,
+    summary: RunSummary,
+}
+
+// Run one simulation headless until one of its end conditions is met
+fn run_headless(
+    manifest: &Manifest,
+    mut config: Config,
+    log_path: &Path,
+    summary_path: &Path,
+) -> Result<RunSummary, Box<dyn std::error::Error>> {
+    // Described before the runner's own settings are applied, so they don't show as differences
+    let metadata_config = serde_json::to_value(&config)?;
+    let conditions = &mut config.end_conditions;
+    // Other end conditions may never be met, e.g. extinction of a colony that survives, so the
+    // manifest's cap always applies
+    conditions.max_sim_secs = Some(
+        conditions
+            .max_sim_secs
+            .map_or(manifest.max_sim_secs, |secs| {
+                secs.min(manifest.max_sim_secs)
+            }),
+    );
+    conditions.exit = true;
+    conditions.summary_path = summary_path.to_string_lossy().into_owned();
+
+    let mut writer = csv::Writer::from_path(log_path)?;
+    writer.write_record([
+        "timestamp",
+        "frame_time_ms",
+        "avg_frame_time_ms",
+        "total_ants",
+        "searching_ants",
+        "returning_ants",
+        "total_markers",
+        "food_markers",
+        "base_markers",
+        "resting_ants",
+        "sim_time_secs",
+        "tick",
+    ])?;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            manifest.tick_ms,
+        )))
+        .insert_resource(config)
+        .insert_resource(SampleLog {
+            writer,
+            timer: Timer::from_seconds(manifest.sample_interval_secs, TimerMode::Repeating),
+            last_tick: Instant::now(),
+            start: chrono::Local::now().naive_local(),
+        })
+        .add_plugins(SimulationPlugin)
+        .add_systems(Last, record_samples);
+
+    while app.world.resource::<Events<AppExit>>().is_empty() {
+        app.update();
+    }
+
+    app.world.resource_mut::<SampleLog>().writer.flush()?;
+    let seed = app.world.resource::<SimRng>().seed();
+    RunMetadata::for_current_build(metadata_config, seed)
+        .write(&log_path.with_extension("json"))?;
+
+    Ok(serde_json::from_str(&std::fs::read_to_string(
+        summary_path,
+    )?)?)
+}
+
+fn write_results(path: &Path, results: &[RunResult]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([
+        "run",
+        "repetition",
+        "seed",
+        "end_reason",
+        "sim_secs",
+        "total_food_delivered",
+        "time_to_first_delivery_secs",
+        "time_to_stable_trail_secs",
+        "peak_ant_count",
+        "average_frame_time_ms",
+    ])?;
+    for result in results {
+        let summary = &result.summary;
+        writer.write_record([
+            result.run.clone(),
+            result.repetition.to_string(),
+            summary.seed.to_string(),
+            serde_json::to_value(summary.end_reason)?
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            format!("{:.2}", summary.sim_secs),
+            summary.total_food_delivered.to_string(),
+            summary
+                .time_to_first_delivery_secs
+                .map_or(String::new(), |secs| format!("{:.2}", secs)),
+            summary
+                .time_to_stable_trail_secs
+                .map_or(String::new(), |secs| format!("{:.2}", secs)),
+            summary.peak_ant_count.to_string(),
+            format!("{:.3}", summary.average_frame_time_ms),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// Mean of each summary figure over the repetitions of every run
+fn write_aggregate(
+    path: &Path,
+    manifest: &Manifest,
+    results: &[RunResult],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mean = |values: Vec<f64>| {
+        if values.is_empty() {
+            String::new()
+        } else {
+            format!("{:.2}", values.iter().sum::<f64>() / values.len() as f64)
+        }
+    };
+
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([
+        "run",
+        "repetitions",
+        "mean_sim_secs",
+        "mean_food_delivered",
+        "mean_time_to_first_delivery_secs",
+        "mean_time_to_stable_trail_secs",
+        "trails_formed",
+        "mean_peak_ant_count",
+        "mean_average_frame_time_ms",
+    ])?;
+    for spec in &manifest.runs {
+        let summaries: Vec<&RunSummary> = results
+            .iter()
+            .filter(|result| result.run == spec.name)
+            .map(|result| &result.summary)
+            .collect();
+        let first_deliveries: Vec<f64> = summaries
+            .iter()
+            .filter_map(|s| s.time_to_first_delivery_secs.map(f64::from))
+            .collect();
+        let stable_trails: Vec<f64> = summaries
+            .iter()
+            .filter_map(|s| s.time_to_stable_trail_secs.map(f64::from))
+            .collect();
+        writer.write_record([
+            spec.name.clone(),
+            summaries.len().to_string(),
+            mean(summaries.iter().map(|s| s.sim_secs as f64).collect()),
+            mean(
+                summaries
+                    .iter()
+                    .map(|s| s.total_food_delivered as f64)
+                    .collect(),
+            ),
+            mean(first_deliveries),
+            mean(stable_trails.clone()),
+            stable_trails.len().to_string(),
+            mean(summaries.iter().map(|s| s.peak_ant_count as f64).collect()),
+            mean(summaries.iter().map(|s| s.average_frame_time_ms).collect()),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let manifest: Manifest = serde_json::from_str(&std::fs::read_to_string(&args.manifest)?)?;
+
+    let logs_dir = manifest.output_dir.join("logs");
+    let summaries_dir = manifest.output_dir.join("summaries");
+    std::fs::create_dir_all(&logs_dir)?;
+    std::fs::create_dir_all(&summaries_dir)?;
+
+    let mut results = Vec::new();
+    // First repetition of each run, compared in the chart report
+    let mut comparison_logs = Vec::new();
+    for spec in &manifest.runs {
+        let config = match build_config(spec) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Skipping run {}: invalid config ({})", spec.name, e);
+                continue;
+            }
+        };
+
+        let repetitions = spec.repetitions.unwrap_or(manifest.repetitions);
+        for repetition in 0..repetitions {
+            let mut config = config.clone();
+            // Fixed seeds still give each repetition its own seed, and with the fixed tick the
+            // same repetition plays out the same every time
+            config.seed = config.seed.map(|seed| seed.wrapping_add(repetition as u64));
+
+            let run_id = format!("{}_rep{:02}", spec.name, repetition);
+            let log_path = logs_dir.join(format!("simulation_{}.csv", run_id));
+            let summary_path = summaries_dir.join(format!("{}.json", run_id));
+            println!(
+                "Running {} ({}/{})...",
+                spec.name,
+                repetition + 1,
+                repetitions
+            );
+            match run_headless(&manifest, config, &log_path, &summary_path) {
+                Ok(summary) => {
+                    if repetition == 0 {
+                        comparison_logs.push(log_path);
+                    }
+                    results.push(RunResult {
+                        run: spec.name.clone(),
+                        repetition,
+                        summary,
+                    });
+                }
+                Err(e) => eprintln!("Run {} failed: {}", run_id, e),
+            }
+        }
+    }
+
+    write_results(&manifest.output_dir.join("results.csv"), &results)?;
+    write_aggregate(
+        &manifest.output_dir.join("aggregate.csv"),
+        &manifest,
+        &results,
+    )?;
+
+    let simulations = parse_multiple_csv_files(comparison_logs)?;
+    let markdown = generate_markdown(&simulations, &["all".to_string()], XAxisType::Time);
+    let comparison_path = manifest.output_dir.join("comparison.md");
+    std::fs::write(&comparison_path, markdown)?;
+
+    println!(
+        "{} run(s) done, results in {}",
+        results.len(),
+        manifest.output_dir.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use ant_sim::network::{NetworkServerPlugin, SnapshotServer};
+use ant_sim_core::config::Config;
+use ant_sim_core::simulation::SimulationPlugin;
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+use clap::Parser;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "sim_server")]
+#[command(about = "Run the simulation headless and serve it to observers (ant-sim --connect)")]
+struct Args {
+    /// Address observers connect to
+    #[arg(long, default_value = "0.0.0.0:7878")]
+    bind: String,
+
+    /// Config file, config.json when unset
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Simulation updates per second
+    #[arg(long, default_value_t = 60.0, value_parser = positive_rate::<f64>)]
+    tick_rate: f64,
+
+    /// Snapshots sent to observers per second
+    #[arg(long, default_value_t = 20.0, value_parser = positive_rate::<f32>)]
+    snapshot_rate: f32,
+}
+
+// Rates are turned into intervals, so they must be finite and above zero
+fn positive_rate<T: std::str::FromStr + Into<f64> + Copy>(value: &str) -> Result<T, String> {
+    let rate: T = value
+        .parse()
+        .map_err(|_| format!("{} is not a number", value))?;
+    let as_f64: f64 = rate.into();
+    if as_f64.is_finite() && as_f64 > 0.0 {
+        Ok(rate)
+    } else {
+        Err(format!("{} must be above 0", value))
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let config = match &args.config {
+        Some(path) => Config::load_from(path)?,
+        None => Config::load()?,
+    };
+
+    let server = SnapshotServer::bind(&args.bind, &config, args.snapshot_rate)?;
+    println!("Serving simulation on {}", server.local_addr()?);
+
+    App::new()
+        .add_plugins(
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+                1.0 / args.tick_rate,
+            ))),
+        )
+        .insert_resource(config)
+        .insert_resource(server)
+        .add_plugins(SimulationPlugin)
+        .add_plugins(NetworkServerPlugin)
+        .run();
+    Ok(())
+}
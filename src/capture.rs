@@ -0,0 +1,364 @@
+use crate::input::{Action, InputMap};
+use ant_sim_core::config::{Config, RecordingConfig, RecordingFormat};
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::window::PrimaryWindow;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// How long the encoder waits for the next frame to be written before giving up on the rest.
+// Screenshots are saved in the background some time after they were asked for.
+const FRAME_WAIT: Duration = Duration::from_secs(5);
+
+// Screenshots of the window, taken periodically for timelapses or on demand (F12 by default)
+#[derive(Resource)]
+pub struct CaptureSettings {
+    dir: PathBuf,
+    interval: Option<Timer>,
+    frame_index: u32,
+    manual_index: u32,
+}
+
+impl CaptureSettings {
+    pub fn new(config: &Config) -> Self {
+        // One directory per run, created on the first capture
+        let now = chrono::Local::now();
+        let dir = PathBuf::from("captures").join(now.format("%Y-%m-%d_%H-%M-%S").to_string());
+
+        Self {
+            dir,
+            interval: config
+                .screenshot_interval_secs
+                .filter(|secs| *secs > 0.0)
+                .map(|secs| Timer::from_seconds(secs, TimerMode::Repeating)),
+            frame_index: 0,
+            manual_index: 0,
+        }
+    }
+
+    // Where screenshots and recordings of this run go
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn next_path(&mut self, manual: bool) -> Result<PathBuf, std::io::Error> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        // Timelapse frames are numbered contiguously so encoders can pick them up as a sequence
+        let filename = if manual {
+            self.manual_index += 1;
+            format!("manual_{:04}.png", self.manual_index)
+        } else {
+            self.frame_index += 1;
+            format!("frame_{:05}.png", self.frame_index)
+        };
+        Ok(self.dir.join(filename))
+    }
+}
+
+pub fn capture_screenshots(
+    mut capture: ResMut<CaptureSettings>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    time: Res<Time>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let interval_elapsed = match capture.interval.as_mut() {
+        Some(timer) => timer.tick(time.delta()).just_finished(),
+        None => false,
+    };
+    let manual = input_map.just_pressed(Action::Screenshot, &keyboard_input);
+    if !interval_elapsed && !manual {
+        return;
+    }
+
+    let path = match capture.next_path(manual) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to create capture directory: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = screenshot_manager.save_screenshot_to_disk(window, path) {
+        eprintln!("Failed to capture screenshot: {}", e);
+    }
+}
+
+// Records frames into a clip directory, then encodes them into a GIF or MP4 when stopped
+#[derive(Resource)]
+pub struct Recorder {
+    settings: RecordingConfig,
+    run_dir: PathBuf,
+    frame_timer: Timer,
+    // Frame directory of the clip being recorded
+    clip: Option<PathBuf>,
+    clip_index: u32,
+    frame_index: u32,
+    // Whether the last frame was asked for during this update, so it is rendered and saved
+    // only after it
+    frame_requested: bool,
+    // Clips still being encoded
+    encoding: Vec<JoinHandle<()>>,
+}
+
+impl Recorder {
+    pub fn new(config: &Config, run_dir: PathBuf) -> Self {
+        let fps = config.recording.fps.max(1);
+        Self {
+            settings: config.recording.clone(),
+            run_dir,
+            frame_timer: Timer::from_seconds(1.0 / fps as f32, TimerMode::Repeating),
+            clip: None,
+            clip_index: 0,
+            frame_index: 0,
+            frame_requested: false,
+            encoding: Vec::new(),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.clip.is_some()
+    }
+
+    fn start(&mut self) {
+        self.clip_index += 1;
+        self.frame_index = 0;
+        self.frame_timer.reset();
+        self.clip = Some(self.run_dir.join(format!("clip_{:02}", self.clip_index)));
+    }
+
+    // Encode the current clip next to its frame directory on another thread, once its frames
+    // are on disk. Frames asked for after the last render are left out when the app is exiting.
+    fn stop(&mut self, exiting: bool) {
+        let Some(frames_dir) = self.clip.take() else {
+            return;
+        };
+        let extension = match self.settings.format {
+            RecordingFormat::Gif => "gif",
+            RecordingFormat::Mp4 => "mp4",
+        };
+        let output = frames_dir.with_extension(extension);
+        let frame_count = if exiting && self.frame_requested {
+            self.frame_index - 1
+        } else {
+            self.frame_index
+        };
+        let settings = self.settings.clone();
+
+        self.encoding.retain(|handle| !handle.is_finished());
+        self.encoding.push(std::thread::spawn(move || {
+            let result = wait_for_frames(&frames_dir, frame_count)
+                .and_then(|frames| encode_clip(&frames_dir, &frames, &output, &settings));
+            match result {
+                Ok(()) => println!("Recording saved to {}", output.display()),
+                Err(e) => eprintln!("Failed to encode recording: {}", e),
+            }
+        }));
+    }
+
+    // Wait for clips still being encoded
+    fn finish(&mut self) {
+        for handle in self.encoding.drain(..) {
+            if handle.join().is_err() {
+                eprintln!("Failed to encode recording: the encoder panicked");
+            }
+        }
+    }
+
+    fn next_frame_path(&self) -> Result<Option<PathBuf>, std::io::Error> {
+        let Some(frames_dir) = &self.clip else {
+            return Ok(None);
+        };
+        std::fs::create_dir_all(frames_dir)?;
+        Ok(Some(frame_path(frames_dir, self.frame_index + 1)))
+    }
+}
+
+fn frame_path(frames_dir: &Path, index: u32) -> PathBuf {
+    frames_dir.join(format!("frame_{:05}.png", index))
+}
+
+// The clip's frames in order, once each can be read whole. Frames still missing after
+// FRAME_WAIT are left out along with every frame after them.
+fn wait_for_frames(
+    frames_dir: &Path,
+    frame_count: u32,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut frames = Vec::new();
+    for index in 1..=frame_count {
+        let path = frame_path(frames_dir, index);
+        if !wait_until_readable(&path) {
+            eprintln!(
+                "Frame {} of {} was never saved, encoding the {} before it",
+                index,
+                frames_dir.display(),
+                frames.len()
+            );
+            break;
+        }
+        frames.push(path);
+    }
+    if frames.is_empty() {
+        return Err("no frames were captured".into());
+    }
+    Ok(frames)
+}
+
+// Files still being written fail to decode until they are complete
+fn wait_until_readable(path: &Path) -> bool {
+    let deadline = Instant::now() + FRAME_WAIT;
+    while image::open(path).is_err() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    true
+}
+
+fn encode_clip(
+    frames_dir: &Path,
+    frames: &[PathBuf],
+    output: &Path,
+    settings: &RecordingConfig,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let fps = settings.fps.max(1);
+
+    match settings.format {
+        RecordingFormat::Gif => {
+            let mut encoder = None;
+            // GIF delays are in hundredths of a second
+            let delay = (100 / fps).max(1) as u16;
+            for path in frames {
+                let mut frame = image::open(path)?.to_rgba8();
+                if let Some(width) = settings.width {
+                    let height =
+                        (frame.height() as u64 * width as u64 / frame.width() as u64) as u32;
+                    frame = image::imageops::resize(
+                        &frame,
+                        width,
+                        height.max(1),
+                        image::imageops::FilterType::Triangle,
+                    );
+                }
+                let (width, height) = (frame.width() as u16, frame.height() as u16);
+                let encoder = match encoder.as_mut() {
+                    Some(encoder) => encoder,
+                    None => {
+                        let mut new_encoder =
+                            gif::Encoder::new(File::create(output)?, width, height, &[])?;
+                        new_encoder.set_repeat(gif::Repeat::Infinite)?;
+                        encoder.insert(new_encoder)
+                    }
+                };
+                let mut gif_frame = gif::Frame::from_rgba_speed(width, height, frame.as_mut(), 10);
+                gif_frame.delay = delay;
+                encoder.write_frame(&gif_frame)?;
+            }
+        }
+        RecordingFormat::Mp4 => {
+            // Even dimensions are required by yuv420p
+            let scale = match settings.width {
+                Some(width) => format!("scale={}:-2", width - width % 2),
+                None => "scale=trunc(iw/2)*2:trunc(ih/2)*2".to_string(),
+            };
+            let status = Command::new("ffmpeg")
+                .arg("-y")
+                .args(["-loglevel", "error"])
+                .args(["-framerate", &fps.to_string()])
+                .arg("-i")
+                .arg(frames_dir.join("frame_%05d.png"))
+                .args(["-frames:v", &frames.len().to_string()])
+                .args(["-vf", &scale, "-pix_fmt", "yuv420p"])
+                .arg(output)
+                .status()
+                .map_err(|e| format!("could not run ffmpeg: {}", e))?;
+            if !status.success() {
+                return Err(format!("ffmpeg exited with {}", status).into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn record_frames(
+    mut recorder: ResMut<Recorder>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    time: Res<Time>,
+) {
+    recorder.frame_requested = false;
+    if input_map.just_pressed(Action::ToggleRecording, &keyboard_input) {
+        if recorder.is_recording() {
+            recorder.stop(false);
+        } else {
+            recorder.start();
+        }
+    }
+
+    if !recorder.is_recording() || !recorder.frame_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    match recorder.next_frame_path() {
+        Ok(Some(path)) => {
+            // Skip the frame if another capture already claimed it, keeping frame numbers contiguous
+            if screenshot_manager
+                .save_screenshot_to_disk(window, path)
+                .is_ok()
+            {
+                recorder.frame_index += 1;
+                recorder.frame_requested = true;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to create recording directory: {}", e),
+    }
+}
+
+// Encode a recording still in progress when the window closes and wait for every clip to be
+// encoded before the app exits
+pub fn finish_recording(mut recorder: ResMut<Recorder>, mut exit_events: EventReader<AppExit>) {
+    if exit_events.read().next().is_some() {
+        recorder.stop(true);
+        recorder.finish();
+    }
+}
+
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        let config = app.world.resource::<Config>();
+        let capture = CaptureSettings::new(config);
+        let mut recorder = Recorder::new(config, capture.dir.clone());
+        if config.recording.enabled {
+            recorder.start();
+        }
+        app.insert_resource(capture)
+            .insert_resource(recorder)
+            .add_systems(
+                Update,
+                (
+                    capture_screenshots,
+                    record_frames.after(capture_screenshots),
+                ),
+            )
+            .add_systems(Last, finish_recording);
+    }
+}
@@ -1,6 +1,75 @@
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
+// Sidecar written next to each log as <run name>.json describing how the run was configured
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub app_version: String,
+    /// Commit the binary was built from, when built in a git checkout
+    pub git_hash: Option<String>,
+    /// Seed actually used, also when the config left it random
+    pub seed: Option<u64>,
+    /// Kept as plain JSON so logs from older config versions still load
+    pub config: serde_json::Value,
+}
+
+impl RunMetadata {
+    pub fn for_current_build(config: serde_json::Value, seed: u64) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: option_env!("GIT_HASH").map(str::to_string),
+            seed: Some(seed),
+            config,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // Config values keyed by dotted path (e.g. "combat.soldier_kill_chance"), plus the seed
+    pub fn parameters(&self) -> BTreeMap<String, String> {
+        let mut parameters = BTreeMap::new();
+        flatten_json("", &self.config, &mut parameters);
+        if let Some(seed) = self.seed {
+            parameters.insert("seed".to_string(), seed.to_string());
+        }
+        parameters
+    }
+}
+
+fn flatten_json(prefix: &str, value: &serde_json::Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_json(&path, value, out);
+            }
+        }
+        serde_json::Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        // Config floats are f32, shown without the noise of their f64 conversion
+        serde_json::Value::Number(n) if n.is_f64() => {
+            let value = n.as_f64().unwrap_or_default() as f32;
+            out.insert(prefix.to_string(), value.to_string());
+        }
+        // Arrays such as locations are compared as a whole
+        other => {
+            out.insert(prefix.to_string(), other.to_string());
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogEntry {
     pub timestamp: String,
@@ -12,12 +81,17 @@ pub struct LogEntry {
     pub total_markers: usize,
     pub food_markers: usize,
     pub base_markers: usize,
+    pub resting_ants: usize,
+    // Simulated time and tick, absent from logs written before the simulation clock
+    pub sim_time_secs: Option<f32>,
+    pub tick: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SimulationData {
     pub filename: String,
     pub entries: Vec<LogEntry>,
+    pub metadata: Option<RunMetadata>,
 }
 
 impl SimulationData {
@@ -28,11 +102,78 @@ impl SimulationData {
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
     }
+
+    // Label for comparisons: the given differing parameters when known, the file name otherwise
+    pub fn label(&self, differing: &[String]) -> String {
+        if let (Some(metadata), false) = (&self.metadata, differing.is_empty()) {
+            let parameters = metadata.parameters();
+            return differing
+                .iter()
+                .map(|key| {
+                    let value = parameters.get(key).map_or("-", String::as_str);
+                    format!("{}={}", key, value)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+        }
+        self.filename
+            .strip_suffix(".csv")
+            .unwrap_or(&self.filename)
+            .to_string()
+    }
+}
+
+// Parameters whose values are not the same in every run, empty unless all runs have metadata
+pub fn differing_parameters(simulations: &[SimulationData]) -> Vec<String> {
+    let Some(all_parameters) = simulations
+        .iter()
+        .map(|sim| sim.metadata.as_ref().map(RunMetadata::parameters))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Vec::new();
+    };
+
+    let mut keys: Vec<&String> = all_parameters.iter().flat_map(|p| p.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| {
+            let first = all_parameters[0].get(*key);
+            all_parameters.iter().any(|p| p.get(*key) != first)
+        })
+        .cloned()
+        .collect()
+}
+
+pub fn metadata_path(log_path: &Path) -> Option<PathBuf> {
+    let file_name = log_path.file_name()?.to_str()?;
+    Some(log_path.with_file_name(format!("{}.json", run_name(file_name))))
+}
+
+fn load_metadata(log_path: &Path) -> Option<RunMetadata> {
+    let contents = std::fs::read_to_string(metadata_path(log_path)?).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(metadata) => Some(metadata),
+        Err(e) => {
+            eprintln!(
+                "Warning: Ignoring unreadable metadata for {}: {}",
+                log_path.display(),
+                e
+            );
+            None
+        }
+    }
 }
 
 pub fn parse_csv_file(path: &Path) -> Result<SimulationData, Box<dyn std::error::Error>> {
     let file = File::open(path)?;
-    let mut rdr = csv::Reader::from_reader(file);
+    // Closed segments of rotated logs may be gzipped
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut rdr = csv::Reader::from_reader(reader);
 
     let filename = path
         .file_name()
@@ -40,6 +181,12 @@ pub fn parse_csv_file(path: &Path) -> Result<SimulationData, Box<dyn std::error:
         .unwrap_or("unknown")
         .to_string();
 
+    // Columns added after the fixed ones are found by name, as colony columns may follow
+    let headers = rdr.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+    let sim_time_column = column("sim_time_secs");
+    let tick_column = column("tick");
+
     let mut entries = Vec::new();
 
     for result in rdr.records() {
@@ -59,22 +206,45 @@ pub fn parse_csv_file(path: &Path) -> Result<SimulationData, Box<dyn std::error:
             total_markers: record.get(6).unwrap_or("0").parse().unwrap_or(0),
             food_markers: record.get(7).unwrap_or("0").parse().unwrap_or(0),
             base_markers: record.get(8).unwrap_or("0").parse().unwrap_or(0),
+            // Older logs predate the resting state and have no such column
+            resting_ants: record.get(9).unwrap_or("0").parse().unwrap_or(0),
+            sim_time_secs: sim_time_column
+                .and_then(|i| record.get(i))
+                .and_then(|value| value.parse().ok()),
+            tick: tick_column
+                .and_then(|i| record.get(i))
+                .and_then(|value| value.parse().ok()),
         };
 
         entries.push(entry);
     }
 
-    Ok(SimulationData { filename, entries })
+    Ok(SimulationData {
+        filename,
+        entries,
+        metadata: load_metadata(path),
+    })
 }
 
 pub fn parse_multiple_csv_files(
     paths: Vec<PathBuf>,
 ) -> Result<Vec<SimulationData>, Box<dyn std::error::Error>> {
-    let mut results = Vec::new();
+    let mut results: Vec<SimulationData> = Vec::new();
 
     for path in paths {
         match parse_csv_file(&path) {
-            Ok(data) => results.push(data),
+            Ok(data) => {
+                // Consecutive segments of a rotated log are joined back into one run
+                if let Some(previous) = results.last_mut() {
+                    let run = run_name(&data.filename);
+                    if run_name(&previous.filename) == run {
+                        previous.filename = format!("{}.csv", run);
+                        previous.entries.extend(data.entries);
+                        continue;
+                    }
+                }
+                results.push(data);
+            }
             Err(e) => eprintln!("Warning: Failed to parse {}: {}", path.display(), e),
         }
     }
@@ -82,12 +252,36 @@ pub fn parse_multiple_csv_files(
     Ok(results)
 }
 
+// Log file name without extensions and rotation suffix:
+// simulation_<timestamp>_part001.csv.gz -> simulation_<timestamp>
+pub fn run_name(file_name: &str) -> &str {
+    let name = file_name.strip_suffix(".gz").unwrap_or(file_name);
+    let name = name.strip_suffix(".csv").unwrap_or(name);
+    match name.rsplit_once("_part") {
+        Some((run, segment))
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            run
+        }
+        _ => name,
+    }
+}
+
 pub fn normalize_time_axis(entries: &[LogEntry]) -> Vec<f32> {
     if entries.is_empty() {
         return Vec::new();
     }
 
-    // Parse first timestamp as reference
+    // Simulated time when every entry has it, so pauses and slow frames don't stretch the axis
+    if let Some(sim_times) = entries
+        .iter()
+        .map(|entry| entry.sim_time_secs)
+        .collect::<Option<Vec<f32>>>()
+    {
+        return sim_times.iter().map(|t| t - sim_times[0]).collect();
+    }
+
+    // Otherwise parse first timestamp as reference
     let first_timestamp = &entries[0].timestamp;
     let first_time = parse_timestamp(first_timestamp);
 
@@ -126,14 +320,15 @@ pub fn find_all_log_files(logs_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::
 
         if path.is_file() {
             if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                if file_name.starts_with("simulation_") && file_name.ends_with(".csv") {
+                let is_log = file_name.ends_with(".csv") || file_name.ends_with(".csv.gz");
+                if file_name.starts_with("simulation_") && is_log {
                     log_files.push(path);
                 }
             }
         }
     }
 
-    // Sort by filename (which includes timestamp) for consistent ordering
+    // Sort by filename (which includes timestamp and segment number) for consistent ordering
     log_files.sort();
 
     Ok(log_files)
@@ -1,4 +1,18 @@
-use crate::chart_data::{LogEntry, SimulationData};
+use crate::chart_data::{differing_parameters, LogEntry, SimulationData};
+
+// Parameters always listed in the comparison table, differing ones are added after them
+const KEY_PARAMETERS: [&str; 10] = [
+    "seed",
+    "map_size",
+    "initial_ant_count",
+    "spawn_rate",
+    "food_quantity",
+    "marker_spawn_interval",
+    "marker_lifetime",
+    "exploration_rate",
+    "recruitment_intensity",
+    "rest_duration",
+];
 
 #[derive(Clone)]
 pub enum XAxisType {
@@ -59,10 +73,19 @@ pub fn generate_ant_charts(simulations: &[SimulationData], x_axis_type: XAxisTyp
         "Returning Ants",
         "Returning Ants",
         simulations,
-        x_axis_type,
+        x_axis_type.clone(),
         |entry| entry.returning_ants as f32,
     ));
 
+    // Resting Ants chart
+    charts.push(generate_chart(
+        "Resting Ants",
+        "Resting Ants",
+        simulations,
+        x_axis_type,
+        |entry| entry.resting_ants as f32,
+    ));
+
     charts
 }
 
@@ -181,16 +204,14 @@ where
         y_max as i32
     );
 
-    // Add lines for each simulation
+    // Add lines for each simulation, labelled by the parameters that set the runs apart
+    let differing = differing_parameters(simulations);
     for (idx, values) in all_values.iter().enumerate() {
         let label = if simulations.len() > 1 {
-            // Use filename without extension as label
-            let sim_name = &simulations[idx].filename;
-            let label = sim_name
-                .strip_suffix(".csv")
-                .unwrap_or(sim_name)
-                .to_string();
-            format!("\"{}\"", label)
+            format!(
+                "\"{}\"",
+                simulations[idx].label(&differing).replace('"', "'")
+            )
         } else {
             String::new()
         };
@@ -202,6 +223,70 @@ where
     chart
 }
 
+// Markdown table of each run's configuration, with the parameters that differ marked.
+// None when no run has metadata to compare.
+pub fn generate_config_table(simulations: &[SimulationData]) -> Option<String> {
+    if simulations.iter().all(|sim| sim.metadata.is_none()) {
+        return None;
+    }
+
+    let differing = differing_parameters(simulations);
+    let mut rows: Vec<String> = KEY_PARAMETERS.iter().map(|key| key.to_string()).collect();
+    for key in &differing {
+        if !rows.contains(key) {
+            rows.push(key.clone());
+        }
+    }
+    let parameters: Vec<_> = simulations
+        .iter()
+        .map(|sim| sim.metadata.as_ref().map(|metadata| metadata.parameters()))
+        .collect();
+
+    let mut table = String::from("| Parameter |");
+    for sim in simulations {
+        table.push_str(&format!(
+            " {} |",
+            sim.filename.strip_suffix(".csv").unwrap_or(&sim.filename)
+        ));
+    }
+    table.push_str(" Differs |\n|---|");
+    table.push_str(&"---|".repeat(simulations.len()));
+    table.push_str("---|\n");
+
+    // Versions first, so differences in code are as visible as differences in config
+    table.push_str("| version |");
+    for sim in simulations {
+        let version =
+            sim.metadata
+                .as_ref()
+                .map_or("-".to_string(), |metadata| match &metadata.git_hash {
+                    Some(hash) => format!("{} ({})", metadata.app_version, hash),
+                    None => metadata.app_version.clone(),
+                });
+        table.push_str(&format!(" {} |", version));
+    }
+    table.push_str("  |\n");
+
+    for key in rows {
+        let is_different = differing.contains(&key);
+        if is_different {
+            table.push_str(&format!("| **{}** |", key));
+        } else {
+            table.push_str(&format!("| {} |", key));
+        }
+        for run_parameters in &parameters {
+            let value = run_parameters
+                .as_ref()
+                .and_then(|p| p.get(&key))
+                .map_or("-", String::as_str);
+            table.push_str(&format!(" {} |", value.replace('|', "\\|")));
+        }
+        table.push_str(if is_different { " yes |\n" } else { "  |\n" });
+    }
+
+    Some(table)
+}
+
 pub fn generate_markdown(
     simulations: &[SimulationData],
     metrics: &[String],
@@ -223,6 +308,12 @@ pub fn generate_markdown(
             markdown.push_str(&format!("- {}\n", sim.filename));
         }
         markdown.push_str("\n");
+
+        if let Some(table) = generate_config_table(simulations) {
+            markdown.push_str("## Configuration\n\n");
+            markdown.push_str(&table);
+            markdown.push('\n');
+        }
     }
 
     // Performance Metrics
@@ -244,7 +335,12 @@ pub fn generate_markdown(
     if metrics.contains(&"all".to_string()) || metrics.contains(&"ants".to_string()) {
         markdown.push_str("## Ant Metrics\n\n");
         let charts = generate_ant_charts(simulations, x_axis_type.clone());
-        let chart_titles = ["Total Ants", "Searching Ants", "Returning Ants"];
+        let chart_titles = [
+            "Total Ants",
+            "Searching Ants",
+            "Returning Ants",
+            "Resting Ants",
+        ];
         for (idx, chart) in charts.iter().enumerate() {
             if idx < chart_titles.len() {
                 markdown.push_str(&format!("### {}\n\n", chart_titles[idx]));
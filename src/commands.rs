@@ -0,0 +1,305 @@
+use crate::entity_export::{entity_points, write_csv, write_geojson};
+use crate::network::{SnapshotQueries, WorldSnapshot};
+use crate::pheromones::{capture_fields, write_fields};
+use ant_sim_core::ant::{spawn_ant, Caste};
+use ant_sim_core::base::Base;
+use ant_sim_core::clock::SimClock;
+use ant_sim_core::colony::Colony;
+use ant_sim_core::config::Config;
+use ant_sim_core::grid::GridMap;
+use ant_sim_core::marker::Marker;
+use ant_sim_core::rng::SimRng;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const SNAPSHOT_DIR: &str = "snapshots";
+// Used when the run isn't logged and has no folder of its own
+const DEFAULT_EXPORT_DIR: &str = "exports";
+
+pub const USAGE: &str = "spawn ants <count>\n\
+                         set <setting> <value>   (e.g. set sensor.range 96)\n\
+                         clear markers\n\
+                         snapshot save <name>\n\
+                         export pheromones [csv] [npy]\n\
+                         export entities [csv]\n\
+                         stress <count>";
+
+// Changes to a running simulation, typed into the console or sent by other front ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum SimCommand {
+    // Workers spread over the bases of every colony
+    SpawnAnts {
+        count: u32,
+    },
+    // Replace a config value, nested settings joined with dots
+    Set {
+        key: String,
+        value: serde_json::Value,
+    },
+    ClearMarkers,
+    // Write the config and the state of every entity to snapshots/<name>.json
+    SaveSnapshot {
+        name: String,
+    },
+    // Write each marker type's intensity per grid cell as a grayscale PNG, and optionally as
+    // CSV and NumPy arrays
+    ExportPheromones {
+        csv: bool,
+        #[serde(default)]
+        npy: bool,
+    },
+    // Write every entity as a point with its state to GeoJSON, and optionally CSV
+    ExportEntities {
+        csv: bool,
+    },
+    // Spawn workers like SpawnAnts and switch to stress mode
+    Stress {
+        count: u32,
+    },
+}
+
+impl FromStr for SimCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["spawn", "ants", count] => count
+                .parse()
+                .map(|count| SimCommand::SpawnAnts { count })
+                .map_err(|_| format!("Not an ant count: {}", count)),
+            ["set", key, value @ ..] if !value.is_empty() => {
+                let value = value.join(" ");
+                Ok(SimCommand::Set {
+                    key: key.to_string(),
+                    // Anything that isn't JSON is taken as a plain string
+                    value: serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value)),
+                })
+            }
+            ["clear", "markers"] => Ok(SimCommand::ClearMarkers),
+            ["snapshot", "save", name] => Ok(SimCommand::SaveSnapshot {
+                name: name.to_string(),
+            }),
+            ["export", "pheromones", formats @ ..]
+                if formats.iter().all(|format| ["csv", "npy"].contains(format)) =>
+            {
+                Ok(SimCommand::ExportPheromones {
+                    csv: formats.contains(&"csv"),
+                    npy: formats.contains(&"npy"),
+                })
+            }
+            ["export", "entities"] => Ok(SimCommand::ExportEntities { csv: false }),
+            ["export", "entities", "csv"] => Ok(SimCommand::ExportEntities { csv: true }),
+            ["stress", count] => count
+                .parse()
+                .map(|count| SimCommand::Stress { count })
+                .map_err(|_| format!("Not an ant count: {}", count)),
+            _ => Err(format!("Unknown command: {}", line.trim())),
+        }
+    }
+}
+
+// Folder of the current run that exports are written to
+#[derive(Resource, Debug, Clone)]
+pub struct ExportDir(pub PathBuf);
+
+// Present while a stress test runs. Front ends drop visual detail that costs frame time and
+// report the simulation's throughput instead.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct StressMode;
+
+// Asks for a command to be run at the end of the frame
+#[derive(Event, Debug, Clone)]
+pub struct CommandRequest(pub SimCommand);
+
+// What came of a command, as a line of text for whoever sent it
+#[derive(Event, Debug, Clone)]
+pub struct CommandOutput(pub Result<String, String>);
+
+fn spawn_ants(
+    In(count): In<u32>,
+    mut commands: Commands,
+    bases: Query<(&Transform, &Colony), With<Base>>,
+    config: Res<Config>,
+    mut rng: ResMut<SimRng>,
+) -> Result<String, String> {
+    let bases: Vec<(Vec3, Colony)> = bases
+        .iter()
+        .map(|(transform, colony)| (transform.translation, *colony))
+        .collect();
+    if bases.is_empty() {
+        return Err("No base to spawn ants at".to_string());
+    }
+    for i in 0..count as usize {
+        let (translation, colony) = bases[i % bases.len()];
+        spawn_ant(
+            &mut commands,
+            &config,
+            &mut *rng,
+            colony,
+            Caste::Worker,
+            translation,
+        );
+    }
+    Ok(format!("Spawned {} ants", count))
+}
+
+// Round trip through JSON so any setting can be changed, checked like a config file.
+// Settings only read at startup keep their effect until the next run.
+fn set_config(
+    In((key, value)): In<(String, serde_json::Value)>,
+    mut config: ResMut<Config>,
+) -> Result<String, String> {
+    let mut json = serde_json::to_value(&*config).map_err(|e| e.to_string())?;
+    let mut target = &mut json;
+    for part in key.split('.') {
+        target = target
+            .get_mut(part)
+            .ok_or_else(|| format!("Unknown setting: {}", key))?;
+    }
+    *target = value.clone();
+    *config = serde_json::from_value(json).map_err(|e| format!("Invalid value: {}", e))?;
+    Ok(format!("{} = {}", key, value))
+}
+
+fn clear_markers(
+    mut commands: Commands,
+    markers: Query<Entity, With<Marker>>,
+    mut grid_map: ResMut<GridMap>,
+) -> Result<String, String> {
+    let mut count = 0;
+    for entity in markers.iter() {
+        commands.entity(entity).despawn();
+        count += 1;
+    }
+    grid_map.clear();
+    Ok(format!("Cleared {} markers", count))
+}
+
+#[derive(Serialize)]
+struct SavedSnapshot<'a> {
+    config: &'a Config,
+    world: WorldSnapshot,
+}
+
+fn save_snapshot(
+    In(name): In<String>,
+    snapshot: SnapshotQueries,
+    config: Res<Config>,
+) -> Result<String, String> {
+    let path = PathBuf::from(SNAPSHOT_DIR).join(format!("{}.json", name));
+    let saved = SavedSnapshot {
+        config: &config,
+        world: snapshot.capture(),
+    };
+    std::fs::create_dir_all(SNAPSHOT_DIR)
+        .and_then(|()| std::fs::File::create(&path))
+        .map_err(|e| e.to_string())
+        .and_then(|file| serde_json::to_writer(file, &saved).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Could not save {}: {}", path.display(), e))?;
+    Ok(format!(
+        "Saved {} entities to {}",
+        saved.world.entities.len(),
+        path.display()
+    ))
+}
+
+// Folder exports go to, the run's own when it is logged
+fn output_dir(export_dir: Option<Res<ExportDir>>) -> PathBuf {
+    export_dir.map_or_else(|| PathBuf::from(DEFAULT_EXPORT_DIR), |dir| dir.0.clone())
+}
+
+fn export_pheromones(
+    In((csv, npy)): In<(bool, bool)>,
+    markers: Query<&Marker>,
+    grid_map: Res<GridMap>,
+    clock: Res<SimClock>,
+    export_dir: Option<Res<ExportDir>>,
+) -> Result<String, String> {
+    let dir = output_dir(export_dir);
+    let fields = capture_fields(grid_map.size(), markers.iter());
+    let prefix = format!("pheromones_tick{:07}", clock.tick());
+    let written = write_fields(&dir, &prefix, &fields, csv, npy)
+        .map_err(|e| format!("Could not export to {}: {}", dir.display(), e))?;
+    Ok(format!(
+        "Wrote {} files to {}",
+        written.len(),
+        dir.display()
+    ))
+}
+
+fn export_entities(
+    In(csv): In<bool>,
+    snapshot: SnapshotQueries,
+    grid_map: Res<GridMap>,
+    clock: Res<SimClock>,
+    export_dir: Option<Res<ExportDir>>,
+) -> Result<String, String> {
+    let dir = output_dir(export_dir);
+    let points = entity_points(&snapshot.capture(), grid_map.grid_type());
+    let name = format!("entities_tick{:07}", clock.tick());
+    let mut written = vec![dir.join(format!("{}.geojson", name))];
+    if csv {
+        written.push(dir.join(format!("{}.csv", name)));
+    }
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| e.to_string())
+        .and_then(|()| write_geojson(&written[0], &points).map_err(|e| e.to_string()))
+        .and_then(|()| match written.get(1) {
+            Some(path) => write_csv(path, &points).map_err(|e| e.to_string()),
+            None => Ok(()),
+        })
+        .map_err(|e| format!("Could not export to {}: {}", dir.display(), e))?;
+    Ok(format!(
+        "Wrote {} entities to {} files in {}",
+        points.len(),
+        written.len(),
+        dir.display()
+    ))
+}
+
+fn stress(world: &mut World, count: u32) -> Result<String, String> {
+    let spawned = world.run_system_once_with(count, spawn_ants)?;
+    world.insert_resource(StressMode);
+    Ok(format!("{}, stress mode on", spawned))
+}
+
+pub fn run_command(world: &mut World, command: SimCommand) -> Result<String, String> {
+    match command {
+        SimCommand::SpawnAnts { count } => world.run_system_once_with(count, spawn_ants),
+        SimCommand::Set { key, value } => world.run_system_once_with((key, value), set_config),
+        SimCommand::ClearMarkers => world.run_system_once(clear_markers),
+        SimCommand::SaveSnapshot { name } => world.run_system_once_with(name, save_snapshot),
+        SimCommand::ExportPheromones { csv, npy } => {
+            world.run_system_once_with((csv, npy), export_pheromones)
+        }
+        SimCommand::ExportEntities { csv } => world.run_system_once_with(csv, export_entities),
+        SimCommand::Stress { count } => stress(world, count),
+    }
+}
+
+// Run the commands requested this frame, answering each with a CommandOutput
+pub fn run_requested_commands(world: &mut World) {
+    let requests: Vec<CommandRequest> = world
+        .resource_mut::<Events<CommandRequest>>()
+        .drain()
+        .collect();
+    for CommandRequest(command) in requests {
+        let output = run_command(world, command);
+        world.send_event(CommandOutput(output));
+    }
+}
+
+pub struct SimCommandsPlugin;
+
+impl Plugin for SimCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CommandRequest>()
+            .add_event::<CommandOutput>()
+            .add_systems(Last, run_requested_commands);
+    }
+}
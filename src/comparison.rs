@@ -0,0 +1,337 @@
+use crate::localization::Locale;
+use crate::mirror::{MirrorCounts, WorldMirror};
+use crate::theme::{Theme, ThemedNode, ThemedSprite};
+use crate::visuals::{marker_layer_image, MainCamera};
+use ant_sim_core::config::Config;
+use ant_sim_core::grid::{GridType, GRID_CELL_SIZE};
+use ant_sim_core::simulation::SimulationPlugin;
+use bevy::app::{AppLabel, SubApp};
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+
+// Empty cells left between the two maps
+const MAP_GAP_CELLS: f32 = 4.0;
+
+// Second simulation, run in its own world next to the main one
+#[derive(AppLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ComparisonApp;
+
+// Where the comparison world is drawn: to the right of the main map
+#[derive(Resource)]
+pub struct ComparisonView {
+    offset: Vec2,
+    label: String,
+    map_size: (u32, u32),
+    grid_type: GridType,
+}
+
+// Sprites standing in for the comparison world's entities
+#[derive(Resource, Default)]
+pub struct ComparisonMirror(WorldMirror);
+
+// Counts shown in the comparison world's stats panel
+#[derive(Resource, Default)]
+pub struct ComparisonStats(MirrorCounts);
+
+#[derive(Component)]
+pub struct ComparisonCamera;
+
+#[derive(Component)]
+pub struct ComparisonMarkerLayer {
+    image: Handle<Image>,
+}
+
+#[derive(Component)]
+pub struct ComparisonStatsText;
+
+// Copy the comparison world's state into the main world. Runs every frame before the
+// comparison world is updated, so it is drawn one frame behind.
+fn mirror_comparison_world(main_world: &mut World, comparison_app: &mut App) {
+    let Some(offset) = main_world
+        .get_resource::<ComparisonView>()
+        .map(|view| view.offset)
+    else {
+        return;
+    };
+    let layer = main_world
+        .query::<&ComparisonMarkerLayer>()
+        .get_single(main_world)
+        .map(|layer| layer.image.clone())
+        .ok();
+
+    main_world.resource_scope(|main_world, mut mirror: Mut<ComparisonMirror>| {
+        let counts = mirror.0.sync(
+            main_world,
+            &mut comparison_app.world,
+            offset,
+            layer.as_ref(),
+        );
+        main_world.insert_resource(ComparisonStats(counts));
+    });
+}
+
+// Background, marker layer, camera and stats panel of the comparison world
+fn setup_comparison_view(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    view: Res<ComparisonView>,
+    theme: Res<Theme>,
+) {
+    let map_size = Vec2::new(view.map_size.0 as f32, view.map_size.1 as f32) * GRID_CELL_SIZE;
+    let center = view.offset + map_size / 2.0;
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.map,
+                custom_size: Some(map_size),
+                ..default()
+            },
+            transform: Transform::from_translation(center.extend(-1.0)),
+            ..default()
+        },
+        ThemedSprite::Map,
+    ));
+
+    let (image, rect) = marker_layer_image(view.grid_type, view.map_size);
+    let image = images.add(image);
+    commands.spawn((
+        ComparisonMarkerLayer {
+            image: image.clone(),
+        },
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(rect.size()),
+                ..default()
+            },
+            texture: image,
+            transform: Transform::from_translation((view.offset + rect.center()).extend(-0.1)),
+            ..default()
+        },
+    ));
+
+    // Right half of the window, kept in step with the main camera by follow_main_camera
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                ..default()
+            },
+            ..default()
+        },
+        UiCameraConfig { show_ui: false },
+        ComparisonCamera,
+    ));
+
+    // Full-window camera drawing only the UI, so panels aren't squeezed into one half
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: 2,
+                ..default()
+            },
+            camera_2d: Camera2d {
+                clear_color: bevy::core_pipeline::clear_color::ClearColorConfig::None,
+            },
+            ..default()
+        },
+        RenderLayers::none(),
+    ));
+
+    // Stats panel of the comparison world, bottom-left of the right half
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(50.0),
+                    bottom: Val::Px(10.0),
+                    margin: UiRect::left(Val::Px(10.0)),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: theme.panel.into(),
+                ..default()
+            },
+            ThemedNode::Panel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ),
+                ComparisonStatsText,
+            ));
+        });
+}
+
+// The main camera only draws the UI through the dedicated UI camera
+fn hide_ui_on_main_camera(
+    mut commands: Commands,
+    cameras: Query<Entity, (Added<MainCamera>, Without<UiCameraConfig>)>,
+) {
+    for entity in cameras.iter() {
+        commands
+            .entity(entity)
+            .insert(UiCameraConfig { show_ui: false });
+    }
+}
+
+// Split the window between the main camera (left) and the comparison camera (right)
+fn update_split_viewports(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Camera, Has<MainCamera>, Has<ComparisonCamera>)>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let half_width = (window.physical_width() / 2).max(1);
+    let height = window.physical_height().max(1);
+
+    for (mut camera, is_main, is_comparison) in cameras.iter_mut() {
+        if !is_main && !is_comparison {
+            continue;
+        }
+        let x = if is_comparison { half_width } else { 0 };
+        let position = UVec2::new(x, 0);
+        let size = UVec2::new(half_width, height);
+        let unchanged = camera.viewport.as_ref().is_some_and(|viewport| {
+            viewport.physical_position == position && viewport.physical_size == size
+        });
+        if !unchanged {
+            camera.viewport = Some(Viewport {
+                physical_position: position,
+                physical_size: size,
+                ..default()
+            });
+        }
+    }
+}
+
+// Show the same part of both maps side by side
+fn follow_main_camera(
+    main_camera: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    mut cameras: Query<
+        (
+            &mut Transform,
+            &mut OrthographicProjection,
+            Has<ComparisonCamera>,
+        ),
+        Without<MainCamera>,
+    >,
+    view: Res<ComparisonView>,
+) {
+    let Ok((main_transform, main_projection)) = main_camera.get_single() else {
+        return;
+    };
+    for (mut transform, mut projection, is_comparison) in cameras.iter_mut() {
+        if !is_comparison {
+            continue;
+        }
+        transform.translation = main_transform.translation + view.offset.extend(0.0);
+        projection.scale = main_projection.scale;
+        projection.scaling_mode = main_projection.scaling_mode.clone();
+    }
+}
+
+fn update_comparison_stats(
+    stats: Res<ComparisonStats>,
+    view: Res<ComparisonView>,
+    mut text: Query<&mut Text, With<ComparisonStatsText>>,
+    locale: Res<Locale>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let [searching, returning, resting, carrying] = stats.0.ants_by_state;
+    let [base, food, danger] = stats.0.markers_by_type;
+    text.sections[0].value = format!(
+        "{}\n\
+         \n\
+         {}: {}\n\
+         - {}: {}\n\
+         - {}: {}\n\
+         - {}: {}\n\
+         - {}: {}\n\
+         \n\
+         {}: {}\n\
+         - {}: {}\n\
+         - {}: {}\n\
+         - {}: {}\n\
+         \n\
+         {}: {}",
+        view.label,
+        locale.ants,
+        searching + returning + resting + carrying,
+        locale.searching,
+        searching,
+        locale.returning,
+        returning,
+        locale.resting,
+        resting,
+        locale.carrying_corpses,
+        carrying,
+        locale.markers,
+        base + food + danger,
+        locale.base,
+        base,
+        locale.food,
+        food,
+        locale.danger,
+        danger,
+        locale.food_left,
+        stats.0.food_left
+    );
+}
+
+// Runs a second simulation with another config beside the main one, in split screen
+pub struct ComparisonPlugin {
+    pub config: Config,
+    pub label: String,
+}
+
+impl Plugin for ComparisonPlugin {
+    fn build(&self, app: &mut App) {
+        // Place the comparison map right of the wider of the two maps
+        let main_map_width = app.world.resource::<Config>().map_size.0;
+        let widest = main_map_width.max(self.config.map_size.0) as f32;
+        let view = ComparisonView {
+            offset: Vec2::new((widest + MAP_GAP_CELLS) * GRID_CELL_SIZE, 0.0),
+            label: self.label.clone(),
+            map_size: self.config.map_size,
+            grid_type: self.config.grid_type,
+        };
+
+        let mut comparison_app = App::new();
+        comparison_app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(self.config.clone())
+            .add_plugins(SimulationPlugin);
+
+        app.insert_resource(view)
+            .init_resource::<ComparisonMirror>()
+            .init_resource::<ComparisonStats>()
+            .init_resource::<Locale>()
+            .add_systems(Startup, setup_comparison_view)
+            .add_systems(
+                Update,
+                (
+                    hide_ui_on_main_camera,
+                    update_split_viewports,
+                    follow_main_camera.after(crate::visuals::camera_movement),
+                    update_comparison_stats,
+                ),
+            );
+        app.insert_sub_app(
+            ComparisonApp,
+            SubApp::new(comparison_app, mirror_comparison_world),
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::input::{Action, InputMap};
+use crate::theme::{Theme, ThemedNode};
+use ant_sim::commands::{CommandOutput, CommandRequest, SimCommand, USAGE};
+use bevy::prelude::*;
+use bevy::window::ReceivedCharacter;
+
+// Lines of history kept above the input line
+const CONSOLE_HISTORY: usize = 12;
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    input: String,
+    history: Vec<String>,
+}
+
+impl Console {
+    fn print(&mut self, line: String) {
+        self.history.extend(line.lines().map(str::to_string));
+        let excess = self.history.len().saturating_sub(CONSOLE_HISTORY);
+        self.history.drain(..excess);
+    }
+}
+
+#[derive(Component)]
+pub struct ConsolePanel;
+
+#[derive(Component)]
+pub struct ConsoleText;
+
+pub fn setup_console(mut commands: Commands, theme: Res<Theme>) {
+    // Across the top of the window, hidden until toggled
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: theme.panel.into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            ConsolePanel,
+            ThemedNode::Panel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ),
+                ConsoleText,
+            ));
+        });
+}
+
+// Open and close the console. The keyboard belongs to it while it is open.
+pub fn toggle_console(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut input_map: ResMut<InputMap>,
+    mut console: ResMut<Console>,
+    mut panel: Query<&mut Visibility, With<ConsolePanel>>,
+) {
+    if !input_map.just_pressed(Action::ToggleConsole, &keyboard_input) {
+        return;
+    }
+    console.open = !console.open;
+    input_map.captured = console.open;
+    for mut visibility in panel.iter_mut() {
+        *visibility = if console.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// Typing, with Enter sending the line as a command
+pub fn edit_console_input(
+    mut characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut console: ResMut<Console>,
+    mut requests: EventWriter<CommandRequest>,
+) {
+    // Characters typed while closed, and the toggle key itself, never reach the input line
+    let typed: String = characters.read().map(|event| event.char).collect();
+    if !console.open {
+        return;
+    }
+
+    for c in typed.chars() {
+        if !c.is_control() && c != '`' {
+            console.input.push(c);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+    if !keyboard_input.just_pressed(KeyCode::Return) {
+        return;
+    }
+
+    let line = std::mem::take(&mut console.input);
+    if line.trim().is_empty() {
+        return;
+    }
+    console.print(format!("> {}", line));
+    if line.trim() == "help" {
+        console.print(USAGE.to_string());
+        return;
+    }
+    match line.parse::<SimCommand>() {
+        Ok(command) => requests.send(CommandRequest(command)),
+        Err(e) => console.print(format!("{} (try help)", e)),
+    }
+}
+
+pub fn print_command_output(mut outputs: EventReader<CommandOutput>, mut console: ResMut<Console>) {
+    for CommandOutput(output) in outputs.read() {
+        let line = match output {
+            Ok(message) => message.clone(),
+            Err(message) => format!("Error: {}", message),
+        };
+        console.print(line);
+    }
+}
+
+pub fn update_console_text(console: Res<Console>, mut text: Query<&mut Text, With<ConsoleText>>) {
+    if !console.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = text.get_single_mut() {
+        let mut value = console.history.join("\n");
+        if !value.is_empty() {
+            value.push('\n');
+        }
+        value.push_str(&format!("> {}_", console.input));
+        text.sections[0].value = value;
+    }
+}
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_systems(Startup, setup_console)
+            .add_systems(
+                Update,
+                (
+                    toggle_console,
+                    edit_console_input.after(toggle_console),
+                    print_command_output,
+                    update_console_text
+                        .after(edit_console_input)
+                        .after(print_command_output),
+                ),
+            );
+    }
+}
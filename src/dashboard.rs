@@ -0,0 +1,510 @@
+use crate::input::{Action, InputMap};
+use crate::localization::Locale;
+use crate::theme::{Theme, ThemedNode};
+use ant_sim_core::ant::{Ant, AntState};
+use ant_sim_core::clock::SimClock;
+use ant_sim_core::colony::{Colony, ColonyStats};
+use ant_sim_core::config::Config;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+// Sim seconds between samples, and samples plotted: five minutes of history
+const SAMPLE_INTERVAL_SECS: f64 = 2.0;
+const PLOT_SAMPLES: usize = 150;
+// Delivery rates are averaged over this many samples so single deliveries don't spike
+const RATE_SAMPLES: usize = 15;
+const TITLE_FONT_SIZE: f32 = 36.0;
+const FONT_SIZE: f32 = 22.0;
+const TABLE_CELL_WIDTH: f32 = 220.0;
+
+#[derive(Clone, Copy, Default)]
+struct ColonySample {
+    ants: u32,
+    // Searching or returning, out on a foraging trip
+    foraging: u32,
+    food_delivered: u32,
+}
+
+// Per-colony history sampled from the simulation, kept while the dashboard is closed too
+#[derive(Resource, Default)]
+pub struct Dashboard {
+    pub open: bool,
+    samples: VecDeque<(f64, Vec<ColonySample>)>,
+    // Ant-seconds lived by each colony, for food per ant-minute
+    ant_seconds: Vec<f64>,
+}
+
+impl Dashboard {
+    // Food delivered per minute by a colony over the RATE_SAMPLES samples up to index
+    fn delivery_rate(&self, index: usize, colony: usize) -> f32 {
+        let start = index.saturating_sub(RATE_SAMPLES);
+        let (Some((end_secs, end)), Some((start_secs, begin))) =
+            (self.samples.get(index), self.samples.get(start))
+        else {
+            return 0.0;
+        };
+        let elapsed = end_secs - start_secs;
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        let delivered = end[colony]
+            .food_delivered
+            .saturating_sub(begin[colony].food_delivered);
+        (delivered as f64 / elapsed * 60.0) as f32
+    }
+
+    fn value(&self, plot: Plot, index: usize, colony: usize) -> f32 {
+        match plot {
+            Plot::Ants => self
+                .samples
+                .get(index)
+                .map_or(0.0, |(_, colonies)| colonies[colony].ants as f32),
+            Plot::FoodPerMinute => self.delivery_rate(index, colony),
+        }
+    }
+
+    fn latest(&self) -> Option<&[ColonySample]> {
+        self.samples.back().map(|(_, colonies)| colonies.as_slice())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Plot {
+    Ants,
+    FoodPerMinute,
+}
+
+#[derive(Component)]
+pub struct DashboardPanel;
+
+#[derive(Component)]
+pub struct PlotTitle(Plot);
+
+// Share of a plot column belonging to one colony; columns stack the colonies from the bottom
+#[derive(Component)]
+pub struct PlotSegment {
+    plot: Plot,
+    column: usize,
+    colony: usize,
+}
+
+#[derive(Clone, Copy)]
+enum TableColumn {
+    Ants,
+    FoodDelivered,
+    FoodPerAntMinute,
+    AvgTrip,
+    Kills,
+    Deaths,
+}
+
+const TABLE_COLUMNS: [TableColumn; 6] = [
+    TableColumn::Ants,
+    TableColumn::FoodDelivered,
+    TableColumn::FoodPerAntMinute,
+    TableColumn::AvgTrip,
+    TableColumn::Kills,
+    TableColumn::Deaths,
+];
+
+#[derive(Component)]
+pub struct TableCell {
+    colony: usize,
+    column: TableColumn,
+}
+
+fn plot_title(locale: &Locale, plot: Plot) -> &'static str {
+    match plot {
+        Plot::Ants => locale.ants_per_colony,
+        Plot::FoodPerMinute => locale.food_per_minute,
+    }
+}
+
+fn column_title(locale: &Locale, column: TableColumn) -> &'static str {
+    match column {
+        TableColumn::Ants => locale.ants,
+        TableColumn::FoodDelivered => locale.food_delivered,
+        TableColumn::FoodPerAntMinute => locale.food_per_ant_minute,
+        TableColumn::AvgTrip => locale.avg_trip,
+        TableColumn::Kills => locale.kills,
+        TableColumn::Deaths => locale.deaths,
+    }
+}
+
+fn text(value: impl Into<String>, font_size: f32, theme: &Theme) -> TextBundle {
+    TextBundle::from_section(
+        value,
+        TextStyle {
+            font_size,
+            color: theme.text,
+            ..default()
+        },
+    )
+}
+
+// Fixed width so the columns line up; tag goes on the text, for cells updated later
+fn table_cell(
+    parent: &mut ChildBuilder,
+    value: impl Into<String>,
+    theme: &Theme,
+    tag: impl Bundle,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(TABLE_CELL_WIDTH),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((text(value, FONT_SIZE, theme), tag));
+        });
+}
+
+pub fn setup_dashboard(
+    mut commands: Commands,
+    config: Res<Config>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+) {
+    let colony_count = config.colony_configs().len();
+
+    // Covers the whole window, under the console
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    padding: UiRect::all(Val::Px(24.0)),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: theme.panel.into(),
+                z_index: ZIndex::Global(5),
+                ..default()
+            },
+            DashboardPanel,
+            ThemedNode::Panel,
+        ))
+        .with_children(|parent| {
+            parent.spawn(text(locale.dashboard, TITLE_FONT_SIZE, &theme));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(55.0),
+                        column_gap: Val::Px(24.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for plot in [Plot::Ants, Plot::FoodPerMinute] {
+                        spawn_plot(parent, plot, colony_count, &locale, &theme);
+                    }
+                });
+
+            // One row per colony, its number shown next to its plot color
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(NodeBundle::default()).with_children(|row| {
+                        // Room for the swatches below
+                        row.spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(FONT_SIZE + 8.0),
+                                ..default()
+                            },
+                            ..default()
+                        });
+                        table_cell(row, locale.colony, &theme, ());
+                        for column in TABLE_COLUMNS {
+                            table_cell(row, column_title(&locale, column), &theme, ());
+                        }
+                    });
+                    for colony in 0..colony_count {
+                        parent.spawn(NodeBundle::default()).with_children(|row| {
+                            row.spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Px(FONT_SIZE),
+                                    height: Val::Px(FONT_SIZE),
+                                    margin: UiRect::right(Val::Px(8.0)),
+                                    ..default()
+                                },
+                                background_color: theme.colony_color(colony as u32).into(),
+                                ..default()
+                            })
+                            .insert(ThemedNode::Colony(colony as u32));
+                            table_cell(row, format!("{}", colony + 1), &theme, ());
+                            for column in TABLE_COLUMNS {
+                                table_cell(row, "-", &theme, TableCell { colony, column });
+                            }
+                        });
+                    }
+                });
+        });
+}
+
+fn spawn_plot(
+    parent: &mut ChildBuilder,
+    plot: Plot,
+    colony_count: usize,
+    locale: &Locale,
+    theme: &Theme,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(50.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                text(plot_title(locale, plot), FONT_SIZE, theme),
+                PlotTitle(plot),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_grow: 1.0,
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    border_color: theme.text.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for column in 0..PLOT_SAMPLES {
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0 / PLOT_SAMPLES as f32),
+                                    height: Val::Percent(100.0),
+                                    flex_direction: FlexDirection::ColumnReverse,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for colony in 0..colony_count {
+                                    parent.spawn((
+                                        NodeBundle {
+                                            style: Style {
+                                                width: Val::Percent(100.0),
+                                                height: Val::Percent(0.0),
+                                                ..default()
+                                            },
+                                            background_color: theme
+                                                .colony_color(colony as u32)
+                                                .into(),
+                                            ..default()
+                                        },
+                                        PlotSegment {
+                                            plot,
+                                            column,
+                                            colony,
+                                        },
+                                        ThemedNode::Colony(colony as u32),
+                                    ));
+                                }
+                            });
+                    }
+                });
+        });
+}
+
+pub fn toggle_dashboard(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut dashboard: ResMut<Dashboard>,
+    mut panel: Query<&mut Style, With<DashboardPanel>>,
+) {
+    if !input_map.just_pressed(Action::ToggleDashboard, &keyboard_input) {
+        return;
+    }
+    dashboard.open = !dashboard.open;
+    // Hidden from layout while closed, so its many nodes cost nothing
+    for mut style in panel.iter_mut() {
+        style.display = if dashboard.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+pub fn sample_colonies(
+    mut dashboard: ResMut<Dashboard>,
+    clock: Res<SimClock>,
+    stats: Res<ColonyStats>,
+    ants: Query<(&Ant, &Colony)>,
+    config: Res<Config>,
+) {
+    let now = clock.elapsed_secs();
+    let last_secs = dashboard.samples.back().map_or(0.0, |(secs, _)| *secs);
+    if !dashboard.samples.is_empty() && now - last_secs < SAMPLE_INTERVAL_SECS {
+        return;
+    }
+
+    let colony_count = config.colony_configs().len();
+    let mut sample = vec![ColonySample::default(); colony_count];
+    for (ant, colony) in ants.iter() {
+        let Some(colony_sample) = sample.get_mut(colony.0 as usize) else {
+            continue;
+        };
+        colony_sample.ants += 1;
+        if matches!(ant.state, AntState::Searching | AntState::Returning) {
+            colony_sample.foraging += 1;
+        }
+    }
+    for (colony_sample, totals) in sample.iter_mut().zip(&stats.colonies) {
+        colony_sample.food_delivered = totals.food_delivered;
+    }
+
+    dashboard.ant_seconds.resize(colony_count, 0.0);
+    for (ant_seconds, colony_sample) in dashboard.ant_seconds.iter_mut().zip(&sample) {
+        *ant_seconds += colony_sample.ants as f64 * (now - last_secs);
+    }
+    dashboard.samples.push_back((now, sample));
+    // Older samples are kept just long enough for the delivery rate of the first column
+    while dashboard.samples.len() > PLOT_SAMPLES + RATE_SAMPLES {
+        dashboard.samples.pop_front();
+    }
+}
+
+pub fn update_dashboard_plots(
+    dashboard: Res<Dashboard>,
+    locale: Res<Locale>,
+    mut segments: Query<(&mut Style, &PlotSegment)>,
+    mut titles: Query<(&mut Text, &PlotTitle)>,
+) {
+    // Plots fill from the left until there is enough history, then scroll
+    let first = dashboard.samples.len().saturating_sub(PLOT_SAMPLES);
+    let index = |column: usize| first + column;
+
+    for (mut text, PlotTitle(plot)) in titles.iter_mut() {
+        let peak = peak_total(&dashboard, *plot, first);
+        text.sections[0].value = format!(
+            "{} ({} {:.0})",
+            plot_title(&locale, *plot),
+            locale.max,
+            peak
+        );
+    }
+
+    let peaks = [Plot::Ants, Plot::FoodPerMinute].map(|plot| peak_total(&dashboard, plot, first));
+    for (mut style, segment) in segments.iter_mut() {
+        let peak = peaks[segment.plot as usize];
+        let value = if index(segment.column) < dashboard.samples.len() && peak > 0.0 {
+            dashboard.value(segment.plot, index(segment.column), segment.colony) / peak
+        } else {
+            0.0
+        };
+        style.height = Val::Percent(value * 100.0);
+    }
+}
+
+// Highest column of a plot, colonies stacked
+fn peak_total(dashboard: &Dashboard, plot: Plot, first: usize) -> f32 {
+    let colony_count = dashboard.latest().map_or(0, <[ColonySample]>::len);
+    (first..dashboard.samples.len())
+        .map(|index| {
+            (0..colony_count)
+                .map(|colony| dashboard.value(plot, index, colony))
+                .sum::<f32>()
+        })
+        .fold(0.0, f32::max)
+}
+
+pub fn update_dashboard_table(
+    dashboard: Res<Dashboard>,
+    stats: Res<ColonyStats>,
+    mut cells: Query<(&mut Text, &TableCell)>,
+) {
+    let Some(latest) = dashboard.latest() else {
+        return;
+    };
+    let last_index = dashboard.samples.len() - 1;
+
+    for (mut text, cell) in cells.iter_mut() {
+        let Some(sample) = latest.get(cell.colony) else {
+            continue;
+        };
+        let totals = stats.colonies.get(cell.colony).copied().unwrap_or_default();
+        text.sections[0].value = match cell.column {
+            TableColumn::Ants => sample.ants.to_string(),
+            TableColumn::FoodDelivered => totals.food_delivered.to_string(),
+            TableColumn::FoodPerAntMinute => {
+                let ant_minutes = dashboard
+                    .ant_seconds
+                    .get(cell.colony)
+                    .copied()
+                    .unwrap_or(0.0)
+                    / 60.0;
+                if ant_minutes > 0.0 {
+                    format!("{:.3}", totals.food_delivered as f64 / ant_minutes)
+                } else {
+                    "-".to_string()
+                }
+            }
+            // Little's law: ants out foraging divided by the rate they come back with food
+            TableColumn::AvgTrip => {
+                let per_second = dashboard.delivery_rate(last_index, cell.colony) / 60.0;
+                if per_second > 0.0 {
+                    format!("{:.0} s", sample.foraging as f32 / per_second)
+                } else {
+                    "-".to_string()
+                }
+            }
+            TableColumn::Kills => totals.kills.to_string(),
+            TableColumn::Deaths => totals.deaths.to_string(),
+        };
+    }
+}
+
+fn dashboard_open(dashboard: Res<Dashboard>) -> bool {
+    dashboard.open
+}
+
+// Full-window plots and per-colony tables, large enough to read on a projector
+pub struct DashboardPlugin;
+
+impl Plugin for DashboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Dashboard>()
+            .add_systems(Startup, setup_dashboard)
+            .add_systems(
+                Update,
+                (
+                    toggle_dashboard,
+                    sample_colonies.run_if(resource_exists::<ColonyStats>()),
+                    (update_dashboard_plots, update_dashboard_table)
+                        .after(sample_colonies)
+                        .after(toggle_dashboard)
+                        .run_if(dashboard_open)
+                        .run_if(resource_exists::<ColonyStats>()),
+                ),
+            );
+    }
+}
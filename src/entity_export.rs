@@ -0,0 +1,149 @@
+use crate::network::WorldSnapshot;
+use ant_sim_core::grid::GridType;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// One entity of a snapshot as a point on the map, in world pixels with y pointing up
+pub struct EntityPoint {
+    pub id: u64,
+    pub kind: String,
+    pub position: [f32; 2],
+    // Everything else the snapshot records about the entity
+    pub properties: Map<String, Value>,
+}
+
+// HeavyFood -> heavy_food
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+// Points of every entity in the snapshot. Markers sit at the center of their grid cell.
+pub fn entity_points(snapshot: &WorldSnapshot, grid_type: GridType) -> Vec<EntityPoint> {
+    let mut points = Vec::new();
+    for entity in &snapshot.entities {
+        // Each kind serializes as { "Kind": { fields } }
+        let Ok(Value::Object(kind)) = serde_json::to_value(&entity.kind) else {
+            continue;
+        };
+        let Some((name, Value::Object(mut properties))) = kind.into_iter().next() else {
+            continue;
+        };
+        let position = match (properties.remove("position"), properties.get("grid_cell")) {
+            (Some(position), _) => serde_json::from_value(position).ok(),
+            (None, Some(cell)) => serde_json::from_value(cell.clone())
+                .ok()
+                .map(|cell| grid_type.grid_to_world(cell).to_array()),
+            (None, None) => None,
+        };
+        let Some(position) = position else {
+            continue;
+        };
+        points.push(EntityPoint {
+            id: entity.id,
+            kind: snake_case(&name),
+            position,
+            properties,
+        });
+    }
+    points
+}
+
+// FeatureCollection of Point features, with the id, kind and properties of each entity
+pub fn write_geojson(
+    path: &Path,
+    points: &[EntityPoint],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let features: Vec<Value> = points
+        .iter()
+        .map(|point| {
+            let mut properties = point.properties.clone();
+            properties.insert("id".to_string(), json!(point.id));
+            properties.insert("kind".to_string(), json!(point.kind));
+            json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": point.position },
+                "properties": properties,
+            })
+        })
+        .collect();
+    let collection = json!({ "type": "FeatureCollection", "features": features });
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut file, &collection)?;
+    file.flush()?;
+    Ok(())
+}
+
+// Nested properties such as an ant's traits become columns of their own, e.g. traits.speed
+fn flatten(prefix: &str, value: &Value, columns: &mut Map<String, Value>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let name = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&name, value, columns);
+            }
+        }
+        _ => {
+            columns.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+fn csv_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// One row per entity with id, kind, x and y first, then every property any entity has,
+// left empty for kinds without it
+pub fn write_csv(path: &Path, points: &[EntityPoint]) -> std::io::Result<()> {
+    let rows: Vec<Map<String, Value>> = points
+        .iter()
+        .map(|point| {
+            let mut columns = Map::new();
+            flatten("", &Value::Object(point.properties.clone()), &mut columns);
+            columns
+        })
+        .collect();
+    let names: BTreeSet<&String> = rows.iter().flat_map(|row| row.keys()).collect();
+
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(
+        ["id", "kind", "x", "y"]
+            .into_iter()
+            .chain(names.iter().map(|name| name.as_str())),
+    )?;
+    for (point, row) in points.iter().zip(&rows) {
+        let values: Vec<String> = [
+            point.id.to_string(),
+            point.kind.clone(),
+            format!("{:.2}", point.position[0]),
+            format!("{:.2}", point.position[1]),
+        ]
+        .into_iter()
+        .chain(
+            names
+                .iter()
+                .map(|name| row.get(*name).map_or_else(String::new, csv_field)),
+        )
+        .collect();
+        writer.write_record(&values)?;
+    }
+    writer.flush()
+}
@@ -0,0 +1,53 @@
+use crate::gui::{update_frame_timing, FrameTiming};
+use crate::visuals::VisualDetail;
+use ant_sim_core::config::Config;
+use bevy::prelude::*;
+
+// Lower the visual detail one step while the average frame time is over budget, and raise it
+// one step once frames are well under it again. Steps are step_secs apart so the average can
+// catch up with each change before the next.
+pub fn adjust_visual_detail(
+    mut detail: ResMut<VisualDetail>,
+    frame_timing: Res<FrameTiming>,
+    config: Res<Config>,
+    time: Res<Time<Real>>,
+    mut since_step_secs: Local<f32>,
+) {
+    let budget = &config.frame_budget;
+    let Some(max_frame_ms) = budget.max_frame_ms else {
+        if *detail != VisualDetail::Full {
+            *detail = VisualDetail::Full;
+        }
+        return;
+    };
+
+    *since_step_secs += time.delta_seconds();
+    if *since_step_secs < budget.step_secs {
+        return;
+    }
+
+    let average_ms = frame_timing.average_ms();
+    let next = if average_ms > max_frame_ms {
+        detail.lower()
+    } else if average_ms < max_frame_ms * budget.recover_fraction {
+        detail.higher()
+    } else {
+        None
+    };
+    if let Some(next) = next {
+        println!(
+            "Average frame time {:.1} ms against a {:.1} ms budget, visual detail now {:?}",
+            average_ms, max_frame_ms, next
+        );
+        *detail = next;
+        *since_step_secs = 0.0;
+    }
+}
+
+pub struct FrameBudgetPlugin;
+
+impl Plugin for FrameBudgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, adjust_visual_detail.after(update_frame_timing));
+    }
+}
@@ -1,9 +1,29 @@
-use crate::ant::{Ant, AntState};
-use crate::marker::{Marker, MarkerType};
+use crate::input::{Action, InputMap};
+use crate::localization::{checkbox_label, Locale};
+use crate::theme::{Theme, ThemedNode};
+use ant_sim_core::ant::{Ant, AntState};
+use ant_sim_core::brood::{BroodCounts, BroodStats};
+use ant_sim_core::clock::SimClock;
+use ant_sim_core::config::ThemeName;
+use ant_sim_core::marker::{Marker, MarkerType};
+use ant_sim_core::profiling::PROFILED_SYSTEMS;
+use ant_sim_core::trail::TrailStats;
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 const FRAME_HISTORY_SIZE: usize = 60;
 const HOVER_ZONE_SIZE: f32 = 100.0;
+const STATE_BAR_WIDTH: f32 = 200.0;
+const STATE_BAR_HEIGHT: f32 = 10.0;
+// States shown in the distribution bar, in display order
+const STATE_BAR_STATES: [AntState; 4] = [
+    AntState::Searching,
+    AntState::Returning,
+    AntState::Resting,
+    AntState::CarryingCorpse,
+];
 
 #[derive(Resource)]
 pub struct FrameTiming {
@@ -37,34 +57,102 @@ impl FrameTiming {
         let sum: f32 = self.frame_history.iter().sum();
         sum / self.frame_history.len() as f32
     }
+
+    // Times of the last FRAME_HISTORY_SIZE frames, oldest first
+    pub fn history_ms(&self) -> Vec<f32> {
+        let (newer, older) = self.frame_history.split_at(self.history_index);
+        older.iter().chain(newer).copied().collect()
+    }
 }
 
-#[derive(Resource, Default)]
+// Saved to gui_settings.json on exit, except for the tool mode and hover state
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GuiSettings {
-    pub hide_markers: bool,
+    pub hide_base_markers: bool,
+    pub hide_food_markers: bool,
+    pub hide_danger_markers: bool,
     pub hide_ants: bool,
+    // Darken ants by how long they have been in their current state
+    pub fade_by_state_time: bool,
+    pub mute_audio: bool,
     pub hide_gui: bool,
+    // Clicking the map adds (left) or removes (right) food sources
+    #[serde(skip)]
+    pub food_tool: bool,
+    #[serde(skip)]
     pub gui_hovered: bool,
+    // Per-system timings overlay, toggled with the toggle_profiler key
+    pub show_profiler: bool,
+}
+
+impl GuiSettings {
+    pub fn is_marker_hidden(&self, marker_type: MarkerType) -> bool {
+        match marker_type {
+            MarkerType::Base => self.hide_base_markers,
+            MarkerType::Food => self.hide_food_markers,
+            MarkerType::Danger => self.hide_danger_markers,
+        }
+    }
+
+    fn toggle_marker(&mut self, marker_type: MarkerType) {
+        let hidden = match marker_type {
+            MarkerType::Base => &mut self.hide_base_markers,
+            MarkerType::Food => &mut self.hide_food_markers,
+            MarkerType::Danger => &mut self.hide_danger_markers,
+        };
+        *hidden = !*hidden;
+    }
 }
 
 #[derive(Component)]
 pub struct DebugUI;
 
+// Checkbox hiding one marker type, with a color swatch doubling as the legend
 #[derive(Component)]
-pub struct CheckboxHideMarkers;
+pub struct CheckboxHideMarkers(MarkerType);
 
 #[derive(Component)]
 pub struct CheckboxHideAnts;
 
+#[derive(Component)]
+pub struct CheckboxFadeAnts;
+
+#[derive(Component)]
+pub struct CheckboxMuteAudio;
+
+// Button opening the list of themes
+#[derive(Component)]
+pub struct ThemeDropdown;
+
+#[derive(Component)]
+pub struct ThemeOptions;
+
+#[derive(Component)]
+pub struct ThemeOption(ThemeName);
+
 #[derive(Component)]
 pub struct CheckboxHideGUI;
 
+#[derive(Component)]
+pub struct CheckboxFoodTool;
+
 #[derive(Component)]
 pub struct MainStatsPanel;
 
+// One segment of the ant state distribution bar
+#[derive(Component)]
+pub struct StateBarSegment(AntState);
+
 #[derive(Component)]
 pub struct HideGUIPanel;
 
+#[derive(Component)]
+pub struct ProfilerOverlay;
+
+#[derive(Component)]
+pub struct ProfilerText;
+
 #[derive(Component)]
 pub struct HoverZone;
 
@@ -72,60 +160,239 @@ pub fn update_frame_timing(mut frame_timing: ResMut<FrameTiming>, time: Res<Time
     frame_timing.update(time.delta_seconds());
 }
 
+// Figures of a simulation run here. Observers of a served simulation have none of them.
+#[derive(SystemParam)]
+pub struct LocalSimStats<'w> {
+    clock: Option<Res<'w, SimClock>>,
+    brood: Option<Res<'w, BroodStats>>,
+    trails: Option<Res<'w, TrailStats>>,
+}
+
 pub fn update_debug_ui(
     mut query: Query<&mut Text, With<DebugUI>>,
     frame_timing: Res<FrameTiming>,
     ants: Query<&Ant>,
     markers: Query<&Marker>,
+    locale: Res<Locale>,
+    stats: LocalSimStats,
 ) {
     // Count ants by state
     let mut searching_count = 0;
     let mut returning_count = 0;
+    let mut resting_count = 0;
+    let mut corpse_carrier_count = 0;
     for ant in ants.iter() {
         match ant.state {
             AntState::Searching => searching_count += 1,
             AntState::Returning => returning_count += 1,
+            AntState::Resting => resting_count += 1,
+            AntState::CarryingCorpse => corpse_carrier_count += 1,
         }
     }
-    let total_ants = searching_count + returning_count;
+    let total_ants = searching_count + returning_count + resting_count + corpse_carrier_count;
 
     // Count markers by type
     let mut base_marker_count = 0;
     let mut food_marker_count = 0;
+    let mut danger_marker_count = 0;
     for marker in markers.iter() {
         match marker.marker_type {
             MarkerType::Base => base_marker_count += 1,
             MarkerType::Food => food_marker_count += 1,
+            MarkerType::Danger => danger_marker_count += 1,
         }
     }
-    let total_markers = base_marker_count + food_marker_count;
+    let total_markers = base_marker_count + food_marker_count + danger_marker_count;
+
+    let sim_time = stats.clock.map_or(String::new(), |clock| {
+        format!(
+            "{}: {:.1} s ({} {})\n",
+            locale.sim_time,
+            clock.elapsed_secs(),
+            locale.tick,
+            clock.tick()
+        )
+    });
+
+    // Eggs, larvae and pupae over all colonies
+    let brood = stats.brood.map_or(String::new(), |brood| {
+        let mut totals = BroodCounts::default();
+        for counts in &brood.colonies {
+            totals.eggs += counts.eggs;
+            totals.larvae += counts.larvae;
+            totals.pupae += counts.pupae;
+        }
+        format!(
+            "\n\n{}: {}\n- {}: {}\n- {}: {}\n- {}: {}",
+            locale.brood,
+            totals.total(),
+            locale.eggs,
+            totals.eggs,
+            locale.larvae,
+            totals.larvae,
+            locale.pupae,
+            totals.pupae
+        )
+    });
+
+    // Trails from the latest analysis, and when they first became stable
+    let trails = stats.trails.map_or(String::new(), |trails| {
+        let first_stable = trails.first_stable_secs.map_or(String::new(), |secs| {
+            format!("\n- {}: {:.1} s", locale.first_stable_trail, secs)
+        });
+        format!(
+            "\n\n{}: {}\n- {}: {:.0} px\n- {}: {:.0}%{}",
+            locale.trails,
+            trails.trails.len(),
+            locale.longest_trail,
+            trails.longest(),
+            locale.trail_stability,
+            trails.stability * 100.0,
+            first_stable
+        )
+    });
 
     // Update the text
     if let Ok(mut text) = query.get_single_mut() {
         text.sections[0].value = format!(
-            "Frame Time: {:.2} ms\n\
-             Avg Frame Time: {:.2} ms\n\
+            "{}\
+             {}: {:.2} ms\n\
+             {}: {:.2} ms\n\
              \n\
-             Ants: {}\n\
-             - Searching: {}\n\
-             - Returning: {}\n\
+             {}: {}\n\
+             - {}: {}\n\
+             - {}: {}\n\
+             - {}: {}\n\
+             - {}: {}\n\
              \n\
-             Markers: {}\n\
-             - Base: {}\n\
-             - Food: {}",
+             {}: {}\n\
+             - {}: {}\n\
+             - {}: {}\n\
+             - {}: {}{}{}",
+            sim_time,
+            locale.frame_time,
             frame_timing.current_ms(),
+            locale.avg_frame_time,
             frame_timing.average_ms(),
+            locale.ants,
             total_ants,
+            locale.searching,
             searching_count,
+            locale.returning,
             returning_count,
+            locale.resting,
+            resting_count,
+            locale.carrying_corpses,
+            corpse_carrier_count,
+            locale.markers,
             total_markers,
+            locale.base,
             base_marker_count,
-            food_marker_count
+            locale.food,
+            food_marker_count,
+            locale.danger,
+            danger_marker_count,
+            brood,
+            trails
         );
     }
 }
 
-pub fn setup_debug_ui(mut commands: Commands) {
+pub fn toggle_profiler(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut settings: ResMut<GuiSettings>,
+    mut overlay: Query<&mut Visibility, With<ProfilerOverlay>>,
+) {
+    if !input_map.just_pressed(Action::ToggleProfiler, &keyboard_input) {
+        return;
+    }
+    settings.show_profiler = !settings.show_profiler;
+    for mut visibility in overlay.iter_mut() {
+        *visibility = if settings.show_profiler {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// Time spent in each profiled simulation system, next to the total frame time
+pub fn update_profiler_overlay(
+    mut query: Query<&mut Text, With<ProfilerText>>,
+    settings: Res<GuiSettings>,
+    diagnostics: Res<DiagnosticsStore>,
+    frame_timing: Res<FrameTiming>,
+    locale: Res<Locale>,
+) {
+    if !settings.show_profiler {
+        return;
+    }
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+
+    let mut value = format!(
+        "{}: {:.2} ms ({} {:.2} ms)\n",
+        locale.profiler_frame,
+        frame_timing.current_ms(),
+        locale.profiler_avg,
+        frame_timing.average_ms()
+    );
+    for (id, name) in PROFILED_SYSTEMS {
+        let Some(diagnostic) = diagnostics.get(id) else {
+            continue;
+        };
+        // Peak over the kept history makes occasional spikes visible
+        let peak = diagnostic.values().copied().fold(0.0, f64::max);
+        value.push_str(&format!(
+            "\n{}: {:.2} ms ({} {:.2}, {} {:.2})",
+            name,
+            diagnostic.value().unwrap_or(0.0),
+            locale.profiler_avg,
+            diagnostic.average().unwrap_or(0.0),
+            locale.profiler_peak,
+            peak
+        ));
+    }
+    text.sections[0].value = value;
+}
+
+// Resize the distribution bar segments to each state's share of the population
+pub fn update_state_bar(mut segments: Query<(&mut Style, &StateBarSegment)>, ants: Query<&Ant>) {
+    let mut counts = [0usize; STATE_BAR_STATES.len()];
+    for ant in ants.iter() {
+        if let Some(index) = STATE_BAR_STATES
+            .iter()
+            .position(|state| *state == ant.state)
+        {
+            counts[index] += 1;
+        }
+    }
+    let total: usize = counts.iter().sum();
+
+    for (mut style, segment) in segments.iter_mut() {
+        let Some(index) = STATE_BAR_STATES
+            .iter()
+            .position(|state| *state == segment.0)
+        else {
+            continue;
+        };
+        let share = if total > 0 {
+            counts[index] as f32 / total as f32 * 100.0
+        } else {
+            0.0
+        };
+        style.width = Val::Percent(share);
+    }
+}
+
+pub fn setup_debug_ui(
+    mut commands: Commands,
+    settings: Res<GuiSettings>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+) {
     // Main stats panel in bottom-left
     let main_panel = commands
         .spawn((
@@ -138,15 +405,61 @@ pub fn setup_debug_ui(mut commands: Commands) {
                     flex_direction: FlexDirection::Column,
                     ..default()
                 },
-                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                background_color: theme.panel.into(),
                 ..default()
             },
             MainStatsPanel,
+            ThemedNode::Panel,
         ))
         .id();
 
     commands.entity(main_panel).with_children(|parent| {
-        // Hide Markers checkbox
+        // One Hide Markers checkbox per marker type
+        for marker_type in [MarkerType::Base, MarkerType::Food, MarkerType::Danger] {
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(4.0)),
+                            margin: UiRect::bottom(Val::Px(4.0)),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: theme.button.into(),
+                        ..default()
+                    },
+                    CheckboxHideMarkers(marker_type),
+                    ThemedNode::Button,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Px(10.0),
+                                height: Val::Px(10.0),
+                                margin: UiRect::right(Val::Px(6.0)),
+                                ..default()
+                            },
+                            background_color: theme.marker_color(marker_type).into(),
+                            ..default()
+                        },
+                        ThemedNode::MarkerSwatch(marker_type),
+                    ));
+                    parent.spawn(TextBundle::from_section(
+                        checkbox_label(
+                            locale.hide_markers(marker_type),
+                            settings.is_marker_hidden(marker_type),
+                        ),
+                        TextStyle {
+                            font_size: 14.0,
+                            color: theme.text,
+                            ..default()
+                        },
+                    ));
+                });
+        }
+
+        // Hide Ants checkbox
         parent
             .spawn((
                 ButtonBundle {
@@ -155,23 +468,24 @@ pub fn setup_debug_ui(mut commands: Commands) {
                         margin: UiRect::bottom(Val::Px(4.0)),
                         ..default()
                     },
-                    background_color: Color::rgba(0.3, 0.3, 0.3, 0.8).into(),
+                    background_color: theme.button.into(),
                     ..default()
                 },
-                CheckboxHideMarkers,
+                CheckboxHideAnts,
+                ThemedNode::Button,
             ))
             .with_children(|parent| {
                 parent.spawn(TextBundle::from_section(
-                    "☐ Hide Markers",
+                    checkbox_label(locale.hide_ants, settings.hide_ants),
                     TextStyle {
                         font_size: 14.0,
-                        color: Color::WHITE,
+                        color: theme.text,
                         ..default()
                     },
                 ));
             });
 
-        // Hide Ants checkbox
+        // Fade by time in state checkbox
         parent
             .spawn((
                 ButtonBundle {
@@ -180,29 +494,184 @@ pub fn setup_debug_ui(mut commands: Commands) {
                         margin: UiRect::bottom(Val::Px(4.0)),
                         ..default()
                     },
-                    background_color: Color::rgba(0.3, 0.3, 0.3, 0.8).into(),
+                    background_color: theme.button.into(),
                     ..default()
                 },
-                CheckboxHideAnts,
+                CheckboxFadeAnts,
+                ThemedNode::Button,
             ))
             .with_children(|parent| {
                 parent.spawn(TextBundle::from_section(
-                    "☐ Hide Ants",
+                    checkbox_label(locale.fade_by_state_time, settings.fade_by_state_time),
                     TextStyle {
                         font_size: 14.0,
-                        color: Color::WHITE,
+                        color: theme.text,
                         ..default()
                     },
                 ));
             });
 
+        // Mute checkbox
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(4.0)),
+                        margin: UiRect::bottom(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: theme.button.into(),
+                    ..default()
+                },
+                CheckboxMuteAudio,
+                ThemedNode::Button,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    checkbox_label(locale.mute_sounds, settings.mute_audio),
+                    TextStyle {
+                        font_size: 14.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ));
+            });
+
+        // Food tool checkbox
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(4.0)),
+                        margin: UiRect::bottom(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: theme.button.into(),
+                    ..default()
+                },
+                CheckboxFoodTool,
+                ThemedNode::Button,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    checkbox_label(locale.food_tool, false),
+                    TextStyle {
+                        font_size: 14.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ));
+            });
+
+        // Theme dropdown, its options listed below it while open
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(4.0)),
+                        margin: UiRect::bottom(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: theme.button.into(),
+                    ..default()
+                },
+                ThemeDropdown,
+                ThemedNode::Button,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    theme_dropdown_label(&locale, theme.name),
+                    TextStyle {
+                        font_size: 14.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ));
+            });
+        parent
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        display: Display::None,
+                        flex_direction: FlexDirection::Column,
+                        margin: UiRect::left(Val::Px(12.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ThemeOptions,
+            ))
+            .with_children(|parent| {
+                for name in [ThemeName::Light, ThemeName::Dark, ThemeName::HighContrast] {
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    padding: UiRect::all(Val::Px(4.0)),
+                                    margin: UiRect::bottom(Val::Px(4.0)),
+                                    ..default()
+                                },
+                                background_color: theme.button.into(),
+                                ..default()
+                            },
+                            ThemeOption(name),
+                            ThemedNode::Button,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                locale.theme_name(name),
+                                TextStyle {
+                                    font_size: 14.0,
+                                    color: theme.text,
+                                    ..default()
+                                },
+                            ));
+                        });
+                }
+            });
+
+        // Ant state distribution bar
+        parent
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(STATE_BAR_WIDTH),
+                        height: Val::Px(STATE_BAR_HEIGHT),
+                        margin: UiRect::vertical(Val::Px(4.0)),
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    // Empty track in the shade of the buttons
+                    background_color: theme.button.into(),
+                    ..default()
+                },
+                ThemedNode::Button,
+            ))
+            .with_children(|parent| {
+                for state in STATE_BAR_STATES {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: theme.state_bar_color(state).into(),
+                            ..default()
+                        },
+                        StateBarSegment(state),
+                        ThemedNode::StateBarSegment(state),
+                    ));
+                }
+            });
+
         // Stats text
         parent.spawn((
             TextBundle::from_section(
                 "",
                 TextStyle {
                     font_size: 16.0,
-                    color: Color::WHITE,
+                    color: theme.text,
                     ..default()
                 },
             ),
@@ -221,10 +690,11 @@ pub fn setup_debug_ui(mut commands: Commands) {
                     padding: UiRect::all(Val::Px(8.0)),
                     ..default()
                 },
-                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                background_color: theme.panel.into(),
                 ..default()
             },
             HideGUIPanel,
+            ThemedNode::Panel,
         ))
         .id();
 
@@ -236,23 +706,61 @@ pub fn setup_debug_ui(mut commands: Commands) {
                         padding: UiRect::all(Val::Px(4.0)),
                         ..default()
                     },
-                    background_color: Color::rgba(0.3, 0.3, 0.3, 0.8).into(),
+                    background_color: theme.button.into(),
                     ..default()
                 },
                 CheckboxHideGUI,
+                ThemedNode::Button,
             ))
             .with_children(|parent| {
                 parent.spawn(TextBundle::from_section(
-                    "☐ Hide GUI",
+                    checkbox_label(locale.hide_gui, settings.hide_gui),
                     TextStyle {
                         font_size: 14.0,
-                        color: Color::WHITE,
+                        color: theme.text,
                         ..default()
                     },
                 ));
             });
     });
 
+    // Profiler overlay in bottom-right, hidden until toggled
+    let profiler_visibility = if settings.show_profiler {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: theme.panel.into(),
+                visibility: profiler_visibility,
+                ..default()
+            },
+            ProfilerOverlay,
+            ThemedNode::Panel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ),
+                ProfilerText,
+            ));
+        });
+
     // Hover zone in top-left corner (invisible but interactive)
     commands.spawn((
         ButtonBundle {
@@ -274,25 +782,26 @@ pub fn setup_debug_ui(mut commands: Commands) {
 // Separate handlers for each checkbox
 pub fn handle_hide_markers_checkbox(
     mut interaction_query: Query<
-        (Entity, &Interaction),
-        (Changed<Interaction>, With<CheckboxHideMarkers>),
+        (Entity, &Interaction, &CheckboxHideMarkers),
+        Changed<Interaction>,
     >,
     mut settings: ResMut<GuiSettings>,
     mut text_query: Query<&mut Text>,
+    locale: Res<Locale>,
     children: Query<&Children>,
 ) {
-    for (entity, interaction) in interaction_query.iter_mut() {
+    for (entity, interaction, checkbox) in interaction_query.iter_mut() {
         if *interaction == Interaction::Pressed {
-            settings.hide_markers = !settings.hide_markers;
+            let marker_type = checkbox.0;
+            settings.toggle_marker(marker_type);
             // Update checkbox text
             if let Ok(children) = children.get(entity) {
                 for child in children.iter() {
                     if let Ok(mut text) = text_query.get_mut(*child) {
-                        text.sections[0].value = if settings.hide_markers {
-                            "☑ Hide Markers".to_string()
-                        } else {
-                            "☐ Hide Markers".to_string()
-                        };
+                        text.sections[0].value = checkbox_label(
+                            locale.hide_markers(marker_type),
+                            settings.is_marker_hidden(marker_type),
+                        );
                     }
                 }
             }
@@ -307,6 +816,7 @@ pub fn handle_hide_ants_checkbox(
     >,
     mut settings: ResMut<GuiSettings>,
     mut text_query: Query<&mut Text>,
+    locale: Res<Locale>,
     children: Query<&Children>,
 ) {
     for (entity, interaction) in interaction_query.iter_mut() {
@@ -316,11 +826,8 @@ pub fn handle_hide_ants_checkbox(
             if let Ok(children) = children.get(entity) {
                 for child in children.iter() {
                     if let Ok(mut text) = text_query.get_mut(*child) {
-                        text.sections[0].value = if settings.hide_ants {
-                            "☑ Hide Ants".to_string()
-                        } else {
-                            "☐ Hide Ants".to_string()
-                        };
+                        text.sections[0].value =
+                            checkbox_label(locale.hide_ants, settings.hide_ants);
                     }
                 }
             }
@@ -328,6 +835,93 @@ pub fn handle_hide_ants_checkbox(
     }
 }
 
+pub fn handle_fade_by_state_time_checkbox(
+    checkboxes: Query<(Ref<Interaction>, &Children), With<CheckboxFadeAnts>>,
+    mut settings: ResMut<GuiSettings>,
+    mut text_query: Query<&mut Text>,
+    locale: Res<Locale>,
+) {
+    for (interaction, children) in checkboxes.iter() {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            settings.fade_by_state_time = !settings.fade_by_state_time;
+            // Update checkbox text
+            for child in children.iter() {
+                if let Ok(mut text) = text_query.get_mut(*child) {
+                    text.sections[0].value =
+                        checkbox_label(locale.fade_by_state_time, settings.fade_by_state_time);
+                }
+            }
+        }
+    }
+}
+
+pub fn handle_mute_audio_checkbox(
+    checkboxes: Query<(Ref<Interaction>, &Children), With<CheckboxMuteAudio>>,
+    mut settings: ResMut<GuiSettings>,
+    mut text_query: Query<&mut Text>,
+    locale: Res<Locale>,
+) {
+    for (interaction, children) in checkboxes.iter() {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            settings.mute_audio = !settings.mute_audio;
+            // Update checkbox text
+            for child in children.iter() {
+                if let Ok(mut text) = text_query.get_mut(*child) {
+                    text.sections[0].value =
+                        checkbox_label(locale.mute_sounds, settings.mute_audio);
+                }
+            }
+        }
+    }
+}
+
+fn theme_dropdown_label(locale: &Locale, name: ThemeName) -> String {
+    format!("{}: {} ▾", locale.theme, locale.theme_name(name))
+}
+
+pub fn handle_theme_dropdown(
+    dropdowns: Query<Ref<Interaction>, With<ThemeDropdown>>,
+    mut options: Query<&mut Style, With<ThemeOptions>>,
+) {
+    for interaction in dropdowns.iter() {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            for mut style in options.iter_mut() {
+                style.display = match style.display {
+                    Display::None => Display::Flex,
+                    _ => Display::None,
+                };
+            }
+        }
+    }
+}
+
+// Switch to the picked theme and close the list
+pub fn handle_theme_option(
+    choices: Query<(Ref<Interaction>, &ThemeOption)>,
+    mut options: Query<&mut Style, With<ThemeOptions>>,
+    dropdowns: Query<&Children, With<ThemeDropdown>>,
+    mut text_query: Query<&mut Text>,
+    mut theme: ResMut<Theme>,
+    locale: Res<Locale>,
+) {
+    for (interaction, option) in choices.iter() {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed {
+            continue;
+        }
+        *theme = Theme::named(option.0);
+        for mut style in options.iter_mut() {
+            style.display = Display::None;
+        }
+        for children in dropdowns.iter() {
+            for child in children.iter() {
+                if let Ok(mut text) = text_query.get_mut(*child) {
+                    text.sections[0].value = theme_dropdown_label(&locale, option.0);
+                }
+            }
+        }
+    }
+}
+
 pub fn handle_hide_gui_checkbox(
     mut interaction_query: Query<
         (Entity, &Interaction),
@@ -335,6 +929,7 @@ pub fn handle_hide_gui_checkbox(
     >,
     mut settings: ResMut<GuiSettings>,
     mut text_query: Query<&mut Text>,
+    locale: Res<Locale>,
     children: Query<&Children>,
 ) {
     for (entity, interaction) in interaction_query.iter_mut() {
@@ -344,11 +939,7 @@ pub fn handle_hide_gui_checkbox(
             if let Ok(children) = children.get(entity) {
                 for child in children.iter() {
                     if let Ok(mut text) = text_query.get_mut(*child) {
-                        text.sections[0].value = if settings.hide_gui {
-                            "☑ Hide GUI".to_string()
-                        } else {
-                            "☐ Hide GUI".to_string()
-                        };
+                        text.sections[0].value = checkbox_label(locale.hide_gui, settings.hide_gui);
                     }
                 }
             }
@@ -356,27 +947,28 @@ pub fn handle_hide_gui_checkbox(
     }
 }
 
-pub fn toggle_markers_visibility(
-    mut commands: Commands,
-    markers: Query<Entity, (With<Marker>, Without<Ant>, Without<Visibility>)>,
-    mut markers_with_visibility: Query<&mut Visibility, (With<Marker>, Without<Ant>)>,
-    settings: Res<GuiSettings>,
+pub fn handle_food_tool_checkbox(
+    mut interaction_query: Query<
+        (Entity, &Interaction),
+        (Changed<Interaction>, With<CheckboxFoodTool>),
+    >,
+    mut settings: ResMut<GuiSettings>,
+    mut text_query: Query<&mut Text>,
+    locale: Res<Locale>,
+    children: Query<&Children>,
 ) {
-    let target_visibility = if settings.hide_markers {
-        Visibility::Hidden
-    } else {
-        Visibility::Visible
-    };
-
-    // Insert Visibility component for entities that don't have it
-    for entity in markers.iter() {
-        commands.entity(entity).insert(target_visibility);
-    }
-
-    // Update existing Visibility components
-    for mut visibility in markers_with_visibility.iter_mut() {
-        if *visibility != target_visibility {
-            *visibility = target_visibility;
+    for (entity, interaction) in interaction_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            settings.food_tool = !settings.food_tool;
+            // Update checkbox text
+            if let Ok(children) = children.get(entity) {
+                for child in children.iter() {
+                    if let Ok(mut text) = text_query.get_mut(*child) {
+                        text.sections[0].value =
+                            checkbox_label(locale.food_tool, settings.food_tool);
+                    }
+                }
+            }
         }
     }
 }
@@ -464,19 +1056,27 @@ impl Plugin for DebugGUIPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FrameTiming>()
             .init_resource::<GuiSettings>()
+            .init_resource::<Locale>()
             .add_systems(Startup, setup_debug_ui)
             .add_systems(
                 Update,
                 (
                     update_frame_timing,
                     update_debug_ui,
+                    update_state_bar,
                     handle_hide_markers_checkbox,
                     handle_hide_ants_checkbox,
+                    handle_fade_by_state_time_checkbox,
+                    handle_mute_audio_checkbox,
+                    handle_theme_dropdown,
+                    handle_theme_option,
                     handle_hide_gui_checkbox,
-                    toggle_markers_visibility,
+                    handle_food_tool_checkbox,
                     toggle_ants_visibility,
                     handle_gui_hover,
                     update_gui_visibility,
+                    toggle_profiler,
+                    update_profiler_overlay.after(update_frame_timing),
                 ),
             );
     }
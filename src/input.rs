@@ -0,0 +1,199 @@
+use ant_sim_core::config::{Config, KeyBindingsConfig};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    CameraUp,
+    CameraDown,
+    CameraLeft,
+    CameraRight,
+    Screenshot,
+    ToggleRecording,
+    SpawnAnts,
+    ToggleProfiler,
+    ToggleConsole,
+    ExportPheromones,
+    ToggleDashboard,
+    NextWorld,
+    SwitchLayer,
+    FitMap,
+    SaveBookmark,
+}
+
+// Keys bound to each action, built from the keybindings section of the config
+#[derive(Resource)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<KeyCode>>,
+    // Set while a text field takes the keyboard; only the console toggle still works
+    pub captured: bool,
+}
+
+impl InputMap {
+    pub fn from_config(keybindings: &KeyBindingsConfig) -> Self {
+        let actions = [
+            (Action::CameraUp, &keybindings.camera_up),
+            (Action::CameraDown, &keybindings.camera_down),
+            (Action::CameraLeft, &keybindings.camera_left),
+            (Action::CameraRight, &keybindings.camera_right),
+            (Action::Screenshot, &keybindings.screenshot),
+            (Action::ToggleRecording, &keybindings.toggle_recording),
+            (Action::SpawnAnts, &keybindings.spawn_ants),
+            (Action::ToggleProfiler, &keybindings.toggle_profiler),
+            (Action::ToggleConsole, &keybindings.toggle_console),
+            (Action::ExportPheromones, &keybindings.export_pheromones),
+            (Action::ToggleDashboard, &keybindings.toggle_dashboard),
+            (Action::NextWorld, &keybindings.next_world),
+            (Action::SwitchLayer, &keybindings.switch_layer),
+            (Action::FitMap, &keybindings.fit_map),
+            (Action::SaveBookmark, &keybindings.save_bookmark),
+        ];
+
+        let mut bindings = HashMap::new();
+        for (action, names) in actions {
+            let keys = names
+                .iter()
+                .filter_map(|name| {
+                    let key = parse_key(name);
+                    if key.is_none() {
+                        eprintln!("Unknown key {:?} bound to {:?}, ignoring it", name, action);
+                    }
+                    key
+                })
+                .collect();
+            bindings.insert(action, keys);
+        }
+
+        Self {
+            bindings,
+            captured: false,
+        }
+    }
+
+    fn keys(&self, action: Action) -> &[KeyCode] {
+        if self.captured && action != Action::ToggleConsole {
+            return &[];
+        }
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn pressed(&self, action: Action, keyboard_input: &Input<KeyCode>) -> bool {
+        keyboard_input.any_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn just_pressed(&self, action: Action, keyboard_input: &Input<KeyCode>) -> bool {
+        keyboard_input.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    // Number key 1-9 pressed this frame, as an index from 0. These keys aren't rebindable.
+    pub fn just_pressed_digit(&self, keyboard_input: &Input<KeyCode>) -> Option<usize> {
+        if self.captured {
+            return None;
+        }
+        DIGIT_KEYS
+            .iter()
+            .position(|key| keyboard_input.just_pressed(*key))
+    }
+}
+
+const DIGIT_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+// Key names are case-insensitive: letters, digits, F1-F12, arrows and common named keys
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let key = match name.to_ascii_uppercase().as_str() {
+        "A" => KeyCode::A,
+        "B" => KeyCode::B,
+        "C" => KeyCode::C,
+        "D" => KeyCode::D,
+        "E" => KeyCode::E,
+        "F" => KeyCode::F,
+        "G" => KeyCode::G,
+        "H" => KeyCode::H,
+        "I" => KeyCode::I,
+        "J" => KeyCode::J,
+        "K" => KeyCode::K,
+        "L" => KeyCode::L,
+        "M" => KeyCode::M,
+        "N" => KeyCode::N,
+        "O" => KeyCode::O,
+        "P" => KeyCode::P,
+        "Q" => KeyCode::Q,
+        "R" => KeyCode::R,
+        "S" => KeyCode::S,
+        "T" => KeyCode::T,
+        "U" => KeyCode::U,
+        "V" => KeyCode::V,
+        "W" => KeyCode::W,
+        "X" => KeyCode::X,
+        "Y" => KeyCode::Y,
+        "Z" => KeyCode::Z,
+        "0" => KeyCode::Key0,
+        "1" => KeyCode::Key1,
+        "2" => KeyCode::Key2,
+        "3" => KeyCode::Key3,
+        "4" => KeyCode::Key4,
+        "5" => KeyCode::Key5,
+        "6" => KeyCode::Key6,
+        "7" => KeyCode::Key7,
+        "8" => KeyCode::Key8,
+        "9" => KeyCode::Key9,
+        "F1" => KeyCode::F1,
+        "F2" => KeyCode::F2,
+        "F3" => KeyCode::F3,
+        "F4" => KeyCode::F4,
+        "F5" => KeyCode::F5,
+        "F6" => KeyCode::F6,
+        "F7" => KeyCode::F7,
+        "F8" => KeyCode::F8,
+        "F9" => KeyCode::F9,
+        "F10" => KeyCode::F10,
+        "F11" => KeyCode::F11,
+        "F12" => KeyCode::F12,
+        "UP" => KeyCode::Up,
+        "DOWN" => KeyCode::Down,
+        "LEFT" => KeyCode::Left,
+        "RIGHT" => KeyCode::Right,
+        "SPACE" => KeyCode::Space,
+        "ENTER" | "RETURN" => KeyCode::Return,
+        "ESCAPE" | "ESC" => KeyCode::Escape,
+        "TAB" => KeyCode::Tab,
+        "BACKSPACE" => KeyCode::Back,
+        "DELETE" => KeyCode::Delete,
+        "HOME" => KeyCode::Home,
+        "END" => KeyCode::End,
+        "PAGEUP" => KeyCode::PageUp,
+        "PAGEDOWN" => KeyCode::PageDown,
+        "SHIFT" | "LSHIFT" => KeyCode::ShiftLeft,
+        "RSHIFT" => KeyCode::ShiftRight,
+        "CTRL" | "LCTRL" => KeyCode::ControlLeft,
+        "RCTRL" => KeyCode::ControlRight,
+        "ALT" | "LALT" => KeyCode::AltLeft,
+        "RALT" => KeyCode::AltRight,
+        "+" | "PLUS" => KeyCode::Plus,
+        "-" | "MINUS" => KeyCode::Minus,
+        "," | "COMMA" => KeyCode::Comma,
+        "." | "PERIOD" => KeyCode::Period,
+        "`" | "GRAVE" | "BACKQUOTE" => KeyCode::Grave,
+        _ => return None,
+    };
+    Some(key)
+}
+
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        let input_map = InputMap::from_config(&app.world.resource::<Config>().keybindings);
+        app.insert_resource(input_map);
+    }
+}
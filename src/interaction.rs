@@ -0,0 +1,158 @@
+use crate::gui::GuiSettings;
+use crate::input::{Action, InputMap};
+use crate::visuals::MainCamera;
+use ant_sim::commands::{CommandRequest, SimCommand};
+use ant_sim_core::ant::{spawn_ant, Caste};
+use ant_sim_core::colony::Colony;
+use ant_sim_core::config::{Config, FoodSourceConfig};
+use ant_sim_core::food::{spawn_food_source, FoodSource};
+use ant_sim_core::grid::{world_to_grid, GRID_CELL_SIZE};
+use ant_sim_core::rng::SimRng;
+use ant_sim_core::spatial::Landmarks;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+// Mouse cursor of the primary window, seen through the main camera
+#[derive(SystemParam)]
+pub struct Cursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<MainCamera>>,
+}
+
+impl Cursor<'_, '_> {
+    // World position under the cursor, if the cursor is over the window
+    pub fn world_position(&self) -> Option<Vec2> {
+        let window = self.windows.get_single().ok()?;
+        let (camera, camera_transform) = self.cameras.get_single().ok()?;
+        let cursor = window.cursor_position()?;
+        camera.viewport_to_world_2d(camera_transform, cursor)
+    }
+}
+
+// Drop a batch of workers at the cursor. They join the colony of the closest base.
+pub fn spawn_ants_at_cursor(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    cursor: Cursor,
+    landmarks: Landmarks,
+    config: Res<Config>,
+    mut rng: ResMut<SimRng>,
+) {
+    if !input_map.just_pressed(Action::SpawnAnts, &keyboard_input) {
+        return;
+    }
+    let Some(cursor_pos) = cursor.world_position() else {
+        return;
+    };
+
+    let colony = landmarks
+        .nearest_base(cursor_pos, None)
+        .map_or(Colony(0), |(_, colony)| colony);
+
+    for _ in 0..config.cursor_spawn_count {
+        spawn_ant(
+            &mut commands,
+            &config,
+            &mut *rng,
+            colony,
+            Caste::Worker,
+            cursor_pos.extend(0.0),
+        );
+    }
+}
+
+// Snapshot of the pheromone field, run as a command so the result shows in the console
+pub fn export_pheromones_on_key(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut requests: EventWriter<CommandRequest>,
+) {
+    if input_map.just_pressed(Action::ExportPheromones, &keyboard_input) {
+        requests.send(CommandRequest(SimCommand::ExportPheromones {
+            csv: false,
+            npy: false,
+        }));
+    }
+}
+
+// Left-click adds a food source on the clicked cell, right-click removes the source under the cursor
+pub fn use_food_tool(
+    mut commands: Commands,
+    mouse_input: Res<Input<MouseButton>>,
+    settings: Res<GuiSettings>,
+    cursor: Cursor,
+    buttons: Query<&Interaction, With<Button>>,
+    food_query: Query<(Entity, &Transform, &FoodSource)>,
+    config: Res<Config>,
+) {
+    if !settings.food_tool {
+        return;
+    }
+    let add = mouse_input.just_pressed(MouseButton::Left);
+    let remove = mouse_input.just_pressed(MouseButton::Right);
+    if !add && !remove {
+        return;
+    }
+    // Clicks on GUI buttons are not meant for the map
+    if buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+    let Some(cursor_pos) = cursor.world_position() else {
+        return;
+    };
+
+    if add {
+        let cell = world_to_grid(cursor_pos);
+        let (width, height) = config.map_size;
+        if cell.0 < 0 || cell.1 < 0 || cell.0 >= width as i32 || cell.1 >= height as i32 {
+            return;
+        }
+        let occupied = food_query
+            .iter()
+            .any(|(_, transform, _)| world_to_grid(transform.translation.truncate()) == cell);
+        if !occupied {
+            let food_config = FoodSourceConfig {
+                location: (cell.0 as u32, cell.1 as u32),
+                quantity: config.placed_food_quantity,
+                radius: 0,
+                respawn_secs: None,
+                quality: 1.0,
+            };
+            spawn_food_source(&mut commands, &food_config, &config);
+        }
+    } else {
+        // Remove the closest source within half a cell of the cursor
+        let closest = food_query
+            .iter()
+            .map(|(entity, transform, food_source)| {
+                let food_pos = transform.translation.truncate();
+                let distance = cursor_pos.distance(food_source.nearest_point(food_pos, cursor_pos));
+                (entity, distance)
+            })
+            .filter(|(_, distance)| *distance <= GRID_CELL_SIZE / 2.0)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((entity, _)) = closest {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CommandRequest>().add_systems(
+            Update,
+            (
+                spawn_ants_at_cursor,
+                export_pheromones_on_key,
+                use_food_tool,
+            ),
+        );
+    }
+}
@@ -1,2 +1,7 @@
 pub mod chart_data;
 pub mod chart_generator;
+pub mod commands;
+pub mod entity_export;
+pub mod network;
+pub mod pheromones;
+pub mod run_manifest;
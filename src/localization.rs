@@ -0,0 +1,229 @@
+use ant_sim_core::config::{Config, Language, ThemeName};
+use ant_sim_core::marker::MarkerType;
+use ant_sim_core::milestone::Milestone;
+use bevy::prelude::*;
+
+// Every piece of text shown in the GUI, one table per language
+pub struct Strings {
+    pub frame_time: &'static str,
+    pub avg_frame_time: &'static str,
+    pub sim_time: &'static str,
+    pub tick: &'static str,
+    pub ants: &'static str,
+    pub searching: &'static str,
+    pub returning: &'static str,
+    pub resting: &'static str,
+    pub carrying_corpses: &'static str,
+    pub markers: &'static str,
+    pub base: &'static str,
+    pub food: &'static str,
+    pub danger: &'static str,
+    pub food_left: &'static str,
+    pub hide_base_markers: &'static str,
+    pub hide_food_markers: &'static str,
+    pub hide_danger_markers: &'static str,
+    pub hide_ants: &'static str,
+    pub fade_by_state_time: &'static str,
+    pub mute_sounds: &'static str,
+    pub food_tool: &'static str,
+    pub hide_gui: &'static str,
+    pub profiler_frame: &'static str,
+    pub profiler_avg: &'static str,
+    pub profiler_peak: &'static str,
+    pub selected_ants: &'static str,
+    pub avg_speed: &'static str,
+    pub avg_time_in_state: &'static str,
+    pub despawn_selection: &'static str,
+    pub recolor_selection: &'static str,
+    pub theme: &'static str,
+    pub theme_light: &'static str,
+    pub theme_dark: &'static str,
+    pub theme_high_contrast: &'static str,
+    pub dashboard: &'static str,
+    pub ants_per_colony: &'static str,
+    pub food_per_minute: &'static str,
+    pub max: &'static str,
+    pub colony: &'static str,
+    pub food_delivered: &'static str,
+    pub food_per_ant_minute: &'static str,
+    pub avg_trip: &'static str,
+    pub kills: &'static str,
+    pub deaths: &'static str,
+    pub main_world: &'static str,
+    pub brood: &'static str,
+    pub eggs: &'static str,
+    pub larvae: &'static str,
+    pub pupae: &'static str,
+    pub milestone: &'static str,
+    pub first_food_found: &'static str,
+    pub first_stable_trail: &'static str,
+    pub population_doubled: &'static str,
+    pub trails: &'static str,
+    pub longest_trail: &'static str,
+    pub trail_stability: &'static str,
+}
+
+const ENGLISH: Strings = Strings {
+    frame_time: "Frame Time",
+    avg_frame_time: "Avg Frame Time",
+    sim_time: "Sim Time",
+    tick: "tick",
+    ants: "Ants",
+    searching: "Searching",
+    returning: "Returning",
+    resting: "Resting",
+    carrying_corpses: "Carrying corpses",
+    markers: "Markers",
+    base: "Base",
+    food: "Food",
+    danger: "Danger",
+    food_left: "Food left",
+    hide_base_markers: "Hide Base Markers",
+    hide_food_markers: "Hide Food Markers",
+    hide_danger_markers: "Hide Danger Markers",
+    hide_ants: "Hide Ants",
+    fade_by_state_time: "Fade by Time in State",
+    mute_sounds: "Mute Sounds",
+    food_tool: "Food Tool (click: add, right-click: remove)",
+    hide_gui: "Hide GUI",
+    profiler_frame: "Frame",
+    profiler_avg: "avg",
+    profiler_peak: "peak",
+    selected_ants: "Selected Ants",
+    avg_speed: "Avg Speed",
+    avg_time_in_state: "Avg Time in State",
+    despawn_selection: "Despawn Selection",
+    recolor_selection: "Recolor Selection",
+    theme: "Theme",
+    theme_light: "Light",
+    theme_dark: "Dark",
+    theme_high_contrast: "High Contrast",
+    dashboard: "Colony Dashboard",
+    ants_per_colony: "Ants per colony",
+    food_per_minute: "Food delivered per minute",
+    max: "max",
+    colony: "Colony",
+    food_delivered: "Food delivered",
+    food_per_ant_minute: "Food / ant-minute",
+    avg_trip: "Avg trip",
+    kills: "Kills",
+    deaths: "Deaths",
+    main_world: "Main world",
+    brood: "Brood",
+    eggs: "Eggs",
+    larvae: "Larvae",
+    pupae: "Pupae",
+    milestone: "Milestone",
+    first_food_found: "First food found",
+    first_stable_trail: "First stable trail",
+    population_doubled: "Population doubled",
+    trails: "Trails",
+    longest_trail: "Longest",
+    trail_stability: "Stability",
+};
+
+const FRENCH: Strings = Strings {
+    frame_time: "Durée d'image",
+    avg_frame_time: "Durée d'image moy.",
+    sim_time: "Temps simulé",
+    tick: "pas",
+    ants: "Fourmis",
+    searching: "En recherche",
+    returning: "En retour",
+    resting: "Au repos",
+    carrying_corpses: "Portent un cadavre",
+    markers: "Marqueurs",
+    base: "Nid",
+    food: "Nourriture",
+    danger: "Danger",
+    food_left: "Nourriture restante",
+    hide_base_markers: "Masquer les marqueurs du nid",
+    hide_food_markers: "Masquer les marqueurs de nourriture",
+    hide_danger_markers: "Masquer les marqueurs de danger",
+    hide_ants: "Masquer les fourmis",
+    fade_by_state_time: "Assombrir selon le temps dans l'état",
+    mute_sounds: "Couper le son",
+    food_tool: "Outil nourriture (clic : ajouter, clic droit : retirer)",
+    hide_gui: "Masquer l'interface",
+    profiler_frame: "Image",
+    profiler_avg: "moy.",
+    profiler_peak: "pic",
+    selected_ants: "Fourmis sélectionnées",
+    avg_speed: "Vitesse moy.",
+    avg_time_in_state: "Temps moy. dans l'état",
+    despawn_selection: "Supprimer la sélection",
+    recolor_selection: "Recolorer la sélection",
+    theme: "Thème",
+    theme_light: "Clair",
+    theme_dark: "Sombre",
+    theme_high_contrast: "Contraste élevé",
+    dashboard: "Tableau de bord des colonies",
+    ants_per_colony: "Fourmis par colonie",
+    food_per_minute: "Nourriture livrée par minute",
+    max: "max",
+    colony: "Colonie",
+    food_delivered: "Nourriture livrée",
+    food_per_ant_minute: "Nourriture / fourmi-minute",
+    avg_trip: "Trajet moy.",
+    kills: "Victimes",
+    deaths: "Pertes",
+    main_world: "Monde principal",
+    brood: "Couvain",
+    eggs: "Œufs",
+    larvae: "Larves",
+    pupae: "Nymphes",
+    milestone: "Étape franchie",
+    first_food_found: "Première nourriture trouvée",
+    first_stable_trail: "Première piste stable",
+    population_doubled: "Population doublée",
+    trails: "Pistes",
+    longest_trail: "La plus longue",
+    trail_stability: "Stabilité",
+};
+
+impl Strings {
+    pub fn theme_name(&self, name: ThemeName) -> &'static str {
+        match name {
+            ThemeName::Light => self.theme_light,
+            ThemeName::Dark => self.theme_dark,
+            ThemeName::HighContrast => self.theme_high_contrast,
+        }
+    }
+
+    pub fn hide_markers(&self, marker_type: MarkerType) -> &'static str {
+        match marker_type {
+            MarkerType::Base => self.hide_base_markers,
+            MarkerType::Food => self.hide_food_markers,
+            MarkerType::Danger => self.hide_danger_markers,
+        }
+    }
+
+    pub fn milestone_name(&self, milestone: Milestone, delivery_target: u32) -> String {
+        match milestone {
+            Milestone::FirstFoodFound => self.first_food_found.to_string(),
+            Milestone::FirstStableTrail => self.first_stable_trail.to_string(),
+            Milestone::DeliveryTarget => format!("{}: {}", self.food_delivered, delivery_target),
+            Milestone::PopulationDoubled => self.population_doubled.to_string(),
+        }
+    }
+}
+
+// Checkbox label with its checked state in front
+pub fn checkbox_label(label: &str, checked: bool) -> String {
+    let checkbox = if checked { "☑" } else { "☐" };
+    format!("{} {}", checkbox, label)
+}
+
+// GUI strings in the configured language
+#[derive(Resource, Deref)]
+pub struct Locale(&'static Strings);
+
+impl FromWorld for Locale {
+    fn from_world(world: &mut World) -> Self {
+        let strings = match world.resource::<Config>().language {
+            Language::En => &ENGLISH,
+            Language::Fr => &FRENCH,
+        };
+        Locale(strings)
+    }
+}
@@ -1,20 +1,57 @@
-use crate::ant::{Ant, AntState};
+use crate::capture::CaptureSettings;
 use crate::gui::FrameTiming;
-use crate::marker::{Marker, MarkerType};
+use ant_sim::chart_data::RunMetadata;
+use ant_sim::commands::ExportDir;
+use ant_sim::run_manifest::{RunManifest, MANIFEST_FILE};
+use ant_sim_core::ant::{Ant, AntState};
+use ant_sim_core::brood::{BroodCounts, BroodStats};
+use ant_sim_core::clock::SimClock;
+use ant_sim_core::colony::{ColonyStats, ColonyTotals, TripDurations, TripSummary};
+use ant_sim_core::config::{Config, LogRotationConfig};
+use ant_sim_core::grid::GridMap;
+use ant_sim_core::marker::{Marker, MarkerType};
+use ant_sim_core::nest::NestStats;
+use ant_sim_core::profiling::PROFILED_SYSTEMS;
+use ant_sim_core::rng::SimRng;
+use ant_sim_core::summary::SimulationEnded;
+use ant_sim_core::trail::TrailStats;
+use bevy::app::AppExit;
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::ecs::entity::Entities;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use std::fs::OpenOptions;
+use bevy::time::TimeUpdateStrategy;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::Instant;
 
 #[derive(Resource)]
 pub struct SimulationLogger {
     log_timer: Timer,
+    logs_dir: PathBuf,
+    // File name without extension, shared by all segments of this run
+    run_name: String,
     file_path: PathBuf,
     header_written: bool,
+    // Whether rows carry the detailed_logging columns
+    detailed: bool,
+    rotation: LogRotationConfig,
+    segment: u32,
+    segment_started: Instant,
+    // Closed segments still being compressed in the background
+    compressing: Vec<JoinHandle<()>>,
 }
 
 impl SimulationLogger {
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(
+        rotation: &LogRotationConfig,
+        detailed: bool,
+        suffix: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         // Create logs directory if it doesn't exist
         let logs_dir = PathBuf::from("logs");
         if !logs_dir.exists() {
@@ -23,45 +60,174 @@ impl SimulationLogger {
 
         // Generate timestamped filename
         let now = chrono::Local::now();
-        let filename = format!("simulation_{}.csv", now.format("%Y-%m-%d_%H-%M-%S"));
-        let file_path = logs_dir.join(filename);
+        let mut run_name = format!("simulation_{}", now.format("%Y-%m-%d_%H-%M-%S"));
+        // Worlds started together share a timestamp, the suffix keeps their logs apart
+        if let Some(suffix) = suffix {
+            run_name = format!("{}_{}", run_name, suffix);
+        }
 
-        Ok(Self {
+        let mut logger = Self {
             log_timer: Timer::from_seconds(1.0, TimerMode::Repeating),
-            file_path,
+            logs_dir,
+            run_name,
+            file_path: PathBuf::new(),
             header_written: false,
-        })
+            detailed,
+            rotation: rotation.clone(),
+            segment: 0,
+            segment_started: Instant::now(),
+            compressing: Vec::new(),
+        };
+        logger.file_path = logger.segment_path(0);
+        Ok(logger)
+    }
+
+    // simulation_<timestamp>.csv, or simulation_<timestamp>_partNNN.csv when rotating
+    fn segment_path(&self, segment: u32) -> PathBuf {
+        let filename = if self.rotation.is_enabled() {
+            format!("{}_part{:03}.csv", self.run_name, segment)
+        } else {
+            format!("{}.csv", self.run_name)
+        };
+        self.logs_dir.join(filename)
+    }
+
+    // Folder for files exported during this run, next to its log
+    pub fn run_dir(&self) -> PathBuf {
+        self.logs_dir.join(&self.run_name)
+    }
+
+    fn metadata_path(&self) -> PathBuf {
+        self.logs_dir.join(format!("{}.json", self.run_name))
+    }
+
+    // Describe the run in <run name>.json next to the log so charts can tell runs apart
+    fn write_metadata(&self, config: &Config, seed: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let metadata = RunMetadata::for_current_build(serde_json::to_value(config)?, seed);
+        metadata.write(&self.metadata_path())
+    }
+
+    // Log segments written so far, compressed or not, and the metadata sidecar
+    fn written_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for segment in 0..=self.segment {
+            let path = self.segment_path(segment);
+            let mut compressed = path.clone().into_os_string();
+            compressed.push(".gz");
+            files.push(path);
+            files.push(compressed.into());
+        }
+        files.push(self.metadata_path());
+        files.retain(|path| path.exists());
+        files
+    }
+
+    fn should_rotate(&self) -> bool {
+        let too_large = self.rotation.max_size_mb.is_some_and(|max_size_mb| {
+            std::fs::metadata(&self.file_path)
+                .is_ok_and(|metadata| metadata.len() as f64 >= max_size_mb as f64 * 1024.0 * 1024.0)
+        });
+        let too_old = self.rotation.max_hours.is_some_and(|max_hours| {
+            self.segment_started.elapsed().as_secs_f32() >= max_hours * 3600.0
+        });
+        too_large || too_old
     }
 
-    fn write_header(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    // Close the current segment and continue in a new one with its own header
+    fn rotate(&mut self) {
+        self.segment += 1;
+        let next_path = self.segment_path(self.segment);
+        let closed = std::mem::replace(&mut self.file_path, next_path);
+        self.header_written = false;
+        self.segment_started = Instant::now();
+
+        if self.rotation.compress {
+            // Compress in the background so large segments don't stall a frame
+            self.compressing.retain(|handle| !handle.is_finished());
+            self.compressing.push(std::thread::spawn(move || {
+                if let Err(e) = compress_file(&closed) {
+                    eprintln!("Error compressing log segment {}: {}", closed.display(), e);
+                }
+            }));
+        }
+    }
+
+    // Compress the last segment when the simulation exits, after waiting for the segments
+    // still being compressed
+    pub fn close(&mut self) {
+        for handle in self.compressing.drain(..) {
+            if handle.join().is_err() {
+                eprintln!("Log segment compression panicked");
+            }
+        }
+        if self.rotation.compress && self.file_path.exists() {
+            if let Err(e) = compress_file(&self.file_path) {
+                eprintln!(
+                    "Error compressing log segment {}: {}",
+                    self.file_path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    fn write_header(
+        &mut self,
+        colony_count: usize,
+        stored_food: &[Vec<u32>],
+        brood_colonies: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&self.file_path)?;
 
+        // Detailed and per-colony columns follow the fixed ones
+        let detailed_columns = if self.detailed {
+            let mut columns =
+                String::from(",total_entities,grid_cells,grid_memory_kb,resident_memory_mb");
+            for (_, name) in PROFILED_SYSTEMS {
+                columns.push_str(&format!(",{}_ms", name));
+            }
+            columns
+        } else {
+            String::new()
+        };
+        let mut colony_columns = String::new();
+        for colony in 0..colony_count {
+            colony_columns.push_str(&format!(
+                ",colony{0}_food_delivered,colony{0}_kills,colony{0}_deaths",
+                colony
+            ));
+        }
+        // Food stored in each food chamber, over the colony's nests
+        for (colony, chambers) in stored_food.iter().enumerate() {
+            for chamber in 0..chambers.len() {
+                colony_columns.push_str(&format!(",colony{}_chamber{}_food", colony, chamber));
+            }
+        }
+        for colony in 0..brood_colonies {
+            colony_columns.push_str(&format!(
+                ",colony{0}_eggs,colony{0}_larvae,colony{0}_pupae",
+                colony
+            ));
+        }
+
         writeln!(
             file,
-            "timestamp,frame_time_ms,avg_frame_time_ms,total_ants,searching_ants,returning_ants,total_markers,food_markers,base_markers"
+            "timestamp,frame_time_ms,avg_frame_time_ms,total_ants,searching_ants,returning_ants,total_markers,food_markers,base_markers,resting_ants,sim_time_secs,tick,trips,trip_mean_secs,trip_p95_secs,trails,trail_length,longest_trail,trail_stability{}{}",
+            detailed_columns,
+            colony_columns
         )?;
 
         self.header_written = true;
         Ok(())
     }
 
-    fn write_log_entry(
-        &mut self,
-        frame_time_ms: f32,
-        avg_frame_time_ms: f32,
-        total_ants: usize,
-        searching_ants: usize,
-        returning_ants: usize,
-        total_markers: usize,
-        food_markers: usize,
-        base_markers: usize,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    fn write_log_entry(&mut self, row: &LogRow) -> Result<(), Box<dyn std::error::Error>> {
         // Write header if not written yet
         if !self.header_written {
-            self.write_header()?;
+            self.write_header(row.colony_totals.len(), row.stored_food, row.brood.len())?;
         }
 
         let mut file = OpenOptions::new()
@@ -69,21 +235,68 @@ impl SimulationLogger {
             .append(true)
             .open(&self.file_path)?;
 
+        let mut colony_values = String::new();
+        for totals in row.colony_totals {
+            colony_values.push_str(&format!(
+                ",{},{},{}",
+                totals.food_delivered, totals.kills, totals.deaths
+            ));
+        }
+        for food in row.stored_food.iter().flatten() {
+            colony_values.push_str(&format!(",{}", food));
+        }
+        for counts in row.brood {
+            colony_values.push_str(&format!(
+                ",{},{},{}",
+                counts.eggs, counts.larvae, counts.pupae
+            ));
+        }
+
+        // Trip columns stay empty for intervals without deliveries
+        let trip_values = row.trips.map_or_else(
+            || "0,,".to_string(),
+            |trips| {
+                format!(
+                    "{},{:.2},{:.2}",
+                    trips.count, trips.mean_secs, trips.p95_secs
+                )
+            },
+        );
+
+        let trail_values = format!(
+            "{},{:.0},{:.0},{:.3}",
+            row.trails.trails.len(),
+            row.trails.total_length(),
+            row.trails.longest(),
+            row.trails.stability
+        );
+
         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
         writeln!(
             file,
-            "{},{:.2},{:.2},{},{},{},{},{},{}",
+            "{},{:.2},{:.2},{},{},{},{},{},{},{},{:.3},{},{},{}{}{}",
             timestamp,
-            frame_time_ms,
-            avg_frame_time_ms,
-            total_ants,
-            searching_ants,
-            returning_ants,
-            total_markers,
-            food_markers,
-            base_markers
+            row.frame_time_ms,
+            row.avg_frame_time_ms,
+            row.ants.total,
+            row.ants.searching,
+            row.ants.returning,
+            row.markers.total,
+            row.markers.food,
+            row.markers.base,
+            row.ants.resting,
+            row.clock.elapsed_secs(),
+            row.clock.tick(),
+            trip_values,
+            trail_values,
+            row.detailed_values,
+            colony_values
         )?;
 
+        if self.rotation.is_enabled() && self.should_rotate() {
+            self.rotate();
+        }
+
         Ok(())
     }
 
@@ -99,12 +312,137 @@ impl SimulationLogger {
     }
 }
 
+// Replace path with a gzipped copy at path.gz. It is written to path.gz.tmp first, so a
+// compression cut short never leaves a truncated path.gz next to the original for the chart
+// loader to pick up.
+fn compress_file(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut compressed_path = path.as_os_str().to_owned();
+    compressed_path.push(".gz");
+    let mut partial_path = compressed_path.clone();
+    partial_path.push(".tmp");
+
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&partial_path)?, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::rename(&partial_path, &compressed_path)?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+// Resident memory of this process, only known where /proc is (assumes 4 KiB pages)
+pub fn resident_memory_mb() -> Option<f64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages as f64 * 4096.0 / (1024.0 * 1024.0))
+}
+
+#[derive(Default)]
+struct AntCounts {
+    total: usize,
+    searching: usize,
+    returning: usize,
+    resting: usize,
+}
+
+#[derive(Default)]
+struct MarkerCounts {
+    total: usize,
+    food: usize,
+    base: usize,
+}
+
+// Everything written in one row of the log
+struct LogRow<'a> {
+    frame_time_ms: f32,
+    avg_frame_time_ms: f32,
+    ants: AntCounts,
+    markers: MarkerCounts,
+    clock: &'a SimClock,
+    trips: Option<TripSummary>,
+    trails: &'a TrailStats,
+    // Empty unless detailed_logging is on
+    detailed_values: String,
+    colony_totals: &'a [ColonyTotals],
+    stored_food: &'a [Vec<u32>],
+    brood: &'a [BroodCounts],
+}
+
+// Where the run stands, how long trips took since the last row, what the nests hold and
+// raise, the trails found, and what the run is made of for detailed_logging
+#[derive(SystemParam)]
+pub struct RunTelemetry<'w> {
+    clock: Res<'w, SimClock>,
+    trips: ResMut<'w, TripDurations>,
+    entities: &'w Entities,
+    grid_map: Res<'w, GridMap>,
+    diagnostics: Res<'w, DiagnosticsStore>,
+    nests: Res<'w, NestStats>,
+    brood: Res<'w, BroodStats>,
+    trails: Res<'w, TrailStats>,
+}
+
+impl RunTelemetry<'_> {
+    // Values of the detailed columns, each with its leading comma
+    fn detailed_values(&self) -> String {
+        let mut values = format!(
+            ",{},{},{:.1},{}",
+            self.entities.len(),
+            self.grid_map.allocated_cells(),
+            self.grid_map.memory_bytes() as f64 / 1024.0,
+            resident_memory_mb().map_or(String::new(), |mb| format!("{:.1}", mb))
+        );
+        // Last frame's times, as this frame's are recorded at its end
+        for (id, _) in PROFILED_SYSTEMS {
+            let ms = self
+                .diagnostics
+                .get(id)
+                .and_then(|diagnostic| diagnostic.value())
+                .unwrap_or(0.0);
+            values.push_str(&format!(",{:.3}", ms));
+        }
+        values
+    }
+
+    // Takes the trips made since the last row
+    fn log_row<'a>(
+        &'a mut self,
+        frame_timing: &FrameTiming,
+        ants: AntCounts,
+        markers: MarkerCounts,
+        colony_stats: &'a ColonyStats,
+        detailed: bool,
+    ) -> LogRow<'a> {
+        let detailed_values = if detailed {
+            self.detailed_values()
+        } else {
+            String::new()
+        };
+        let trips = self.trips.take_summary();
+        LogRow {
+            frame_time_ms: frame_timing.current_ms(),
+            avg_frame_time_ms: frame_timing.average_ms(),
+            ants,
+            markers,
+            clock: &self.clock,
+            trips,
+            trails: &self.trails,
+            detailed_values,
+            colony_totals: &colony_stats.colonies,
+            stored_food: &self.nests.colonies,
+            brood: &self.brood.colonies,
+        }
+    }
+}
+
 pub fn log_simulation_stats(
     mut logger: ResMut<SimulationLogger>,
     time: Res<Time>,
     frame_timing: Res<FrameTiming>,
     ants: Query<&Ant>,
     markers: Query<&Marker>,
+    colony_stats: Res<ColonyStats>,
+    mut telemetry: RunTelemetry,
 ) {
     let frame_time_ms = frame_timing.current_ms();
 
@@ -114,54 +452,193 @@ pub fn log_simulation_stats(
     }
 
     // Count ants by state
-    let mut searching_count = 0;
-    let mut returning_count = 0;
+    let mut ant_counts = AntCounts::default();
     for ant in ants.iter() {
         match ant.state {
-            AntState::Searching => searching_count += 1,
-            AntState::Returning => returning_count += 1,
+            AntState::Searching => ant_counts.searching += 1,
+            AntState::Returning => ant_counts.returning += 1,
+            AntState::Resting => ant_counts.resting += 1,
+            AntState::CarryingCorpse => {}
         }
+        ant_counts.total += 1;
     }
-    let total_ants = searching_count + returning_count;
 
     // Count markers by type
-    let mut base_marker_count = 0;
-    let mut food_marker_count = 0;
+    let mut marker_counts = MarkerCounts::default();
     for marker in markers.iter() {
         match marker.marker_type {
-            MarkerType::Base => base_marker_count += 1,
-            MarkerType::Food => food_marker_count += 1,
-        }
-    }
-    let total_markers = base_marker_count + food_marker_count;
-
-    // Write log entry
-    if let Err(e) = logger.write_log_entry(
-        frame_time_ms,
-        frame_timing.average_ms(),
-        total_ants,
-        searching_count,
-        returning_count,
-        total_markers,
-        food_marker_count,
-        base_marker_count,
-    ) {
+            MarkerType::Base => marker_counts.base += 1,
+            MarkerType::Food => marker_counts.food += 1,
+            MarkerType::Danger => {}
+        }
+        marker_counts.total += 1;
+    }
+
+    let detailed = logger.detailed;
+    let row = telemetry.log_row(
+        &frame_timing,
+        ant_counts,
+        marker_counts,
+        &colony_stats,
+        detailed,
+    );
+    if let Err(e) = logger.write_log_entry(&row) {
         eprintln!("Error writing log entry: {}", e);
     }
 }
 
-pub struct LoggingPlugin;
+// Runs once the simulation is set up, when the seed in use is known
+pub fn write_run_metadata(logger: Res<SimulationLogger>, config: Res<Config>, rng: Res<SimRng>) {
+    if let Err(e) = logger.write_metadata(&config, rng.seed()) {
+        eprintln!("Error writing run metadata: {}", e);
+    }
+}
+
+// Top-level files and folders of dir, none if it was never created
+fn dir_entries(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    paths.sort();
+    paths
+}
+
+// Everything a run writes, for its manifest
+#[derive(SystemParam)]
+pub struct RunOutputs<'w> {
+    logger: Res<'w, SimulationLogger>,
+    export_dir: Res<'w, ExportDir>,
+    capture: Option<Res<'w, CaptureSettings>>,
+    config: Res<'w, Config>,
+}
+
+impl RunOutputs<'_> {
+    // The summary goes to summary_path, which later runs overwrite, so the run keeps a copy
+    fn copy_summary(&self) -> Result<(), std::io::Error> {
+        let summary_path = Path::new(&self.config.end_conditions.summary_path);
+        let file_name = summary_path
+            .file_name()
+            .unwrap_or_else(|| std::ffi::OsStr::new("summary.json"));
+        std::fs::create_dir_all(&self.export_dir.0)?;
+        std::fs::copy(summary_path, self.export_dir.0.join(file_name))?;
+        Ok(())
+    }
+
+    fn artifacts(&self) -> Vec<PathBuf> {
+        let mut artifacts = self.logger.written_files();
+        artifacts.extend(
+            dir_entries(&self.export_dir.0)
+                .into_iter()
+                .filter(|path| !path.ends_with(MANIFEST_FILE)),
+        );
+        if let Some(capture) = &self.capture {
+            artifacts.extend(dir_entries(capture.dir()));
+        }
+        artifacts
+    }
+}
+
+// Written at startup so even a run that crashes leaves its config and seed behind
+pub fn start_run_manifest(
+    mut commands: Commands,
+    export_dir: Res<ExportDir>,
+    config: Res<Config>,
+    rng: Res<SimRng>,
+    time_strategy: Res<TimeUpdateStrategy>,
+) {
+    let tick = match *time_strategy {
+        TimeUpdateStrategy::ManualDuration(tick) => Some(tick),
+        _ => None,
+    };
+    let manifest = match RunManifest::start(&config, rng.seed(), tick) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("Error creating run manifest: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = manifest.write(&export_dir.0) {
+        eprintln!("Error writing run manifest: {}", e);
+    }
+    commands.insert_resource(manifest);
+}
+
+// Record the end time and the files written once the run ends, and again on exit for files
+// written after that
+pub fn finish_run_manifest(
+    manifest: Option<ResMut<RunManifest>>,
+    outputs: RunOutputs,
+    mut exit_events: EventReader<AppExit>,
+    mut ended_events: EventReader<SimulationEnded>,
+) {
+    let ended = ended_events.read().next().is_some();
+    let exiting = exit_events.read().next().is_some();
+    if !ended && !exiting {
+        return;
+    }
+    let Some(mut manifest) = manifest else {
+        return;
+    };
+    if ended {
+        if let Err(e) = outputs.copy_summary() {
+            eprintln!("Error copying summary to the run folder: {}", e);
+        }
+    }
+    if manifest.ended_at.is_none() {
+        manifest.ended_at = Some(chrono::Local::now().to_rfc3339());
+    }
+    manifest.artifacts = outputs.artifacts();
+    if let Err(e) = manifest.write(&outputs.export_dir.0) {
+        eprintln!("Error writing run manifest: {}", e);
+    }
+}
+
+pub fn close_log(mut logger: ResMut<SimulationLogger>, mut exit_events: EventReader<AppExit>) {
+    if exit_events.read().next().is_some() {
+        logger.close();
+    }
+}
+
+// Logs the simulation of the app it is added to. Extra worlds add one each, with a suffix
+// appended to the run name.
+pub struct LoggingPlugin {
+    pub suffix: Option<String>,
+}
 
 impl Plugin for LoggingPlugin {
     fn build(&self, app: &mut App) {
         // Initialize logger resource
-        match SimulationLogger::new() {
+        let config = app.world.resource::<Config>();
+        match SimulationLogger::new(
+            &config.log_rotation,
+            config.detailed_logging,
+            self.suffix.as_deref(),
+        ) {
             Ok(logger) => {
+                app.insert_resource(ExportDir(logger.run_dir()));
                 app.insert_resource(logger);
+                app.add_systems(PostStartup, (write_run_metadata, start_run_manifest));
                 app.add_systems(
                     Update,
                     log_simulation_stats.after(crate::gui::update_frame_timing),
                 );
+                app.add_systems(
+                    Update,
+                    crate::spikes::capture_frame_spikes.after(crate::gui::update_frame_timing),
+                );
+                app.add_systems(
+                    Last,
+                    (
+                        close_log,
+                        // Once the last log segment is compressed and the recording encoded
+                        finish_run_manifest
+                            .after(close_log)
+                            .after(crate::capture::finish_recording),
+                    ),
+                );
             }
             Err(e) => {
                 eprintln!("Failed to initialize simulation logger: {}", e);
@@ -1,50 +1,230 @@
 use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
 
-mod ant;
-mod base;
-mod chart_data;
-mod chart_generator;
-mod config;
-mod food;
+mod audio;
+mod capture;
+mod comparison;
+mod console;
+mod dashboard;
+mod frame_budget;
 mod gui;
+mod input;
+mod interaction;
+mod localization;
 mod logging;
-mod marker;
-mod simulation;
+mod milestones;
+mod mirror;
+mod nest_view;
+mod saved_settings;
+mod selection;
+mod spikes;
+mod sprites;
+mod stress;
+mod theme;
+mod visuals;
+mod worlds;
 
-use config::Config;
+use ant_sim::commands::SimCommandsPlugin;
+use ant_sim::network::{NetworkClientPlugin, SnapshotClient};
+use ant_sim::run_manifest::{RunManifest, MANIFEST_FILE};
+use ant_sim_core::config::Config;
+use ant_sim_core::simulation::SimulationPlugin;
+use audio::SimAudioPlugin;
+use capture::CapturePlugin;
+use clap::Parser;
+use comparison::ComparisonPlugin;
+use console::ConsolePlugin;
+use dashboard::DashboardPlugin;
+use frame_budget::FrameBudgetPlugin;
 use gui::DebugGUIPlugin;
+use input::InputMapPlugin;
+use interaction::InteractionPlugin;
 use logging::LoggingPlugin;
-use simulation::SimulationPlugin;
+use milestones::MilestonePlugin;
+use nest_view::NestViewPlugin;
+use saved_settings::SavedSettingsPlugin;
+use selection::SelectionPlugin;
+use sprites::SpriteAssetsPlugin;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use stress::StressPlugin;
+use theme::ThemePlugin;
+use visuals::{MainCamera, VisualsPlugin};
+use worlds::WorldsPlugin;
+
+#[derive(Parser)]
+#[command(name = "ant-sim")]
+#[command(about = "Ant colony simulation")]
+struct Args {
+    /// Config of a second simulation run side by side with the main one
+    #[arg(long, value_name = "CONFIG")]
+    compare: Option<PathBuf>,
+
+    /// Watch a simulation served by sim_server at this address instead of running one
+    #[arg(long, value_name = "ADDR", conflicts_with = "compare")]
+    connect: Option<String>,
+
+    /// Spawn this many ants at launch and run in stress mode, printing throughput
+    #[arg(long, value_name = "N", conflicts_with = "connect")]
+    stress: Option<u32>,
+
+    /// Config of an extra simulation run in its own world above the main one; repeatable
+    #[arg(long = "world", value_name = "CONFIG", conflicts_with = "connect")]
+    worlds: Vec<PathBuf>,
+
+    /// Run again with the config, seed and tick recorded in run.json of this run folder (logs/<run>)
+    #[arg(long, value_name = "DIR", conflicts_with = "connect")]
+    replay_run: Option<PathBuf>,
+
+    /// Advance the simulation by this many milliseconds every frame instead of by the time the
+    /// frame took, so the run can be replayed exactly
+    #[arg(
+        long,
+        value_name = "MS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["connect", "replay_run"]
+    )]
+    tick_ms: Option<u64>,
+}
+
+// Config of a run recorded in run_dir, with its seed pinned, and the tick it ran with
+fn load_replay(run_dir: &Path) -> (Config, Option<Duration>) {
+    let manifest = RunManifest::load(run_dir).unwrap_or_else(|e| {
+        panic!(
+            "Failed to load {}: {}",
+            run_dir.join(MANIFEST_FILE).display(),
+            e
+        )
+    });
+    let config = manifest
+        .replay_config()
+        .unwrap_or_else(|e| panic!("Failed to read the config of {}: {}", run_dir.display(), e));
+    if manifest.other_build() {
+        eprintln!(
+            "{} was recorded by version {} ({}), it may play out differently with this build",
+            run_dir.display(),
+            manifest.metadata.app_version,
+            manifest
+                .metadata
+                .git_hash
+                .as_deref()
+                .unwrap_or("unknown commit")
+        );
+    }
+    if manifest.tick().is_none() {
+        eprintln!(
+            "{} ran on the wall clock instead of a fixed --tick-ms, the replay will play out differently",
+            run_dir.display()
+        );
+    }
+    println!(
+        "Replaying {} with seed {}",
+        run_dir.display(),
+        config
+            .seed
+            .map_or_else(|| "-".to_string(), |seed| seed.to_string())
+    );
+    (config, manifest.tick())
+}
 
 fn main() {
-    // Load configuration
-    let config = Config::load().expect("Failed to load config.json");
+    let args = Args::parse();
+
+    // Load configuration, or take the served simulation's when observing a server
+    let (config, client, tick) = match &args.connect {
+        Some(addr) => {
+            let (config, client) = SnapshotClient::connect(addr.as_str())
+                .unwrap_or_else(|e| panic!("Failed to connect to {}: {}", addr, e));
+            (config, Some(client), None)
+        }
+        None => match &args.replay_run {
+            Some(run_dir) => {
+                let (config, tick) = load_replay(run_dir);
+                (config, None, tick)
+            }
+            None => (
+                Config::load().expect("Failed to load config.json"),
+                None,
+                args.tick_ms.map(Duration::from_millis),
+            ),
+        },
+    };
 
     // Window size is independent of map size (can be smaller than map)
     const WINDOW_WIDTH: f32 = 1024.0;
     const WINDOW_HEIGHT: f32 = 768.0;
 
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Ant Simulation".into(),
-                resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
-                resizable: true,
-                ..default()
-            }),
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Ant Simulation".into(),
+            resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
+            resizable: true,
             ..default()
-        }))
-        .insert_resource(config)
-        .insert_resource(ClearColor(Color::rgb(0.3, 0.3, 0.3))) // Darker grey for out-of-bounds
-        .add_plugins(SimulationPlugin)
-        .add_plugins(DebugGUIPlugin)
-        .add_plugins(LoggingPlugin)
-        .add_systems(Startup, setup_camera)
-        .run();
+        }),
+        ..default()
+    }))
+    .insert_resource(config)
+    .add_plugins(SavedSettingsPlugin)
+    .add_plugins(ThemePlugin)
+    .add_plugins(InputMapPlugin)
+    .add_plugins(VisualsPlugin)
+    .add_plugins(SelectionPlugin)
+    .add_plugins(SpriteAssetsPlugin)
+    .add_plugins(DebugGUIPlugin)
+    .add_plugins(FrameBudgetPlugin)
+    .add_plugins(CapturePlugin)
+    .add_plugins(SimAudioPlugin)
+    .add_systems(Startup, setup_camera);
+    if let Some(tick) = tick {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(tick));
+    }
+
+    match client {
+        // The server simulates, logs and owns the world; edits from here wouldn't reach it
+        Some(client) => {
+            app.insert_resource(client).add_plugins(NetworkClientPlugin);
+        }
+        None => {
+            app.add_plugins(SimulationPlugin)
+                .add_plugins(InteractionPlugin)
+                .add_plugins(LoggingPlugin { suffix: None })
+                .add_plugins(SimCommandsPlugin)
+                .add_plugins(ConsolePlugin)
+                .add_plugins(DashboardPlugin)
+                .add_plugins(NestViewPlugin)
+                .add_plugins(MilestonePlugin)
+                .add_plugins(StressPlugin { spawn: args.stress });
+        }
+    }
+
+    if let Some(path) = args.compare {
+        let config = Config::load_from(&path)
+            .unwrap_or_else(|e| panic!("Failed to load {}: {}", path.display(), e));
+        app.add_plugins(ComparisonPlugin {
+            config,
+            label: path.display().to_string(),
+        });
+    }
+
+    if !args.worlds.is_empty() {
+        let worlds = args
+            .worlds
+            .iter()
+            .map(|path| {
+                let config = Config::load_from(path)
+                    .unwrap_or_else(|e| panic!("Failed to load {}: {}", path.display(), e));
+                (path.display().to_string(), config)
+            })
+            .collect();
+        app.add_plugins(WorldsPlugin { worlds });
+    }
+
+    app.run();
 }
 
 fn setup_camera(mut commands: Commands, config: Res<Config>) {
-    use crate::marker::GRID_CELL_SIZE;
+    use ant_sim_core::grid::GRID_CELL_SIZE;
 
     // Map size in config is grid cells, convert to pixels
     let map_width_pixels = config.map_size.0 as f32 * GRID_CELL_SIZE;
@@ -59,5 +239,5 @@ fn setup_camera(mut commands: Commands, config: Res<Config>) {
     // Position camera at map center
     camera.transform = Transform::from_xyz(map_width_pixels / 2.0, map_height_pixels / 2.0, 0.0);
 
-    commands.spawn(camera);
+    commands.spawn((camera, MainCamera));
 }
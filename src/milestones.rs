@@ -0,0 +1,106 @@
+use crate::localization::Locale;
+use crate::theme::{Theme, ThemedNode};
+use ant_sim_core::config::Config;
+use ant_sim_core::milestone::MilestoneReached;
+use bevy::prelude::*;
+
+// Seconds a toast stays on screen, counted in real time so they also go while paused
+const TOAST_SECS: f32 = 6.0;
+
+// Column of toasts under the top edge of the window, newest at the bottom
+#[derive(Component)]
+pub struct ToastStack;
+
+#[derive(Component)]
+pub struct Toast {
+    timer: Timer,
+}
+
+pub fn setup_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(48.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            ..default()
+        },
+        ToastStack,
+    ));
+}
+
+// Announce each milestone with a toast
+pub fn show_milestone_toasts(
+    mut commands: Commands,
+    mut reached_events: EventReader<MilestoneReached>,
+    stack: Query<Entity, With<ToastStack>>,
+    config: Res<Config>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+) {
+    let Ok(stack) = stack.get_single() else {
+        return;
+    };
+    for reached in reached_events.read() {
+        let text = format!(
+            "{}: {} ({:.1} s)",
+            locale.milestone,
+            locale.milestone_name(reached.milestone, config.milestones.delivery_target),
+            reached.sim_secs
+        );
+        let toast = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(8.0)),
+                        ..default()
+                    },
+                    background_color: theme.panel.into(),
+                    ..default()
+                },
+                ThemedNode::Panel,
+                Toast {
+                    timer: Timer::from_seconds(TOAST_SECS, TimerMode::Once),
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 18.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ));
+            })
+            .id();
+        commands.entity(stack).add_child(toast);
+    }
+}
+
+pub fn expire_toasts(
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast)>,
+    time: Res<Time<Real>>,
+) {
+    for (entity, mut toast) in toasts.iter_mut() {
+        if toast.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// Toasts announcing the milestones of the run, also recorded in its summary
+pub struct MilestonePlugin;
+
+impl Plugin for MilestonePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_toast_stack)
+            .add_systems(Update, (show_milestone_toasts, expire_toasts));
+    }
+}
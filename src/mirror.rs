@@ -0,0 +1,229 @@
+use crate::gui::GuiSettings;
+use crate::theme::Theme;
+use crate::visuals::{
+    ant_color, ant_size, base_size, corpse_size, draw_markers, food_size, heavy_food_size,
+    predator_size,
+};
+use ant_sim_core::ant::{Ant, AntState};
+use ant_sim_core::base::Base;
+use ant_sim_core::config::Config;
+use ant_sim_core::corpse::Corpse;
+use ant_sim_core::food::{FoodQuantity, FoodSource};
+use ant_sim_core::marker::{Marker, MarkerType};
+use ant_sim_core::nest::Underground;
+use ant_sim_core::predator::Predator;
+use ant_sim_core::transport::HeavyFood;
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+// Counts of a mirrored world, for its stats panel
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MirrorCounts {
+    pub ants_by_state: [usize; 4],
+    pub markers_by_type: [usize; 3],
+    pub food_left: u32,
+}
+
+fn state_index(state: AntState) -> usize {
+    match state {
+        AntState::Searching => 0,
+        AntState::Returning => 1,
+        AntState::Resting => 2,
+        AntState::CarryingCorpse => 3,
+    }
+}
+
+fn marker_index(marker_type: MarkerType) -> usize {
+    match marker_type {
+        MarkerType::Base => 0,
+        MarkerType::Food => 1,
+        MarkerType::Danger => 2,
+    }
+}
+
+// How one entity of the mirrored world is drawn
+struct MirroredSprite {
+    transform: Transform,
+    color: Color,
+    size: Vec2,
+    visible: bool,
+}
+
+// Sprites in the main world standing in for the entities of a simulation run in a world of
+// its own, keyed by their entity there
+#[derive(Default)]
+pub struct WorldMirror {
+    entities: HashMap<Entity, Entity>,
+}
+
+impl WorldMirror {
+    // Copy the state of world into the main world, drawn at offset, and redraw its marker
+    // layer if it has one. Called from a sub app's extract function, before the sub app is
+    // updated, so the mirrored world is drawn one frame behind.
+    pub fn sync(
+        &mut self,
+        main_world: &mut World,
+        world: &mut World,
+        offset: Vec2,
+        marker_layer: Option<&Handle<Image>>,
+    ) -> MirrorCounts {
+        let offset = offset.extend(0.0);
+        let gui_settings = main_world.resource::<GuiSettings>();
+        let (hide_ants, fade) = (gui_settings.hide_ants, gui_settings.fade_by_state_time);
+        let theme = main_world.resource::<Theme>().clone();
+
+        let mut sprites = HashMap::new();
+        let mut counts = MirrorCounts::default();
+        let moved =
+            |transform: &Transform| transform.with_translation(transform.translation + offset);
+
+        for (entity, transform, ant, underground) in world
+            .query::<(Entity, &Transform, &Ant, Has<Underground>)>()
+            .iter(world)
+        {
+            counts.ants_by_state[state_index(ant.state)] += 1;
+            // Ants down in their nest are not drawn on the surface
+            if underground {
+                continue;
+            }
+            sprites.insert(
+                entity,
+                MirroredSprite {
+                    transform: moved(transform),
+                    color: ant_color(ant, fade, &theme),
+                    size: ant_size(ant.caste),
+                    visible: !hide_ants,
+                },
+            );
+        }
+        for (entity, transform, source, quantity) in world
+            .query::<(Entity, &Transform, &FoodSource, &FoodQuantity)>()
+            .iter(world)
+        {
+            counts.food_left += quantity.quantity;
+            sprites.insert(
+                entity,
+                MirroredSprite {
+                    transform: moved(transform),
+                    color: theme.food,
+                    size: food_size(source),
+                    visible: quantity.quantity > 0,
+                },
+            );
+        }
+        for (entity, transform, item) in world
+            .query::<(Entity, &Transform, &HeavyFood)>()
+            .iter(world)
+        {
+            sprites.insert(
+                entity,
+                MirroredSprite {
+                    transform: moved(transform),
+                    color: theme.heavy_food,
+                    size: heavy_food_size(item),
+                    visible: true,
+                },
+            );
+        }
+        for (entity, transform) in world
+            .query_filtered::<(Entity, &Transform), With<Base>>()
+            .iter(world)
+        {
+            sprites.insert(
+                entity,
+                MirroredSprite {
+                    transform: moved(transform),
+                    color: theme.base,
+                    size: base_size(),
+                    visible: true,
+                },
+            );
+        }
+        for (entity, transform) in world
+            .query_filtered::<(Entity, &Transform), With<Corpse>>()
+            .iter(world)
+        {
+            sprites.insert(
+                entity,
+                MirroredSprite {
+                    transform: moved(transform),
+                    color: theme.corpse,
+                    size: corpse_size(),
+                    visible: true,
+                },
+            );
+        }
+        for (entity, transform) in world
+            .query_filtered::<(Entity, &Transform), With<Predator>>()
+            .iter(world)
+        {
+            sprites.insert(
+                entity,
+                MirroredSprite {
+                    transform: moved(transform),
+                    color: theme.predator,
+                    size: predator_size(),
+                    visible: true,
+                },
+            );
+        }
+
+        let mut markers = world.query::<&Marker>();
+        for marker in markers.iter(world) {
+            counts.markers_by_type[marker_index(marker.marker_type)] += 1;
+        }
+
+        if let Some(layer) = marker_layer {
+            main_world.resource_scope(|main_world, mut images: Mut<Assets<Image>>| {
+                if let Some(image) = images.get_mut(layer) {
+                    draw_markers(
+                        image,
+                        markers.iter(world),
+                        world.resource::<Config>().grid_type,
+                        main_world.resource::<GuiSettings>(),
+                        &theme,
+                    );
+                }
+            });
+        }
+
+        let alive: HashSet<Entity> = sprites.keys().copied().collect();
+        self.entities.retain(|source, mirrored| {
+            let keep = alive.contains(source);
+            if !keep {
+                main_world.despawn(*mirrored);
+            }
+            keep
+        });
+
+        for (source, sprite) in sprites {
+            let visibility = if sprite.visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+            if let Some(&mirrored) = self.entities.get(&source) {
+                let mut entity = main_world.entity_mut(mirrored);
+                *entity.get_mut::<Transform>().unwrap() = sprite.transform;
+                *entity.get_mut::<Visibility>().unwrap() = visibility;
+                entity.get_mut::<Sprite>().unwrap().color = sprite.color;
+            } else {
+                let mirrored = main_world
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: sprite.color,
+                            custom_size: Some(sprite.size),
+                            ..default()
+                        },
+                        transform: sprite.transform,
+                        visibility,
+                        ..default()
+                    })
+                    .id();
+                self.entities.insert(source, mirrored);
+            }
+        }
+
+        counts
+    }
+}
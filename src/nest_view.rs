@@ -0,0 +1,348 @@
+use crate::gui::GuiSettings;
+use crate::input::{Action, InputMap};
+use crate::localization::Locale;
+use crate::theme::{Theme, ThemedSprite};
+use crate::visuals::{ant_color, ant_size, base_size, MainCamera};
+use ant_sim_core::ant::{Ant, Caste};
+use ant_sim_core::brood::Brood;
+use ant_sim_core::colony::Colony;
+use ant_sim_core::config::Config;
+use ant_sim_core::grid::GRID_CELL_SIZE;
+use ant_sim_core::nest::{ChamberKind, Nest, Underground};
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use std::collections::HashMap;
+
+// Render layer of the nests. The surface is drawn on the default layer 0.
+const NEST_LAYER: u8 = 1;
+
+const CHAMBER_SIZE: Vec2 = Vec2::new(64.0, 40.0);
+const TUNNEL_WIDTH: f32 = 10.0;
+
+// Layer the main camera shows, switched with the SwitchLayer key
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewLayer {
+    #[default]
+    Surface,
+    Nest,
+}
+
+// Stored food drawn inside a chamber, rising with how full it is
+#[derive(Component)]
+pub struct ChamberFill {
+    nest: Entity,
+    chamber: usize,
+}
+
+#[derive(Component)]
+pub struct ChamberLabel {
+    nest: Entity,
+    chamber: usize,
+}
+
+fn nest_sprite(color: Color, size: Vec2, translation: Vec3) -> (SpriteBundle, RenderLayers) {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(translation),
+            ..default()
+        },
+        RenderLayers::layer(NEST_LAYER),
+    )
+}
+
+// Straight tunnel between two points of the nest layer
+fn tunnel(from: Vec2, to: Vec2, theme: &Theme) -> (SpriteBundle, RenderLayers, ThemedSprite) {
+    let size = Vec2::new(
+        (to.x - from.x).abs() + TUNNEL_WIDTH,
+        (to.y - from.y).abs() + TUNNEL_WIDTH,
+    );
+    let center = (from + to) / 2.0;
+    let (sprite, layers) = nest_sprite(theme.tunnel, size, center.extend(-0.5));
+    (sprite, layers, ThemedSprite::Tunnel)
+}
+
+pub fn switch_view_layer(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut layer: ResMut<ViewLayer>,
+    mut commands: Commands,
+    cameras: Query<Entity, With<MainCamera>>,
+) {
+    if !input_map.just_pressed(Action::SwitchLayer, &keyboard_input) {
+        return;
+    }
+    *layer = match *layer {
+        ViewLayer::Surface => ViewLayer::Nest,
+        ViewLayer::Nest => ViewLayer::Surface,
+    };
+    let layers = match *layer {
+        ViewLayer::Surface => RenderLayers::default(),
+        ViewLayer::Nest => RenderLayers::layer(NEST_LAYER),
+    };
+    for camera in cameras.iter() {
+        commands.entity(camera).insert(layers);
+    }
+}
+
+// Earth under the whole map, so the nest layer hides the surface entirely
+pub fn setup_soil(mut commands: Commands, config: Res<Config>, theme: Res<Theme>) {
+    let map_size = Vec2::new(config.map_size.0 as f32, config.map_size.1 as f32) * GRID_CELL_SIZE;
+    commands.spawn((
+        nest_sprite(theme.soil, map_size, (map_size / 2.0).extend(-1.0)),
+        ThemedSprite::Soil,
+    ));
+}
+
+// Dig the tunnels and chambers of each new nest below its base
+pub fn draw_nests(
+    mut commands: Commands,
+    nests: Query<(Entity, &Transform, &Colony, &Nest), Added<Nest>>,
+    theme: Res<Theme>,
+) {
+    for (nest_entity, transform, colony, nest) in nests.iter() {
+        let entrance = transform.translation.truncate();
+        let junction = entrance + Nest::junction();
+
+        commands.spawn((
+            nest_sprite(theme.base, base_size(), entrance.extend(-0.4)),
+            ThemedSprite::Base,
+        ));
+        commands.spawn(tunnel(entrance, junction, &theme));
+        for (index, chamber) in nest.chambers.iter().enumerate() {
+            let center = entrance + chamber.position;
+            // Food chambers hang off the gallery, the brood chamber off the shaft's end
+            commands.spawn(tunnel(junction, Vec2::new(center.x, junction.y), &theme));
+            commands.spawn(tunnel(Vec2::new(center.x, junction.y), center, &theme));
+            commands.spawn((
+                nest_sprite(theme.tunnel, CHAMBER_SIZE, center.extend(-0.4)),
+                ThemedSprite::Tunnel,
+            ));
+
+            if chamber.kind == ChamberKind::FoodStorage {
+                commands.spawn((
+                    nest_sprite(theme.food, Vec2::ZERO, center.extend(-0.3)),
+                    ThemedSprite::Food,
+                    ChamberFill {
+                        nest: nest_entity,
+                        chamber: index,
+                    },
+                ));
+            } else {
+                // The queen, in the colony's color
+                commands.spawn(nest_sprite(
+                    theme.colony_color(colony.0),
+                    ant_size(Caste::Soldier) * 2.0,
+                    center.extend(-0.3),
+                ));
+            }
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 14.0,
+                            color: theme.text,
+                            ..default()
+                        },
+                    ),
+                    text_anchor: bevy::sprite::Anchor::BottomCenter,
+                    transform: Transform::from_translation(
+                        (center + Vec2::new(0.0, CHAMBER_SIZE.y / 2.0 + 2.0)).extend(1.0),
+                    ),
+                    ..default()
+                },
+                RenderLayers::layer(NEST_LAYER),
+                ChamberLabel {
+                    nest: nest_entity,
+                    chamber: index,
+                },
+            ));
+        }
+    }
+}
+
+// Fill each food chamber up to its share of the capacity and label it with what it holds
+pub fn update_chambers(
+    nests: Query<(&Transform, &Nest)>,
+    changed: Query<(), Changed<Nest>>,
+    new_labels: Query<(), Added<ChamberLabel>>,
+    mut fills: Query<(&ChamberFill, &mut Sprite, &mut Transform), Without<Nest>>,
+    mut labels: Query<(&ChamberLabel, &mut Text)>,
+    locale: Res<Locale>,
+) {
+    if changed.is_empty() && new_labels.is_empty() && !locale.is_changed() {
+        return;
+    }
+    for (fill, mut sprite, mut transform) in fills.iter_mut() {
+        let Ok((nest_transform, nest)) = nests.get(fill.nest) else {
+            continue;
+        };
+        let chamber = &nest.chambers[fill.chamber];
+        let full = (chamber.stored_food as f32 / nest.capacity.max(1) as f32).min(1.0);
+        let size = Vec2::new(CHAMBER_SIZE.x - 8.0, (CHAMBER_SIZE.y - 8.0) * full);
+        let bottom = nest_transform.translation.truncate() + chamber.position
+            - Vec2::new(0.0, CHAMBER_SIZE.y / 2.0 - 4.0);
+        sprite.custom_size = Some(size);
+        transform.translation = (bottom + Vec2::new(0.0, size.y / 2.0)).extend(-0.3);
+    }
+    for (label, mut text) in labels.iter_mut() {
+        let Ok((_, nest)) = nests.get(label.nest) else {
+            continue;
+        };
+        let chamber = &nest.chambers[label.chamber];
+        if chamber.kind == ChamberKind::FoodStorage {
+            text.sections[0].value =
+                format!("{}: {}/{}", locale.food, chamber.stored_food, nest.capacity);
+        }
+    }
+}
+
+// Label brood chambers with the eggs, larvae and pupae developing in them
+pub fn update_brood_labels(
+    nests: Query<(&Nest, &Brood)>,
+    mut labels: Query<(&ChamberLabel, &mut Text)>,
+    config: Res<Config>,
+    locale: Res<Locale>,
+) {
+    for (label, mut text) in labels.iter_mut() {
+        let Ok((nest, brood)) = nests.get(label.nest) else {
+            continue;
+        };
+        if nest.chambers[label.chamber].kind != ChamberKind::Brood {
+            continue;
+        }
+        let counts = brood.counts(&config.brood);
+        let value = format!(
+            "{}: {} {}, {} {}, {} {}",
+            locale.brood,
+            counts.eggs,
+            locale.eggs,
+            counts.larvae,
+            locale.larvae,
+            counts.pupae,
+            locale.pupae
+        );
+        // Brood ages every frame, only lay the text out again when the counts change
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+// Draw ants in the nest on the nest layer and hide them from the surface until they leave
+pub fn draw_underground_ants(
+    mut commands: Commands,
+    ants: Query<(Entity, &Ant, &Underground)>,
+    nests: Query<&Transform, With<Nest>>,
+    mut sprites: Query<(&mut Transform, &mut Sprite), Without<Nest>>,
+    mut mirrored: Local<HashMap<Entity, Entity>>,
+    gui_settings: Res<GuiSettings>,
+    theme: Res<Theme>,
+) {
+    mirrored.retain(|ant, sprite| {
+        let keep = ants.contains(*ant);
+        if !keep {
+            commands.entity(*sprite).despawn();
+            // Ants killed or despawned below ground are gone already
+            if let Some(mut ant) = commands.get_entity(*ant) {
+                ant.remove::<RenderLayers>();
+            }
+        }
+        keep
+    });
+
+    for (entity, ant, underground) in ants.iter() {
+        let Ok(nest_transform) = nests.get(underground.nest) else {
+            continue;
+        };
+        let translation =
+            (nest_transform.translation.truncate() + underground.position).extend(0.5);
+        let color = ant_color(ant, gui_settings.fade_by_state_time, &theme);
+        match mirrored.get(&entity) {
+            Some(&sprite_entity) => {
+                if let Ok((mut transform, mut sprite)) = sprites.get_mut(sprite_entity) {
+                    transform.translation = translation;
+                    sprite.color = color;
+                }
+            }
+            None => {
+                let sprite = commands
+                    .spawn(nest_sprite(color, ant_size(ant.caste), translation))
+                    .id();
+                mirrored.insert(entity, sprite);
+                // No camera draws the ant itself while it is below ground
+                commands.entity(entity).try_insert(RenderLayers::none());
+            }
+        }
+    }
+}
+
+// Underground layer showing each base's nest: its chambers, the food they store, the queen
+// and her brood, and the ants resting inside
+pub struct NestViewPlugin;
+
+impl Plugin for NestViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ViewLayer>()
+            .add_systems(Startup, setup_soil)
+            .add_systems(
+                Update,
+                (
+                    switch_view_layer,
+                    draw_nests,
+                    update_chambers.after(draw_nests),
+                    update_brood_labels.after(draw_nests),
+                    draw_underground_ants,
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_sim_core::config::ThemeName;
+    use ant_sim_core::rng::SimRng;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn despawning_an_underground_ant_drops_its_sprite() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(GuiSettings::default())
+            .insert_resource(Theme::named(ThemeName::Light))
+            .add_systems(Update, draw_underground_ants);
+        let config = Config::default();
+        let nest = app
+            .world
+            .spawn((
+                Nest::new(&config.nest),
+                TransformBundle::from_transform(Transform::from_xyz(100.0, 100.0, 0.0)),
+            ))
+            .id();
+        let ant = app
+            .world
+            .spawn((
+                Ant::new(&config, &mut SimRng::new(Some(1))),
+                Underground {
+                    nest,
+                    position: Vec2::ZERO,
+                    path: VecDeque::new(),
+                },
+                TransformBundle::from_transform(Transform::from_xyz(100.0, 100.0, 0.0)),
+            ))
+            .id();
+        app.update();
+        let mut sprites = app.world.query_filtered::<(), With<Sprite>>();
+        assert_eq!(sprites.iter(&app.world).count(), 1);
+
+        app.world.despawn(ant);
+        app.update();
+        assert_eq!(sprites.iter(&app.world).count(), 0);
+    }
+}
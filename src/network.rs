@@ -0,0 +1,623 @@
+use ant_sim_core::ant::{Ant, AntState, AntTraits, Caste};
+use ant_sim_core::base::Base;
+use ant_sim_core::colony::Colony;
+use ant_sim_core::config::Config;
+use ant_sim_core::corpse::Corpse;
+use ant_sim_core::food::{FoodQuantity, FoodSource};
+use ant_sim_core::marker::{Marker, MarkerType};
+use ant_sim_core::predator::Predator;
+use ant_sim_core::task::Task;
+use ant_sim_core::transport::HeavyFood;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+// Snapshots queued per observer; when an observer falls behind further, snapshots are dropped for it
+const CLIENT_QUEUE_LENGTH: usize = 2;
+
+// Messages sent by the server, one JSON object per line. Observers first get the
+// config (so they can lay out the map), then a snapshot at every broadcast.
+#[derive(Serialize, Deserialize)]
+pub enum ServerMessage {
+    Config(Box<Config>),
+    Snapshot(WorldSnapshot),
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct WorldSnapshot {
+    pub entities: Vec<EntityState>,
+}
+
+// State of one simulation entity, identified by its entity on the server
+#[derive(Serialize, Deserialize)]
+pub struct EntityState {
+    pub id: u64,
+    pub kind: EntityKind,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum EntityKind {
+    Ant {
+        position: [f32; 2],
+        velocity: [f32; 2],
+        state: AntState,
+        has_food: bool,
+        caste: Caste,
+        // Older snapshots don't record tasks
+        #[serde(default)]
+        task: Task,
+        colony: u32,
+        state_timer: f32,
+        traits: AntTraits,
+    },
+    Food {
+        position: [f32; 2],
+        radius: f32,
+        initial_quantity: u32,
+        quantity: u32,
+    },
+    HeavyFood {
+        position: [f32; 2],
+        carriers_needed: u32,
+        value: u32,
+        radius: f32,
+    },
+    Base {
+        position: [f32; 2],
+        colony: u32,
+    },
+    Corpse {
+        position: [f32; 2],
+        disposed: bool,
+    },
+    Predator {
+        position: [f32; 2],
+        velocity: [f32; 2],
+        digesting: f32,
+    },
+    Marker {
+        grid_cell: (i32, i32),
+        marker_type: MarkerType,
+        intensity: f32,
+    },
+}
+
+// Everything an observer needs to draw the simulation
+#[derive(SystemParam)]
+pub struct SnapshotQueries<'w, 's> {
+    ants: Query<'w, 's, (Entity, &'static Transform, &'static Ant, &'static Colony)>,
+    food: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Transform,
+            &'static FoodSource,
+            &'static FoodQuantity,
+        ),
+    >,
+    heavy_food: Query<'w, 's, (Entity, &'static Transform, &'static HeavyFood)>,
+    bases: Query<'w, 's, (Entity, &'static Transform, &'static Colony), With<Base>>,
+    corpses: Query<'w, 's, (Entity, &'static Transform, &'static Corpse)>,
+    predators: Query<'w, 's, (Entity, &'static Transform, &'static Predator)>,
+    markers: Query<'w, 's, (Entity, &'static Marker)>,
+}
+
+impl SnapshotQueries<'_, '_> {
+    pub fn capture(&self) -> WorldSnapshot {
+        let position = |transform: &Transform| transform.translation.truncate().to_array();
+        let mut entities = Vec::new();
+
+        for (entity, transform, ant, colony) in self.ants.iter() {
+            entities.push(EntityState {
+                id: entity.to_bits(),
+                kind: EntityKind::Ant {
+                    position: position(transform),
+                    velocity: ant.velocity.to_array(),
+                    state: ant.state,
+                    has_food: ant.has_food,
+                    caste: ant.caste,
+                    task: ant.task,
+                    colony: colony.0,
+                    state_timer: ant.state_timer,
+                    traits: ant.traits,
+                },
+            });
+        }
+        for (entity, transform, source, quantity) in self.food.iter() {
+            entities.push(EntityState {
+                id: entity.to_bits(),
+                kind: EntityKind::Food {
+                    position: position(transform),
+                    radius: source.radius,
+                    initial_quantity: source.initial_quantity,
+                    quantity: quantity.quantity,
+                },
+            });
+        }
+        for (entity, transform, item) in self.heavy_food.iter() {
+            entities.push(EntityState {
+                id: entity.to_bits(),
+                kind: EntityKind::HeavyFood {
+                    position: position(transform),
+                    carriers_needed: item.carriers_needed,
+                    value: item.value,
+                    radius: item.radius,
+                },
+            });
+        }
+        for (entity, transform, colony) in self.bases.iter() {
+            entities.push(EntityState {
+                id: entity.to_bits(),
+                kind: EntityKind::Base {
+                    position: position(transform),
+                    colony: colony.0,
+                },
+            });
+        }
+        for (entity, transform, corpse) in self.corpses.iter() {
+            entities.push(EntityState {
+                id: entity.to_bits(),
+                kind: EntityKind::Corpse {
+                    position: position(transform),
+                    disposed: corpse.disposed,
+                },
+            });
+        }
+        for (entity, transform, predator) in self.predators.iter() {
+            entities.push(EntityState {
+                id: entity.to_bits(),
+                kind: EntityKind::Predator {
+                    position: position(transform),
+                    velocity: predator.velocity.to_array(),
+                    digesting: predator.digesting,
+                },
+            });
+        }
+        for (entity, marker) in self.markers.iter() {
+            entities.push(EntityState {
+                id: entity.to_bits(),
+                kind: EntityKind::Marker {
+                    grid_cell: marker.grid_cell,
+                    marker_type: marker.marker_type,
+                    intensity: marker.intensity,
+                },
+            });
+        }
+
+        WorldSnapshot { entities }
+    }
+}
+
+fn encode(message: &ServerMessage) -> Option<Arc<str>> {
+    match serde_json::to_string(message) {
+        Ok(mut line) => {
+            line.push('\n');
+            Some(line.into())
+        }
+        Err(e) => {
+            eprintln!("Error serializing message: {}", e);
+            None
+        }
+    }
+}
+
+// Accepts observers and broadcasts the simulation to them
+#[derive(Resource)]
+pub struct SnapshotServer {
+    listener: TcpListener,
+    config: Arc<str>,
+    // Each observer is written to from its own thread, so a slow one can't stall the simulation
+    clients: Vec<SyncSender<Arc<str>>>,
+    timer: Timer,
+}
+
+impl SnapshotServer {
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        config: &Config,
+        snapshots_per_sec: f32,
+    ) -> std::io::Result<Self> {
+        if !(snapshots_per_sec.is_finite() && snapshots_per_sec > 0.0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "snapshot rate must be above 0",
+            ));
+        }
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let config = encode(&ServerMessage::Config(Box::new(config.clone())))
+            .ok_or_else(|| std::io::Error::other("config can't be serialized"))?;
+        Ok(Self {
+            listener,
+            config,
+            clients: Vec::new(),
+            timer: Timer::from_seconds(1.0 / snapshots_per_sec, TimerMode::Repeating),
+        })
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn add_client(&mut self, stream: TcpStream) {
+        let peer = stream
+            .peer_addr()
+            .map_or_else(|_| "unknown".to_string(), |addr| addr.to_string());
+        // Accepted streams inherit the listener's non-blocking mode
+        if let Err(e) = stream.set_nonblocking(false) {
+            eprintln!("Error accepting observer {}: {}", peer, e);
+            return;
+        }
+        let _ = stream.set_nodelay(true);
+
+        println!("Observer {} connected", peer);
+        let (sender, receiver) = mpsc::sync_channel::<Arc<str>>(CLIENT_QUEUE_LENGTH);
+        let config = self.config.clone();
+        std::thread::spawn(move || {
+            let mut stream = stream;
+            if stream.write_all(config.as_bytes()).is_err() {
+                return;
+            }
+            for line in receiver {
+                if stream.write_all(line.as_bytes()).is_err() {
+                    break;
+                }
+            }
+            println!("Observer {} disconnected", peer);
+        });
+        self.clients.push(sender);
+    }
+}
+
+pub fn accept_observers(mut server: ResMut<SnapshotServer>) {
+    loop {
+        match server.listener.accept() {
+            Ok((stream, _)) => server.add_client(stream),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(e) => {
+                eprintln!("Error accepting observer: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+// Broadcast rate follows wall clock time so paused simulations stay visible
+pub fn broadcast_snapshots(
+    mut server: ResMut<SnapshotServer>,
+    time: Res<Time<Real>>,
+    snapshot: SnapshotQueries,
+) {
+    server.timer.tick(time.delta());
+    if !server.timer.just_finished() || server.clients.is_empty() {
+        return;
+    }
+    let Some(line) = encode(&ServerMessage::Snapshot(snapshot.capture())) else {
+        return;
+    };
+    server
+        .clients
+        .retain(|client| match client.try_send(line.clone()) {
+            Ok(()) | Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+}
+
+// Runs the simulation headless and serves it to observers
+pub struct NetworkServerPlugin;
+
+impl Plugin for NetworkServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, (accept_observers, broadcast_snapshots));
+    }
+}
+
+// Receives snapshots from a server and mirrors them as simulation entities
+#[derive(Resource)]
+pub struct SnapshotClient {
+    receiver: Mutex<Receiver<WorldSnapshot>>,
+    // Local entity standing in for each server entity
+    entities: HashMap<u64, Entity>,
+}
+
+impl SnapshotClient {
+    // Connect to a server, returning the config of the simulation it runs
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<(Config, Self), Box<dyn std::error::Error>> {
+        let stream = TcpStream::connect(addr)?;
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let ServerMessage::Config(config) = serde_json::from_str(&line)? else {
+            return Err("server didn't send its config".into());
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match serde_json::from_str(&line) {
+                    Ok(ServerMessage::Snapshot(snapshot)) => {
+                        if sender.send(snapshot).is_err() {
+                            return;
+                        }
+                    }
+                    Ok(ServerMessage::Config(_)) => {}
+                    Err(e) => eprintln!("Error reading snapshot: {}", e),
+                }
+            }
+            println!("Disconnected from server");
+        });
+
+        Ok((
+            *config,
+            Self {
+                receiver: Mutex::new(receiver),
+                entities: HashMap::new(),
+            },
+        ))
+    }
+
+    // Most recent snapshot received since the last call, older ones are skipped
+    fn latest(&self) -> Option<WorldSnapshot> {
+        self.receiver.lock().unwrap().try_iter().last()
+    }
+}
+
+fn translation(position: [f32; 2], z: f32) -> Vec3 {
+    Vec2::from(position).extend(z)
+}
+
+fn spawn_mirror(world: &mut World, kind: &EntityKind) -> Entity {
+    match *kind {
+        EntityKind::Ant {
+            position,
+            velocity,
+            state,
+            has_food,
+            caste,
+            task,
+            colony,
+            state_timer,
+            traits,
+        } => world
+            .spawn((
+                Ant {
+                    state,
+                    has_food,
+                    food_richness: 1.0,
+                    velocity: Vec2::from(velocity),
+                    direction_change_timer: 0.0,
+                    marker_timer: 0.0,
+                    state_timer,
+                    trip_timer: 0.0,
+                    pheromone: 0.0,
+                    exploring: false,
+                    caste,
+                    task,
+                    traits,
+                },
+                Colony(colony),
+                TransformBundle::from_transform(Transform::from_translation(translation(
+                    position, 0.0,
+                ))),
+            ))
+            .id(),
+        EntityKind::Food {
+            position,
+            radius,
+            initial_quantity,
+            quantity,
+        } => world
+            .spawn((
+                FoodSource {
+                    radius,
+                    initial_quantity,
+                    respawn: None,
+                    quality: 1.0,
+                },
+                FoodQuantity { quantity },
+                TransformBundle::from_transform(Transform::from_translation(translation(
+                    position, 0.0,
+                ))),
+            ))
+            .id(),
+        EntityKind::HeavyFood {
+            position,
+            carriers_needed,
+            value,
+            radius,
+        } => world
+            .spawn((
+                HeavyFood {
+                    carriers_needed,
+                    value,
+                    radius,
+                },
+                TransformBundle::from_transform(Transform::from_translation(translation(
+                    position, 0.0,
+                ))),
+            ))
+            .id(),
+        EntityKind::Base { position, colony } => world
+            .spawn((
+                Base,
+                Colony(colony),
+                TransformBundle::from_transform(Transform::from_translation(translation(
+                    position, 0.0,
+                ))),
+            ))
+            .id(),
+        EntityKind::Corpse { position, disposed } => world
+            .spawn((
+                Corpse {
+                    disposed,
+                    lifetime: Timer::default(),
+                    marker_timer: 0.0,
+                },
+                TransformBundle::from_transform(Transform::from_translation(translation(
+                    position, 0.0,
+                ))),
+            ))
+            .id(),
+        EntityKind::Predator {
+            position,
+            velocity,
+            digesting,
+        } => world
+            .spawn((
+                Predator {
+                    velocity: Vec2::from(velocity),
+                    digesting,
+                },
+                TransformBundle::from_transform(Transform::from_translation(translation(
+                    position, 0.0,
+                ))),
+            ))
+            .id(),
+        EntityKind::Marker {
+            grid_cell,
+            marker_type,
+            intensity,
+        } => {
+            let position = world
+                .resource::<Config>()
+                .grid_type
+                .grid_to_world(grid_cell);
+            world
+                .spawn((
+                    Marker {
+                        intensity,
+                        marker_type,
+                        grid_cell,
+                        owner: None,
+                    },
+                    Transform::from_translation(position.extend(-0.1)),
+                ))
+                .id()
+        }
+    }
+}
+
+fn update_mirror(entity: &mut EntityWorldMut, kind: &EntityKind) {
+    match *kind {
+        EntityKind::Ant {
+            position,
+            velocity,
+            state,
+            has_food,
+            task,
+            state_timer,
+            ..
+        } => {
+            let velocity = Vec2::from(velocity);
+            if let Some(mut transform) = entity.get_mut::<Transform>() {
+                transform.translation = translation(position, transform.translation.z);
+                if velocity.length_squared() > 0.0 {
+                    transform.rotation = Quat::from_rotation_z(velocity.y.atan2(velocity.x));
+                }
+            }
+            if let Some(mut ant) = entity.get_mut::<Ant>() {
+                ant.velocity = velocity;
+                ant.state_timer = state_timer;
+                ant.task = task;
+                // Only touched on change, ants are recolored when they change
+                if ant.state != state || ant.has_food != has_food {
+                    ant.state = state;
+                    ant.has_food = has_food;
+                }
+            }
+        }
+        EntityKind::Food { quantity, .. } => {
+            if let Some(mut food_quantity) = entity.get_mut::<FoodQuantity>() {
+                if food_quantity.quantity != quantity {
+                    food_quantity.quantity = quantity;
+                }
+            }
+        }
+        EntityKind::HeavyFood { position, .. } => {
+            if let Some(mut transform) = entity.get_mut::<Transform>() {
+                transform.translation = translation(position, transform.translation.z);
+            }
+        }
+        EntityKind::Corpse { position, disposed } => {
+            if let Some(mut transform) = entity.get_mut::<Transform>() {
+                transform.translation = translation(position, transform.translation.z);
+            }
+            if let Some(mut corpse) = entity.get_mut::<Corpse>() {
+                corpse.disposed = disposed;
+            }
+        }
+        EntityKind::Predator {
+            position,
+            velocity,
+            digesting,
+        } => {
+            let velocity = Vec2::from(velocity);
+            if let Some(mut transform) = entity.get_mut::<Transform>() {
+                transform.translation = translation(position, transform.translation.z);
+                if velocity.length_squared() > 0.0 {
+                    transform.rotation = Quat::from_rotation_z(velocity.y.atan2(velocity.x));
+                }
+            }
+            if let Some(mut predator) = entity.get_mut::<Predator>() {
+                predator.velocity = velocity;
+                predator.digesting = digesting;
+            }
+        }
+        EntityKind::Marker { intensity, .. } => {
+            if let Some(mut marker) = entity.get_mut::<Marker>() {
+                marker.intensity = intensity;
+            }
+        }
+        EntityKind::Base { .. } => {}
+    }
+}
+
+// Bring the local mirror in line with the latest snapshot from the server
+pub fn apply_snapshots(world: &mut World) {
+    world.resource_scope(|world, mut client: Mut<SnapshotClient>| {
+        let Some(snapshot) = client.latest() else {
+            return;
+        };
+
+        let received: HashSet<u64> = snapshot.entities.iter().map(|state| state.id).collect();
+        client.entities.retain(|id, entity| {
+            let keep = received.contains(id);
+            if !keep {
+                world.despawn(*entity);
+            }
+            keep
+        });
+
+        for state in &snapshot.entities {
+            match client.entities.get(&state.id) {
+                Some(&entity) => {
+                    if let Some(mut entity) = world.get_entity_mut(entity) {
+                        update_mirror(&mut entity, &state.kind);
+                    }
+                }
+                None => {
+                    let entity = spawn_mirror(world, &state.kind);
+                    client.entities.insert(state.id, entity);
+                }
+            }
+        }
+    });
+}
+
+// Draws a simulation running on a server instead of simulating locally
+pub struct NetworkClientPlugin;
+
+impl Plugin for NetworkClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, apply_snapshots);
+    }
+}
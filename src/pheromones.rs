@@ -0,0 +1,132 @@
+use ant_sim_core::marker::{Marker, MarkerType, INITIAL_INTENSITY};
+use image::{GrayImage, Luma};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const MARKER_TYPES: [MarkerType; 3] = [MarkerType::Base, MarkerType::Food, MarkerType::Danger];
+
+// Marker intensity of every grid cell for one marker type, 0 where there is no marker.
+// Hex grids keep their offset layout, with odd rows half a cell further right on the map.
+pub struct PheromoneField {
+    pub marker_type: MarkerType,
+    pub width: u32,
+    pub height: u32,
+    // Row by row from the top of the map, as images are stored
+    pub intensities: Vec<f32>,
+}
+
+impl PheromoneField {
+    fn index(&self, cell: (i32, i32)) -> Option<usize> {
+        let (x, y) = cell;
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let row = self.height as usize - 1 - y as usize;
+        Some(row * self.width as usize + x as usize)
+    }
+
+    // Full intensity is white, so images from different moments share a scale
+    pub fn to_image(&self) -> GrayImage {
+        GrayImage::from_fn(self.width, self.height, |x, y| {
+            let intensity = self.intensities[(y * self.width + x) as usize];
+            Luma([((intensity / INITIAL_INTENSITY).clamp(0.0, 1.0) * 255.0).round() as u8])
+        })
+    }
+
+    pub fn write_csv(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        for row in self.intensities.chunks(self.width as usize) {
+            let values: Vec<String> = row.iter().map(|v| format!("{:.2}", v)).collect();
+            writeln!(file, "{}", values.join(","))?;
+        }
+        file.flush()
+    }
+
+    // NumPy .npy array of float32 with shape (height, width), laid out like the CSV matrix
+    pub fn write_npy(&self, path: &Path) -> std::io::Result<()> {
+        let dict = format!(
+            "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+            self.height, self.width
+        );
+        // Magic string, version and header length come first, and the header is padded with
+        // spaces so the data starts on a multiple of 64 bytes
+        let unpadded = 10 + dict.len() + 1;
+        let header = format!("{}{}\n", dict, " ".repeat((64 - unpadded % 64) % 64));
+
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(b"\x93NUMPY\x01\x00")?;
+        file.write_all(&(header.len() as u16).to_le_bytes())?;
+        file.write_all(header.as_bytes())?;
+        for intensity in &self.intensities {
+            file.write_all(&intensity.to_le_bytes())?;
+        }
+        file.flush()
+    }
+}
+
+// One field per marker type, from the markers currently on the map
+pub fn capture_fields<'a>(
+    map_size: (u32, u32),
+    markers: impl Iterator<Item = &'a Marker>,
+) -> Vec<PheromoneField> {
+    let mut fields: Vec<PheromoneField> = MARKER_TYPES
+        .iter()
+        .map(|&marker_type| PheromoneField {
+            marker_type,
+            width: map_size.0,
+            height: map_size.1,
+            intensities: vec![0.0; (map_size.0 * map_size.1) as usize],
+        })
+        .collect();
+    for marker in markers {
+        let Some(field) = fields
+            .iter_mut()
+            .find(|field| field.marker_type == marker.marker_type)
+        else {
+            continue;
+        };
+        if let Some(index) = field.index(marker.grid_cell) {
+            field.intensities[index] = marker.intensity;
+        }
+    }
+    fields
+}
+
+fn channel_name(marker_type: MarkerType) -> &'static str {
+    match marker_type {
+        MarkerType::Base => "base",
+        MarkerType::Food => "food",
+        MarkerType::Danger => "danger",
+    }
+}
+
+// Write <prefix>_<channel>.png for every field, and a .csv matrix or .npy array next to each
+// when asked
+pub fn write_fields(
+    dir: &Path,
+    prefix: &str,
+    fields: &[PheromoneField],
+    csv: bool,
+    npy: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    for field in fields {
+        let name = format!("{}_{}", prefix, channel_name(field.marker_type));
+        let png_path = dir.join(format!("{}.png", name));
+        field.to_image().save(&png_path)?;
+        written.push(png_path);
+        if csv {
+            let csv_path = dir.join(format!("{}.csv", name));
+            field.write_csv(&csv_path)?;
+            written.push(csv_path);
+        }
+        if npy {
+            let npy_path = dir.join(format!("{}.npy", name));
+            field.write_npy(&npy_path)?;
+            written.push(npy_path);
+        }
+    }
+    Ok(written)
+}
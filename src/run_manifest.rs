@@ -0,0 +1,77 @@
+use crate::chart_data::RunMetadata;
+use ant_sim_core::config::Config;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const MANIFEST_FILE: &str = "run.json";
+
+// run.json in each run's folder: the build, config, seed and tick the run started from and
+// every file it wrote, enough for --replay-run to start the same run again
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    #[serde(flatten)]
+    pub metadata: RunMetadata,
+    // Seconds the simulation advanced each frame, unset when it followed the wall clock. Only
+    // runs with a fixed tick replay exactly.
+    #[serde(default)]
+    pub tick_secs: Option<f64>,
+    // Local times in RFC 3339
+    pub started_at: String,
+    // Unset while the run is going or when it didn't exit cleanly
+    pub ended_at: Option<String>,
+    // Logs, summary, exports, screenshots and recordings, as paths from the working directory
+    pub artifacts: Vec<PathBuf>,
+}
+
+impl RunManifest {
+    pub fn start(
+        config: &Config,
+        seed: u64,
+        tick: Option<Duration>,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            metadata: RunMetadata::for_current_build(serde_json::to_value(config)?, seed),
+            tick_secs: tick.map(|tick| tick.as_secs_f64()),
+            started_at: chrono::Local::now().to_rfc3339(),
+            ended_at: None,
+            artifacts: Vec::new(),
+        })
+    }
+
+    pub fn load(run_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(run_dir.join(MANIFEST_FILE))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn write(&self, run_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(run_dir)?;
+        std::fs::write(
+            run_dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    // The run's config with its seed pinned, so a random seed is picked the same way again
+    pub fn replay_config(&self) -> Result<Config, serde_json::Error> {
+        let mut config: Config = serde_json::from_value(self.metadata.config.clone())?;
+        if let Some(seed) = self.metadata.seed {
+            config.seed = Some(seed);
+        }
+        Ok(config)
+    }
+
+    pub fn tick(&self) -> Option<Duration> {
+        self.tick_secs.map(Duration::from_secs_f64)
+    }
+
+    // Whether the run was made by another version or commit than the one running now, which
+    // may make it play out differently
+    pub fn other_build(&self) -> bool {
+        let current = RunMetadata::for_current_build(serde_json::Value::Null, 0);
+        current.app_version != self.metadata.app_version
+            || current.git_hash != self.metadata.git_hash
+    }
+}
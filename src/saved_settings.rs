@@ -0,0 +1,114 @@
+use crate::gui::GuiSettings;
+use crate::theme::Theme;
+use crate::visuals::{CameraBookmarks, MainCamera};
+use ant_sim_core::config::{Config, ThemeName};
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_PATH: &str = "gui_settings.json";
+
+// Where the main camera looked and how far it was zoomed out
+#[derive(Resource, Clone, Copy, Serialize, Deserialize)]
+pub struct SavedCamera {
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+impl SavedCamera {
+    pub fn of(transform: &Transform, projection: &OrthographicProjection) -> Self {
+        Self {
+            x: transform.translation.x,
+            y: transform.translation.y,
+            scale: projection.scale,
+        }
+    }
+
+    pub fn apply(&self, transform: &mut Transform, projection: &mut OrthographicProjection) {
+        transform.translation.x = self.x;
+        transform.translation.y = self.y;
+        projection.scale = self.scale;
+    }
+}
+
+// View preferences kept between runs; the simulation itself is set up by config.json
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedSettings {
+    gui: GuiSettings,
+    // Theme picked in the GUI when it differs from the one in the config
+    theme: Option<ThemeName>,
+    camera: Option<SavedCamera>,
+    bookmarks: CameraBookmarks,
+}
+
+fn load_settings() -> SavedSettings {
+    let Ok(contents) = std::fs::read_to_string(SETTINGS_PATH) else {
+        return SavedSettings::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        eprintln!("Ignoring unreadable {}: {}", SETTINGS_PATH, e);
+        SavedSettings::default()
+    })
+}
+
+// The camera is spawned at startup, so it's moved back afterwards
+pub fn restore_camera(
+    saved: Res<SavedCamera>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        saved.apply(&mut transform, &mut projection);
+    }
+}
+
+pub fn save_settings(
+    mut exit_events: EventReader<AppExit>,
+    settings: Res<GuiSettings>,
+    theme: Res<Theme>,
+    config: Res<Config>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    bookmarks: Res<CameraBookmarks>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+    let saved = SavedSettings {
+        gui: settings.clone(),
+        theme: (theme.name != config.theme).then_some(theme.name),
+        camera: cameras
+            .get_single()
+            .ok()
+            .map(|(transform, projection)| SavedCamera::of(transform, projection)),
+        bookmarks: bookmarks.clone(),
+    };
+    let result = serde_json::to_string_pretty(&saved)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(SETTINGS_PATH, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("Error saving {}: {}", SETTINGS_PATH, e);
+    }
+}
+
+// Must be added before the plugins initializing GuiSettings, Theme and CameraBookmarks
+pub struct SavedSettingsPlugin;
+
+impl Plugin for SavedSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let saved = load_settings();
+        app.insert_resource(saved.gui)
+            .insert_resource(saved.bookmarks);
+        if let Some(name) = saved.theme {
+            app.insert_resource(Theme::named(name));
+        }
+        if let Some(camera) = saved.camera {
+            app.insert_resource(camera);
+        }
+        app.add_systems(
+            PostStartup,
+            restore_camera.run_if(resource_exists::<SavedCamera>()),
+        )
+        .add_systems(Last, save_settings);
+    }
+}
@@ -0,0 +1,306 @@
+use crate::gui::GuiSettings;
+use crate::interaction::Cursor;
+use crate::localization::Locale;
+use crate::theme::{Theme, ThemedNode};
+use crate::visuals::Tint;
+use ant_sim_core::ant::{Ant, AntState, ANT_SPEED};
+use ant_sim_core::spatial::{SpatialIndex, SpatialIndexPlugin};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+// Drags shorter than this are plain clicks, which clear the selection
+const MIN_DRAG_DISTANCE: f32 = 4.0;
+const HIGHLIGHT_RADIUS: f32 = 6.0;
+// Colors the recolor button cycles through
+const TINTS: [Color; 4] = [Color::FUCHSIA, Color::CYAN, Color::ORANGE, Color::WHITE];
+
+#[derive(Component)]
+pub struct Selected;
+
+// Selection state: where the current drag started (world position) and the next recolor tint
+#[derive(Resource, Default)]
+pub struct DragSelection {
+    start: Option<Vec2>,
+    next_tint: usize,
+}
+
+#[derive(Component)]
+pub struct InspectorPanel;
+
+#[derive(Component)]
+pub struct InspectorText;
+
+#[derive(Component)]
+pub enum InspectorButton {
+    Despawn,
+    Recolor,
+}
+
+// Ants found through the spatial index, and the ones already selected
+#[derive(SystemParam)]
+pub struct SelectableAnts<'w, 's> {
+    index: Res<'w, SpatialIndex<Ant>>,
+    selected: Query<'w, 's, Entity, (With<Ant>, With<Selected>)>,
+}
+
+// Left-drag a rectangle on the map to select the ants inside it
+pub fn drag_select(
+    mut commands: Commands,
+    mut drag: ResMut<DragSelection>,
+    mouse_input: Res<Input<MouseButton>>,
+    settings: Res<GuiSettings>,
+    cursor: Cursor,
+    buttons: Query<&Interaction, With<Button>>,
+    ants: SelectableAnts,
+) {
+    // Clicks belong to the food tool while it is active
+    if settings.food_tool {
+        drag.start = None;
+        return;
+    }
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        // Clicks on GUI buttons are not meant for the map
+        let over_gui = buttons
+            .iter()
+            .any(|interaction| *interaction != Interaction::None);
+        drag.start = if over_gui {
+            None
+        } else {
+            cursor.world_position()
+        };
+    }
+
+    if !mouse_input.just_released(MouseButton::Left) {
+        return;
+    }
+    let (Some(start), Some(end)) = (drag.start.take(), cursor.world_position()) else {
+        return;
+    };
+
+    // A click without dragging selects nothing
+    let inside: HashSet<Entity> = if start.distance(end) >= MIN_DRAG_DISTANCE {
+        ants.index
+            .within_rect(Rect::from_corners(start, end))
+            .map(|(entity, _)| entity)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    for entity in ants.selected.iter() {
+        if !inside.contains(&entity) {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    for &entity in &inside {
+        commands.entity(entity).insert(Selected);
+    }
+}
+
+// Outline the rectangle being dragged and circle the selected ants
+pub fn draw_selection(
+    mut gizmos: Gizmos,
+    drag: Res<DragSelection>,
+    cursor: Cursor,
+    selected: Query<&Transform, (With<Ant>, With<Selected>)>,
+) {
+    if let (Some(start), Some(end)) = (drag.start, cursor.world_position()) {
+        let area = Rect::from_corners(start, end);
+        gizmos.rect_2d(area.center(), 0.0, area.size(), Color::YELLOW);
+    }
+
+    for transform in selected.iter() {
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            HIGHLIGHT_RADIUS,
+            Color::YELLOW,
+        );
+    }
+}
+
+pub fn setup_inspector(mut commands: Commands, locale: Res<Locale>, theme: Res<Theme>) {
+    // Inspector panel in top-right, only shown while ants are selected
+    let panel = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: theme.panel.into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            InspectorPanel,
+            ThemedNode::Panel,
+        ))
+        .id();
+
+    commands.entity(panel).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 16.0,
+                    color: theme.text,
+                    ..default()
+                },
+            ),
+            InspectorText,
+        ));
+
+        for (button, label) in [
+            (InspectorButton::Despawn, locale.despawn_selection),
+            (InspectorButton::Recolor, locale.recolor_selection),
+        ] {
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(4.0)),
+                            margin: UiRect::top(Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: theme.button.into(),
+                        ..default()
+                    },
+                    button,
+                    ThemedNode::Button,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font_size: 14.0,
+                            color: theme.text,
+                            ..default()
+                        },
+                    ));
+                });
+        }
+    });
+}
+
+// Aggregate stats of the selected ants
+pub fn update_inspector(
+    mut panel: Query<&mut Visibility, With<InspectorPanel>>,
+    mut text: Query<&mut Text, With<InspectorText>>,
+    selected: Query<&Ant, With<Selected>>,
+    locale: Res<Locale>,
+) {
+    let count = selected.iter().count();
+    if let Ok(mut visibility) = panel.get_single_mut() {
+        let target_visibility = if count > 0 {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != target_visibility {
+            *visibility = target_visibility;
+        }
+    }
+    if count == 0 {
+        return;
+    }
+
+    let mut searching_count = 0;
+    let mut returning_count = 0;
+    let mut resting_count = 0;
+    let mut corpse_carrier_count = 0;
+    let mut total_speed = 0.0;
+    let mut total_time_in_state = 0.0;
+    for ant in selected.iter() {
+        match ant.state {
+            AntState::Searching => searching_count += 1,
+            AntState::Returning => returning_count += 1,
+            AntState::Resting => resting_count += 1,
+            AntState::CarryingCorpse => corpse_carrier_count += 1,
+        }
+        // Resting ants stand still at the base
+        if ant.state != AntState::Resting {
+            total_speed += ant.velocity.length() * ANT_SPEED * ant.traits.speed;
+        }
+        total_time_in_state += ant.state_timer;
+    }
+
+    if let Ok(mut text) = text.get_single_mut() {
+        text.sections[0].value = format!(
+            "{}: {}\n\
+             - {}: {}\n\
+             - {}: {}\n\
+             - {}: {}\n\
+             - {}: {}\n\
+             {}: {:.1} px/s\n\
+             {}: {:.1} s",
+            locale.selected_ants,
+            count,
+            locale.searching,
+            searching_count,
+            locale.returning,
+            returning_count,
+            locale.resting,
+            resting_count,
+            locale.carrying_corpses,
+            corpse_carrier_count,
+            locale.avg_speed,
+            total_speed / count as f32,
+            locale.avg_time_in_state,
+            total_time_in_state / count as f32
+        );
+    }
+}
+
+pub fn handle_inspector_buttons(
+    mut commands: Commands,
+    mut drag: ResMut<DragSelection>,
+    interaction_query: Query<(&Interaction, &InspectorButton), Changed<Interaction>>,
+    selected: Query<Entity, (With<Ant>, With<Selected>)>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            InspectorButton::Despawn => {
+                for entity in selected.iter() {
+                    commands.entity(entity).despawn();
+                }
+            }
+            InspectorButton::Recolor => {
+                let tint = TINTS[drag.next_tint % TINTS.len()];
+                drag.next_tint += 1;
+                for entity in selected.iter() {
+                    commands.entity(entity).insert(Tint(tint));
+                }
+            }
+        }
+    }
+}
+
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        // Selections also work when watching a server, without the simulation's plugin
+        if !app.is_plugin_added::<SpatialIndexPlugin>() {
+            app.add_plugins(SpatialIndexPlugin);
+        }
+        app.init_resource::<DragSelection>()
+            .init_resource::<Locale>()
+            .add_systems(Startup, setup_inspector)
+            .add_systems(
+                Update,
+                (
+                    drag_select,
+                    draw_selection,
+                    update_inspector,
+                    handle_inspector_buttons,
+                ),
+            );
+    }
+}
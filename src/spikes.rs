@@ -0,0 +1,132 @@
+use crate::gui::FrameTiming;
+use crate::logging::resident_memory_mb;
+use ant_sim::commands::ExportDir;
+use ant_sim_core::ant::Ant;
+use ant_sim_core::clock::SimClock;
+use ant_sim_core::config::Config;
+use ant_sim_core::corpse::Corpse;
+use ant_sim_core::food::FoodSource;
+use ant_sim_core::grid::GridMap;
+use ant_sim_core::marker::Marker;
+use ant_sim_core::profiling::PROFILED_SYSTEMS;
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::ecs::entity::Entities;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Starting up always takes long, so the first frames are never reported
+const WARMUP_TICKS: u64 = 60;
+
+#[derive(Serialize)]
+struct EntityCounts {
+    total: u32,
+    ants: usize,
+    markers: usize,
+    food_sources: usize,
+    corpses: usize,
+}
+
+// What the simulation looked like around a frame that took too long
+#[derive(Serialize)]
+struct SpikeBundle {
+    sim_secs: f64,
+    tick: u64,
+    frame_ms: f32,
+    threshold_ms: f32,
+    // Oldest first, ending with the spike
+    recent_frame_ms: Vec<f32>,
+    entities: EntityCounts,
+    // Last frame's time in each profiled system
+    system_ms: BTreeMap<&'static str, f64>,
+    grid_cells: usize,
+    grid_memory_kb: f64,
+    resident_memory_mb: Option<f64>,
+}
+
+// Everything a bundle records besides the frame times
+#[derive(SystemParam)]
+pub struct SpikeContext<'w, 's> {
+    clock: Res<'w, SimClock>,
+    entities: &'w Entities,
+    ants: Query<'w, 's, (), With<Ant>>,
+    markers: Query<'w, 's, (), With<Marker>>,
+    food: Query<'w, 's, (), With<FoodSource>>,
+    corpses: Query<'w, 's, (), With<Corpse>>,
+    grid_map: Res<'w, GridMap>,
+    diagnostics: Res<'w, DiagnosticsStore>,
+}
+
+impl SpikeContext<'_, '_> {
+    fn bundle(&self, frame_timing: &FrameTiming, threshold_ms: f32) -> SpikeBundle {
+        let system_ms = PROFILED_SYSTEMS
+            .iter()
+            .map(|(id, name)| {
+                let ms = self
+                    .diagnostics
+                    .get(*id)
+                    .and_then(|diagnostic| diagnostic.value())
+                    .unwrap_or(0.0);
+                (*name, ms)
+            })
+            .collect();
+        SpikeBundle {
+            sim_secs: self.clock.elapsed_secs(),
+            tick: self.clock.tick(),
+            frame_ms: frame_timing.current_ms(),
+            threshold_ms,
+            recent_frame_ms: frame_timing.history_ms(),
+            entities: EntityCounts {
+                total: self.entities.len(),
+                ants: self.ants.iter().count(),
+                markers: self.markers.iter().count(),
+                food_sources: self.food.iter().count(),
+                corpses: self.corpses.iter().count(),
+            },
+            system_ms,
+            grid_cells: self.grid_map.allocated_cells(),
+            grid_memory_kb: self.grid_map.memory_bytes() as f64 / 1024.0,
+            resident_memory_mb: resident_memory_mb(),
+        }
+    }
+}
+
+// Write spike_tick<tick>.json to the run's log folder when a frame exceeds the threshold
+pub fn capture_frame_spikes(
+    frame_timing: Res<FrameTiming>,
+    context: SpikeContext,
+    export_dir: Res<ExportDir>,
+    config: Res<Config>,
+    time: Res<Time<Real>>,
+    mut last_capture: Local<Option<f32>>,
+) {
+    let Some(threshold_ms) = config.spike_capture.threshold_ms else {
+        return;
+    };
+    if frame_timing.current_ms() <= threshold_ms || context.clock.tick() < WARMUP_TICKS {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    if last_capture.is_some_and(|last| now - last < config.spike_capture.cooldown_secs) {
+        return;
+    }
+    *last_capture = Some(now);
+
+    let bundle = context.bundle(&frame_timing, threshold_ms);
+    let path = export_dir
+        .0
+        .join(format!("spike_tick{:07}.json", bundle.tick));
+    let written = std::fs::create_dir_all(&export_dir.0)
+        .and_then(|()| std::fs::File::create(&path))
+        .map_err(|e| e.to_string())
+        .and_then(|file| serde_json::to_writer_pretty(file, &bundle).map_err(|e| e.to_string()));
+    match written {
+        Ok(()) => println!(
+            "Frame took {:.0} ms, diagnostics written to {}",
+            bundle.frame_ms,
+            path.display()
+        ),
+        Err(e) => eprintln!("Error writing {}: {}", path.display(), e),
+    }
+}
@@ -0,0 +1,145 @@
+use crate::theme::ThemedSprite;
+use ant_sim_core::ant::{Ant, AntState};
+use ant_sim_core::base::Base;
+use ant_sim_core::config::Config;
+use ant_sim_core::food::FoodSource;
+use bevy::prelude::*;
+use std::path::Path;
+
+// Distance in pixels an ant travels per animation frame
+const STRIDE_LENGTH: f32 = 4.0;
+// Position jumps larger than this are map wrap-arounds, not walking
+const MAX_STEP: f32 = 100.0;
+
+// Loaded sprite sheet handles, only present when sprite sheets are enabled
+#[derive(Resource)]
+pub struct SpriteAssets {
+    pub ant_walk: Handle<Image>,
+    pub food: Handle<Image>,
+    pub base: Handle<Image>,
+    frame_size: Vec2,
+    frame_count: u32,
+}
+
+impl SpriteAssets {
+    // Region of the ant walk sheet holding the given frame
+    fn ant_frame_rect(&self, frame: u32) -> Rect {
+        let x = (frame % self.frame_count) as f32 * self.frame_size.x;
+        Rect::new(x, 0.0, x + self.frame_size.x, self.frame_size.y)
+    }
+}
+
+#[derive(Component)]
+pub struct WalkAnimation {
+    last_position: Vec2,
+    distance: f32,
+}
+
+pub fn load_sprite_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<Config>,
+) {
+    if !config.use_sprite_sheets {
+        return;
+    }
+
+    let sheets = &config.sprite_sheets;
+    let missing: Vec<&String> = [&sheets.ant_walk, &sheets.food, &sheets.base]
+        .into_iter()
+        .filter(|path| !Path::new("assets").join(path).exists())
+        .collect();
+    if !missing.is_empty() {
+        eprintln!(
+            "Sprite sheets not found ({:?}), falling back to colored rectangles",
+            missing
+        );
+        return;
+    }
+
+    commands.insert_resource(SpriteAssets {
+        ant_walk: asset_server.load(sheets.ant_walk.clone()),
+        food: asset_server.load(sheets.food.clone()),
+        base: asset_server.load(sheets.base.clone()),
+        frame_size: Vec2::new(
+            sheets.ant_frame_size.0 as f32,
+            sheets.ant_frame_size.1 as f32,
+        ),
+        frame_count: sheets.ant_frame_count.max(1),
+    });
+}
+
+// Entities that were just given a sprite, by kind
+type NewAntSprite = (Added<Sprite>, With<Ant>);
+type NewFoodSprite = (Added<Sprite>, With<FoodSource>, Without<Ant>);
+type NewBaseSprite = (Added<Sprite>, With<Base>, Without<Ant>, Without<FoodSource>);
+
+// Swap the plain colored rectangles of newly spawned entities for textures
+pub fn apply_sprite_textures(
+    mut commands: Commands,
+    sprite_assets: Res<SpriteAssets>,
+    mut ants: Query<(Entity, &Transform, &mut Sprite), NewAntSprite>,
+    mut food: Query<(Entity, &mut Sprite), NewFoodSprite>,
+    mut bases: Query<(Entity, &mut Sprite), NewBaseSprite>,
+) {
+    for (entity, transform, mut sprite) in ants.iter_mut() {
+        sprite.rect = Some(sprite_assets.ant_frame_rect(0));
+        commands.entity(entity).insert((
+            sprite_assets.ant_walk.clone(),
+            WalkAnimation {
+                last_position: transform.translation.truncate(),
+                distance: 0.0,
+            },
+        ));
+    }
+
+    // Textures are tinted by sprite color, so draw them untinted whatever the theme
+    for (entity, mut sprite) in food.iter_mut() {
+        sprite.color = Color::WHITE;
+        commands
+            .entity(entity)
+            .insert(sprite_assets.food.clone())
+            .remove::<ThemedSprite>();
+    }
+
+    for (entity, mut sprite) in bases.iter_mut() {
+        sprite.color = Color::WHITE;
+        commands
+            .entity(entity)
+            .insert(sprite_assets.base.clone())
+            .remove::<ThemedSprite>();
+    }
+}
+
+// Advance walk frames by distance travelled, so faster ants animate faster
+pub fn animate_ants(
+    sprite_assets: Res<SpriteAssets>,
+    mut ants: Query<(&Transform, &Ant, &mut Sprite, &mut WalkAnimation)>,
+) {
+    for (transform, ant, mut sprite, mut animation) in ants.iter_mut() {
+        let position = transform.translation.truncate();
+        let step = position.distance(animation.last_position);
+        animation.last_position = position;
+
+        if ant.state == AntState::Resting || step > MAX_STEP {
+            continue;
+        }
+
+        animation.distance += step;
+        let frame = (animation.distance / STRIDE_LENGTH) as u32;
+        sprite.rect = Some(sprite_assets.ant_frame_rect(frame));
+    }
+}
+
+pub struct SpriteAssetsPlugin;
+
+impl Plugin for SpriteAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_sprite_assets).add_systems(
+            Update,
+            (apply_sprite_textures, animate_ants)
+                .chain()
+                .run_if(resource_exists::<SpriteAssets>()),
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::visuals::{GridLine, MarkerLayer};
+use ant_sim::commands::{CommandRequest, SimCommand, StressMode};
+use ant_sim_core::ant::Ant;
+use ant_sim_core::clock::SimClock;
+use bevy::prelude::*;
+use std::time::Instant;
+
+// Wall clock seconds between throughput reports
+const REPORT_INTERVAL_SECS: f32 = 5.0;
+
+// Ticks and ant updates since the last report
+pub struct ThroughputWindow {
+    started: Instant,
+    start_tick: u64,
+    ant_updates: u64,
+}
+
+// Hide the grid lines and the marker layer; markers are still simulated, just not drawn
+pub fn enter_stress_mode(
+    mut grid_lines: Query<&mut Visibility, With<GridLine>>,
+    mut marker_layers: Query<&mut Visibility, (With<MarkerLayer>, Without<GridLine>)>,
+) {
+    for mut visibility in grid_lines.iter_mut().chain(marker_layers.iter_mut()) {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// Print ticks per second and ants per tick, measured over real time so rendering counts too
+pub fn report_throughput(
+    mut window: Local<Option<ThroughputWindow>>,
+    clock: Res<SimClock>,
+    ants: Query<(), With<Ant>>,
+) {
+    let window = window.get_or_insert_with(|| ThroughputWindow {
+        started: Instant::now(),
+        start_tick: clock.tick(),
+        ant_updates: 0,
+    });
+    window.ant_updates += ants.iter().count() as u64;
+
+    let elapsed = window.started.elapsed().as_secs_f32();
+    if elapsed < REPORT_INTERVAL_SECS {
+        return;
+    }
+    let ticks = clock.tick() - window.start_tick;
+    let ants_per_tick = window.ant_updates as f32 / ticks.max(1) as f32;
+    println!(
+        "stress: {:.1} ticks/s, {:.0} ants/tick, {:.0} ant updates/s",
+        ticks as f32 / elapsed,
+        ants_per_tick,
+        window.ant_updates as f32 / elapsed
+    );
+    *window = ThroughputWindow {
+        started: Instant::now(),
+        start_tick: clock.tick(),
+        ant_updates: 0,
+    };
+}
+
+// Stress tests started from the console, or with --stress at launch when spawn is set
+pub struct StressPlugin {
+    pub spawn: Option<u32>,
+}
+
+impl Plugin for StressPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(count) = self.spawn {
+            // Run at the end of the first frame, once the bases exist
+            app.add_systems(Startup, move |mut requests: EventWriter<CommandRequest>| {
+                requests.send(CommandRequest(SimCommand::Stress { count }));
+            });
+        }
+        app.add_systems(
+            Update,
+            (
+                enter_stress_mode.run_if(resource_added::<StressMode>()),
+                report_throughput.run_if(resource_exists::<StressMode>()),
+            ),
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use ant_sim_core::ant::AntState;
+use ant_sim_core::config::{Config, ThemeName};
+use ant_sim_core::marker::MarkerType;
+use bevy::prelude::*;
+
+// Colors telling colonies apart in the dashboard, repeated past the last one
+const COLONY_COLORS: [Color; 6] = [
+    Color::rgb(0.9, 0.3, 0.2),
+    Color::rgb(0.2, 0.5, 0.95),
+    Color::rgb(0.95, 0.75, 0.1),
+    Color::rgb(0.3, 0.75, 0.3),
+    Color::rgb(0.7, 0.35, 0.85),
+    Color::rgb(0.1, 0.75, 0.75),
+];
+
+// Colors of the GUI, the map and everything drawn on it
+#[derive(Resource, Clone)]
+pub struct Theme {
+    pub name: ThemeName,
+    pub panel: Color,
+    pub button: Color,
+    pub text: Color,
+    pub map: Color,
+    // Window area around the map
+    pub out_of_bounds: Color,
+    pub grid: Color,
+    pub ant_searching: Color,
+    pub ant_with_food: Color,
+    pub ant_carrying_corpse: Color,
+    // Color ants fade to when they stay in one state for long
+    pub stale_ant: Color,
+    // State bar colors of states without an ant color of their own
+    pub resting_bar: Color,
+    pub corpse_carrier_bar: Color,
+    pub food: Color,
+    pub heavy_food: Color,
+    pub base: Color,
+    pub corpse: Color,
+    pub predator: Color,
+    pub obstacle: Color,
+    // Nest layer: earth around the nest and the tunnels and chambers dug in it
+    pub soil: Color,
+    pub tunnel: Color,
+    pub base_marker: Color,
+    pub food_marker: Color,
+    pub danger_marker: Color,
+}
+
+impl Theme {
+    pub fn named(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Light => Self {
+                name,
+                panel: Color::rgba(0.0, 0.0, 0.0, 0.7),
+                button: Color::rgba(0.3, 0.3, 0.3, 0.8),
+                text: Color::WHITE,
+                map: Color::rgb(0.9, 0.9, 0.9),
+                out_of_bounds: Color::rgb(0.3, 0.3, 0.3),
+                grid: Color::rgba(0.7, 0.7, 0.7, 0.3),
+                ant_searching: Color::rgb(0.8, 0.2, 0.2),
+                ant_with_food: Color::rgb(0.2, 0.8, 0.2),
+                ant_carrying_corpse: Color::rgb(0.3, 0.3, 0.3),
+                stale_ant: Color::rgb(0.05, 0.05, 0.05),
+                resting_bar: Color::rgb(0.3, 0.5, 0.9),
+                corpse_carrier_bar: Color::rgb(0.5, 0.5, 0.5),
+                food: Color::rgb(0.9, 0.7, 0.1),
+                heavy_food: Color::rgb(0.75, 0.45, 0.1),
+                base: Color::rgb(0.3, 0.3, 0.8),
+                corpse: Color::rgb(0.25, 0.2, 0.15),
+                predator: Color::rgb(0.55, 0.1, 0.6),
+                obstacle: Color::rgb(0.45, 0.42, 0.38),
+                soil: Color::rgb(0.45, 0.33, 0.22),
+                tunnel: Color::rgb(0.68, 0.55, 0.4),
+                base_marker: Color::rgb(0.2, 0.6, 1.0),
+                food_marker: Color::rgb(0.2, 0.8, 0.2),
+                danger_marker: Color::rgb(0.7, 0.1, 0.5),
+            },
+            ThemeName::Dark => Self {
+                name,
+                panel: Color::rgba(0.05, 0.05, 0.08, 0.85),
+                button: Color::rgba(0.25, 0.25, 0.3, 0.9),
+                text: Color::rgb(0.92, 0.92, 0.92),
+                map: Color::rgb(0.12, 0.12, 0.14),
+                out_of_bounds: Color::rgb(0.04, 0.04, 0.05),
+                grid: Color::rgba(0.5, 0.5, 0.5, 0.15),
+                ant_searching: Color::rgb(1.0, 0.35, 0.35),
+                ant_with_food: Color::rgb(0.35, 1.0, 0.45),
+                ant_carrying_corpse: Color::rgb(0.7, 0.7, 0.7),
+                stale_ant: Color::rgb(0.95, 0.95, 0.95),
+                resting_bar: Color::rgb(0.4, 0.6, 1.0),
+                corpse_carrier_bar: Color::rgb(0.6, 0.6, 0.6),
+                food: Color::rgb(1.0, 0.8, 0.2),
+                heavy_food: Color::rgb(0.95, 0.55, 0.15),
+                base: Color::rgb(0.4, 0.45, 1.0),
+                corpse: Color::rgb(0.55, 0.45, 0.35),
+                predator: Color::rgb(0.85, 0.35, 1.0),
+                obstacle: Color::rgb(0.32, 0.3, 0.28),
+                soil: Color::rgb(0.14, 0.11, 0.08),
+                tunnel: Color::rgb(0.3, 0.23, 0.17),
+                base_marker: Color::rgb(0.3, 0.7, 1.0),
+                food_marker: Color::rgb(0.3, 0.95, 0.4),
+                danger_marker: Color::rgb(0.9, 0.3, 0.8),
+            },
+            // Opaque panels and saturated colors on white, for projectors
+            ThemeName::HighContrast => Self {
+                name,
+                panel: Color::BLACK,
+                button: Color::rgb(0.2, 0.2, 0.2),
+                text: Color::WHITE,
+                map: Color::WHITE,
+                out_of_bounds: Color::BLACK,
+                grid: Color::rgba(0.0, 0.0, 0.0, 0.25),
+                ant_searching: Color::rgb(0.85, 0.0, 0.0),
+                ant_with_food: Color::rgb(0.0, 0.55, 0.0),
+                ant_carrying_corpse: Color::BLACK,
+                stale_ant: Color::rgb(0.45, 0.0, 0.55),
+                resting_bar: Color::rgb(0.0, 0.4, 1.0),
+                corpse_carrier_bar: Color::WHITE,
+                food: Color::rgb(1.0, 0.6, 0.0),
+                heavy_food: Color::rgb(0.6, 0.3, 0.0),
+                base: Color::rgb(0.0, 0.0, 0.8),
+                corpse: Color::rgb(0.35, 0.2, 0.05),
+                predator: Color::rgb(0.5, 0.0, 0.9),
+                obstacle: Color::BLACK,
+                soil: Color::rgb(0.3, 0.2, 0.1),
+                tunnel: Color::WHITE,
+                base_marker: Color::rgb(0.0, 0.35, 1.0),
+                food_marker: Color::rgb(0.0, 0.75, 0.0),
+                danger_marker: Color::rgb(0.85, 0.0, 0.85),
+            },
+        }
+    }
+
+    // Full-intensity color of each marker type, also used by the GUI legend
+    pub fn marker_color(&self, marker_type: MarkerType) -> Color {
+        match marker_type {
+            MarkerType::Base => self.base_marker,
+            MarkerType::Food => self.food_marker,
+            MarkerType::Danger => self.danger_marker,
+        }
+    }
+
+    pub fn colony_color(&self, colony: u32) -> Color {
+        COLONY_COLORS[colony as usize % COLONY_COLORS.len()]
+    }
+
+    pub fn state_bar_color(&self, state: AntState) -> Color {
+        match state {
+            AntState::Searching => self.ant_searching,
+            AntState::Returning => self.ant_with_food,
+            AntState::Resting => self.resting_bar,
+            AntState::CarryingCorpse => self.corpse_carrier_bar,
+        }
+    }
+}
+
+impl FromWorld for Theme {
+    fn from_world(world: &mut World) -> Self {
+        Theme::named(world.resource::<Config>().theme)
+    }
+}
+
+// UI node whose background follows the theme
+#[derive(Component, Clone, Copy)]
+pub enum ThemedNode {
+    Panel,
+    Button,
+    MarkerSwatch(MarkerType),
+    StateBarSegment(AntState),
+    Colony(u32),
+}
+
+// Plain colored sprite or mesh whose color follows the theme. Ants are recolored by color_ants.
+#[derive(Component, Clone, Copy)]
+pub enum ThemedSprite {
+    Map,
+    Grid,
+    Food,
+    HeavyFood,
+    Base,
+    Corpse,
+    Predator,
+    Obstacle,
+    Soil,
+    Tunnel,
+}
+
+impl ThemedSprite {
+    pub fn color(self, theme: &Theme) -> Color {
+        match self {
+            ThemedSprite::Map => theme.map,
+            ThemedSprite::Grid => theme.grid,
+            ThemedSprite::Food => theme.food,
+            ThemedSprite::HeavyFood => theme.heavy_food,
+            ThemedSprite::Base => theme.base,
+            ThemedSprite::Corpse => theme.corpse,
+            ThemedSprite::Predator => theme.predator,
+            ThemedSprite::Obstacle => theme.obstacle,
+            ThemedSprite::Soil => theme.soil,
+            ThemedSprite::Tunnel => theme.tunnel,
+        }
+    }
+}
+
+// Recolor the GUI after the theme changed
+pub fn apply_ui_theme(
+    theme: Res<Theme>,
+    mut nodes: Query<(&mut BackgroundColor, &ThemedNode)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (mut background, node) in nodes.iter_mut() {
+        background.0 = match *node {
+            ThemedNode::Panel => theme.panel,
+            ThemedNode::Button => theme.button,
+            ThemedNode::MarkerSwatch(marker_type) => theme.marker_color(marker_type),
+            ThemedNode::StateBarSegment(state) => theme.state_bar_color(state),
+            ThemedNode::Colony(colony) => theme.colony_color(colony),
+        };
+    }
+    for mut text in texts.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color = theme.text;
+        }
+    }
+}
+
+// Recolor the map and the entities on it after the theme changed
+pub fn apply_map_theme(
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    mut sprites: Query<(&mut Sprite, &ThemedSprite)>,
+    meshes: Query<(&Handle<ColorMaterial>, &ThemedSprite)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    clear_color.0 = theme.out_of_bounds;
+    for (mut sprite, themed) in sprites.iter_mut() {
+        sprite.color = themed.color(&theme);
+    }
+    for (handle, themed) in meshes.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            material.color = themed.color(&theme);
+        }
+    }
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Theme>().add_systems(
+            Update,
+            (apply_ui_theme, apply_map_theme).run_if(resource_changed::<Theme>()),
+        );
+    }
+}
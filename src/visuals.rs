@@ -0,0 +1,725 @@
+use crate::gui::GuiSettings;
+use crate::input::{Action, InputMap};
+use crate::saved_settings::SavedCamera;
+use crate::theme::{Theme, ThemedSprite};
+use ant_sim::commands::StressMode;
+use ant_sim_core::ant::{Ant, AntState, Caste};
+use ant_sim_core::base::Base;
+use ant_sim_core::config::Config;
+use ant_sim_core::corpse::Corpse;
+use ant_sim_core::food::{FoodQuantity, FoodSource};
+use ant_sim_core::grid::{grid_to_world, GridType, GRID_CELL_SIZE, HEX_ROW_HEIGHT, HEX_SIZE};
+use ant_sim_core::marker::{update_marker_lifetimes, Marker, INITIAL_INTENSITY};
+use ant_sim_core::obstacle::Obstacles;
+use ant_sim_core::predator::Predator;
+use ant_sim_core::transport::HeavyFood;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::sprite::MaterialMesh2dBundle;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// Ant body is longer than it is wide so its heading is visible once rotated
+pub const ANT_SIZE: Vec2 = Vec2::new(8.0, 4.0);
+// Sprite size of a single-cell food source
+pub const FOOD_SIZE: f32 = 15.0;
+const CORPSE_SIZE: f32 = 5.0;
+// Longer than wide, so their heading shows
+const PREDATOR_SIZE: Vec2 = Vec2::new(14.0, 8.0);
+const MARKER_LAYER_OPACITY: f32 = 0.6;
+// Time in state (seconds) after which an ant is drawn fully faded
+const STATE_FADE_SECS: f32 = 60.0;
+
+// How much of the map's decoration is drawn, lowered by the frame budget when frames run long
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VisualDetail {
+    #[default]
+    Full,
+    // Marker layer redrawn every few frames
+    SparseMarkers,
+    // Grid lines hidden as well
+    NoGrid,
+    // Marker layer rarely redrawn and ants no longer faded by time in state
+    Minimal,
+}
+
+impl VisualDetail {
+    pub fn lower(self) -> Option<Self> {
+        match self {
+            VisualDetail::Full => Some(VisualDetail::SparseMarkers),
+            VisualDetail::SparseMarkers => Some(VisualDetail::NoGrid),
+            VisualDetail::NoGrid => Some(VisualDetail::Minimal),
+            VisualDetail::Minimal => None,
+        }
+    }
+
+    pub fn higher(self) -> Option<Self> {
+        match self {
+            VisualDetail::Full => None,
+            VisualDetail::SparseMarkers => Some(VisualDetail::Full),
+            VisualDetail::NoGrid => Some(VisualDetail::SparseMarkers),
+            VisualDetail::Minimal => Some(VisualDetail::NoGrid),
+        }
+    }
+
+    // Frames between redraws of the marker layer
+    pub fn marker_layer_interval(self) -> u32 {
+        match self {
+            VisualDetail::Full => 1,
+            VisualDetail::SparseMarkers | VisualDetail::NoGrid => 4,
+            VisualDetail::Minimal => 15,
+        }
+    }
+
+    pub fn shows_grid(self) -> bool {
+        self < VisualDetail::NoGrid
+    }
+
+    pub fn fades_ants(self) -> bool {
+        self < VisualDetail::Minimal
+    }
+}
+
+// Single map-sized sprite that all markers are drawn into, one or two texels per grid cell
+#[derive(Component)]
+pub struct MarkerLayer {
+    image: Handle<Image>,
+}
+
+pub fn setup_background(mut commands: Commands, config: Res<Config>, theme: Res<Theme>) {
+    // Map size in config is grid cells, convert to pixels
+    let map_width_pixels = config.map_size.0 as f32 * GRID_CELL_SIZE;
+    let map_height_pixels = config.map_size.1 as f32 * GRID_CELL_SIZE;
+
+    // Spawn map background (lighter grey area representing the simulation playground)
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.map,
+                custom_size: Some(Vec2::new(map_width_pixels, map_height_pixels)),
+                ..default()
+            },
+            transform: Transform::from_xyz(map_width_pixels / 2.0, map_height_pixels / 2.0, -1.0), // Behind all entities
+            ..default()
+        },
+        ThemedSprite::Map,
+    ));
+}
+
+fn sprite_bundle(color: Color, size: Vec2) -> (Sprite, Handle<Image>, VisibilityBundle) {
+    (
+        Sprite {
+            color,
+            custom_size: Some(size),
+            ..default()
+        },
+        Handle::default(),
+        VisibilityBundle::default(),
+    )
+}
+
+// With fade_by_state_time, ants darken the longer they stay in their state, so stuck
+// searchers and lost returners stand out. Resting ants wait at the base by design.
+pub fn ant_color(ant: &Ant, fade_by_state_time: bool, theme: &Theme) -> Color {
+    let color = match ant.state {
+        AntState::CarryingCorpse => theme.ant_carrying_corpse,
+        _ if ant.has_food => theme.ant_with_food,
+        _ => theme.ant_searching,
+    };
+    if !fade_by_state_time || ant.state == AntState::Resting {
+        return color;
+    }
+    let t = (ant.state_timer / STATE_FADE_SECS).clamp(0.0, 1.0);
+    let from = Vec4::from(color.as_rgba_f32());
+    let to = Vec4::from(theme.stale_ant.as_rgba_f32());
+    Color::from(from.lerp(to, t))
+}
+
+pub fn ant_size(caste: Caste) -> Vec2 {
+    match caste {
+        Caste::Worker => ANT_SIZE,
+        Caste::Soldier => ANT_SIZE * 1.5,
+    }
+}
+
+pub fn food_size(food_source: &FoodSource) -> Vec2 {
+    // Multi-cell sources are drawn over every cell they cover
+    let size = if food_source.radius > 0.0 {
+        2.0 * food_source.radius + GRID_CELL_SIZE
+    } else {
+        FOOD_SIZE
+    };
+    Vec2::splat(size)
+}
+
+pub fn heavy_food_size(item: &HeavyFood) -> Vec2 {
+    Vec2::splat(2.0 * item.radius)
+}
+
+pub fn base_size() -> Vec2 {
+    Vec2::splat(2.0 * GRID_CELL_SIZE) // 64x64 pixels
+}
+
+pub fn corpse_size() -> Vec2 {
+    Vec2::splat(CORPSE_SIZE)
+}
+
+pub fn predator_size() -> Vec2 {
+    PREDATOR_SIZE
+}
+
+// Give entities spawned by the simulation a colored rectangle to be drawn with
+pub fn add_sprites(
+    mut commands: Commands,
+    ants: Query<(Entity, &Ant), Added<Ant>>,
+    food: Query<(Entity, &FoodSource), Added<FoodSource>>,
+    heavy_food: Query<(Entity, &HeavyFood), Added<HeavyFood>>,
+    bases: Query<Entity, Added<Base>>,
+    corpses: Query<Entity, Added<Corpse>>,
+    theme: Res<Theme>,
+) {
+    // Unfaded, color_ants takes over from the next frame
+    for (entity, ant) in ants.iter() {
+        commands.entity(entity).insert(sprite_bundle(
+            ant_color(ant, false, &theme),
+            ant_size(ant.caste),
+        ));
+    }
+
+    for (entity, food_source) in food.iter() {
+        commands.entity(entity).insert((
+            sprite_bundle(theme.food, food_size(food_source)),
+            ThemedSprite::Food,
+        ));
+    }
+
+    for (entity, item) in heavy_food.iter() {
+        commands.entity(entity).insert((
+            sprite_bundle(theme.heavy_food, heavy_food_size(item)),
+            ThemedSprite::HeavyFood,
+        ));
+    }
+
+    for entity in bases.iter() {
+        commands
+            .entity(entity)
+            .insert((sprite_bundle(theme.base, base_size()), ThemedSprite::Base));
+    }
+
+    for entity in corpses.iter() {
+        commands.entity(entity).insert((
+            sprite_bundle(theme.corpse, corpse_size()),
+            ThemedSprite::Corpse,
+        ));
+    }
+}
+
+pub fn add_predator_sprites(
+    mut commands: Commands,
+    predators: Query<Entity, Added<Predator>>,
+    theme: Res<Theme>,
+) {
+    for entity in predators.iter() {
+        commands.entity(entity).insert((
+            sprite_bundle(theme.predator, predator_size()),
+            ThemedSprite::Predator,
+        ));
+    }
+}
+
+// Fixed color overriding the state colors of an ant
+#[derive(Component)]
+pub struct Tint(pub Color);
+
+// Square drawn over one blocked cell
+#[derive(Component)]
+pub struct ObstacleSprite;
+
+// One square per blocked cell, redrawn whenever the obstacles change
+pub fn draw_obstacles(
+    mut commands: Commands,
+    obstacles: Res<Obstacles>,
+    sprites: Query<Entity, With<ObstacleSprite>>,
+    theme: Res<Theme>,
+) {
+    for entity in sprites.iter() {
+        commands.entity(entity).despawn();
+    }
+    for cell in obstacles.blocked_cells() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.obstacle,
+                    custom_size: Some(Vec2::splat(GRID_CELL_SIZE)),
+                    ..default()
+                },
+                // Above the marker layer, below ants
+                transform: Transform::from_translation(grid_to_world(cell).extend(-0.05)),
+                ..default()
+            },
+            ObstacleSprite,
+            ThemedSprite::Obstacle,
+        ));
+    }
+}
+
+// Ants whose color may have changed
+type Recolored = Or<(Changed<Ant>, Changed<Tint>)>;
+
+// Color ants by what they are doing
+pub fn color_ants(
+    mut ants: Query<(&Ant, Option<&Tint>, &mut Sprite), Recolored>,
+    gui_settings: Res<GuiSettings>,
+    detail: Res<VisualDetail>,
+    theme: Res<Theme>,
+) {
+    let fade = gui_settings.fade_by_state_time && detail.fades_ants();
+    for (ant, tint, mut sprite) in ants.iter_mut() {
+        sprite.color = tint.map_or_else(|| ant_color(ant, fade, &theme), |tint| tint.0);
+    }
+}
+
+// Food sources whose quantity changed
+type RefilledOrTaken = (With<FoodSource>, Changed<FoodQuantity>);
+
+// Depleted food sources waiting to respawn are hidden
+pub fn hide_depleted_food(mut food: Query<(&FoodQuantity, &mut Visibility), RefilledOrTaken>) {
+    for (food_quantity, mut visibility) in food.iter_mut() {
+        *visibility = if food_quantity.quantity == 0 {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
+
+// Marker layer texels of a cell, along its row. Hex cells take two texels each so that
+// odd rows can sit half a cell to the right.
+fn cell_texels(grid_type: GridType, cell: (i32, i32)) -> Range<i32> {
+    match grid_type {
+        GridType::Square => cell.0..cell.0 + 1,
+        GridType::Hex => {
+            let x = 2 * cell.0 + (cell.1 & 1);
+            x..x + 2
+        }
+    }
+}
+
+// Marker layer texture for a map, and the world rectangle it is stretched over
+pub fn marker_layer_image(grid_type: GridType, map_size: (u32, u32)) -> (Image, Rect) {
+    let (columns, rows) = grid_type.cell_counts(map_size);
+    let (width, rect) = match grid_type {
+        GridType::Square => (
+            columns,
+            Rect::new(
+                0.0,
+                0.0,
+                columns as f32 * GRID_CELL_SIZE,
+                rows as f32 * GRID_CELL_SIZE,
+            ),
+        ),
+        // Cell (0, 0) is centered on the map origin
+        GridType::Hex => {
+            let width = 2 * columns + 1;
+            let min = Vec2::new(-GRID_CELL_SIZE / 2.0, -HEX_ROW_HEIGHT / 2.0);
+            let size = Vec2::new(
+                width as f32 * GRID_CELL_SIZE / 2.0,
+                rows as f32 * HEX_ROW_HEIGHT,
+            );
+            (width, Rect::from_corners(min, min + size))
+        }
+    };
+
+    let size = Extent3d {
+        width,
+        height: rows,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    // Keep cells crisp instead of blurring between neighbours
+    image.sampler = ImageSampler::nearest();
+    (image, rect)
+}
+
+pub fn setup_marker_layer(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    config: Res<Config>,
+) {
+    let (image, rect) = marker_layer_image(config.grid_type, config.map_size);
+    let image = images.add(image);
+
+    commands.spawn((
+        MarkerLayer {
+            image: image.clone(),
+        },
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(rect.size()),
+                ..default()
+            },
+            texture: image,
+            transform: Transform::from_translation(rect.center().extend(-0.1)), // Behind ants, above grid lines
+            ..default()
+        },
+    ));
+}
+
+// Whether the marker layer is redrawn this frame at the current level of detail
+pub fn marker_layer_due(detail: Res<VisualDetail>, mut frame: Local<u32>) -> bool {
+    *frame = frame.wrapping_add(1);
+    frame.is_multiple_of(detail.marker_layer_interval())
+}
+
+// Hide or show the grid lines as the level of detail changes; stress mode keeps them hidden
+pub fn apply_grid_detail(
+    detail: Res<VisualDetail>,
+    stress: Option<Res<StressMode>>,
+    mut grid_lines: Query<&mut Visibility, With<GridLine>>,
+) {
+    if stress.is_some() {
+        return;
+    }
+    for mut visibility in grid_lines.iter_mut() {
+        *visibility = if detail.shows_grid() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// Redraw the marker layer texture. Rendering cost is a single sprite
+// regardless of how many markers exist.
+pub fn render_marker_layer(
+    layer_query: Query<&MarkerLayer>,
+    markers: Query<&Marker>,
+    mut images: ResMut<Assets<Image>>,
+    gui_settings: Res<GuiSettings>,
+    theme: Res<Theme>,
+    config: Res<Config>,
+) {
+    let Ok(layer) = layer_query.get_single() else {
+        return;
+    };
+    let Some(image) = images.get_mut(&layer.image) else {
+        return;
+    };
+    draw_markers(
+        image,
+        markers.iter(),
+        config.grid_type,
+        &gui_settings,
+        &theme,
+    );
+}
+
+// Paint markers into a marker layer texture, one texel per grid cell
+pub fn draw_markers<'a>(
+    image: &mut Image,
+    markers: impl Iterator<Item = &'a Marker>,
+    grid_type: GridType,
+    gui_settings: &GuiSettings,
+    theme: &Theme,
+) {
+    let width = image.texture_descriptor.size.width as i32;
+    let height = image.texture_descriptor.size.height as i32;
+    image.data.fill(0);
+
+    for marker in markers {
+        if gui_settings.is_marker_hidden(marker.marker_type) {
+            continue;
+        }
+        let y = marker.grid_cell.1;
+        if y < 0 || y >= height {
+            continue;
+        }
+
+        let opacity = (marker.intensity / INITIAL_INTENSITY).clamp(0.0, 1.0) * MARKER_LAYER_OPACITY;
+        let color = theme.marker_color(marker.marker_type).with_a(opacity);
+        let rgba = color.as_rgba_u8();
+
+        for x in cell_texels(grid_type, marker.grid_cell) {
+            if x < 0 || x >= width {
+                continue;
+            }
+            // Image rows go top to bottom while grid rows go bottom to top
+            let index = (((height - 1 - y) * width + x) * 4) as usize;
+            let texel = &mut image.data[index..index + 4];
+            if texel[3] == 0 {
+                texel.copy_from_slice(&rgba);
+            } else {
+                // Cell holds both marker types, mix them evenly
+                for channel in 0..3 {
+                    texel[channel] = ((texel[channel] as u16 + rgba[channel] as u16) / 2) as u8;
+                }
+                texel[3] = texel[3].max(rgba[3]);
+            }
+        }
+    }
+}
+
+// Outline of every hex cell as a single line mesh. Each cell draws its three left-hand
+// edges, the other three belong to its neighbours.
+fn hex_grid_mesh(map_size: (u32, u32)) -> Mesh {
+    let corner = |center: Vec2, index: i32| {
+        let angle = (60.0 * index as f32 + 30.0).to_radians();
+        (center + Vec2::new(angle.cos(), angle.sin()) * HEX_SIZE)
+            .extend(0.0)
+            .to_array()
+    };
+
+    let (columns, rows) = GridType::Hex.cell_counts(map_size);
+    let mut positions = Vec::new();
+    for row in 0..rows as i32 {
+        for column in 0..columns as i32 {
+            let center = GridType::Hex.grid_to_world((column, row));
+            // Corners 1 to 4 run from the top corner round the left side to the bottom one
+            for index in 1..4 {
+                positions.push(corner(center, index));
+                positions.push(corner(center, index + 1));
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh
+}
+
+pub fn render_grid(
+    mut commands: Commands,
+    config: Res<Config>,
+    existing_grid: Query<Entity, With<GridLine>>,
+    theme: Res<Theme>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // Clear existing grid lines
+    for entity in existing_grid.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    // Thousands of hexes would be as many sprites per edge direction, so they are one mesh
+    if config.grid_type == GridType::Hex {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: meshes.add(hex_grid_mesh(config.map_size)).into(),
+                material: materials.add(ColorMaterial::from(theme.grid)),
+                transform: Transform::from_xyz(0.0, 0.0, -0.5),
+                ..default()
+            },
+            GridLine,
+            ThemedSprite::Grid,
+        ));
+        return;
+    }
+
+    // Map size in config is grid cells, convert to pixels
+    let map_width_pixels = config.map_size.0 as f32 * GRID_CELL_SIZE;
+    let map_height_pixels = config.map_size.1 as f32 * GRID_CELL_SIZE;
+    let grid_color = theme.grid;
+    const LINE_WIDTH: f32 = 1.0;
+
+    // Draw vertical lines
+    let num_vertical = config.map_size.0 as i32;
+    for i in 0..=num_vertical {
+        let x = i as f32 * GRID_CELL_SIZE;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: grid_color,
+                    custom_size: Some(Vec2::new(LINE_WIDTH, map_height_pixels)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(x, map_height_pixels / 2.0, -0.5)),
+                ..default()
+            },
+            GridLine,
+            ThemedSprite::Grid,
+        ));
+    }
+
+    // Draw horizontal lines
+    let num_horizontal = config.map_size.1 as i32;
+    for i in 0..=num_horizontal {
+        let y = i as f32 * GRID_CELL_SIZE;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: grid_color,
+                    custom_size: Some(Vec2::new(map_width_pixels, LINE_WIDTH)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(map_width_pixels / 2.0, y, -0.5)),
+                ..default()
+            },
+            GridLine,
+            ThemedSprite::Grid,
+        ));
+    }
+}
+
+#[derive(Component)]
+pub struct GridLine;
+
+// Camera the user steers; other cameras (split screen, UI) follow or ignore input
+#[derive(Component)]
+pub struct MainCamera;
+
+const MOVEMENT_SPEED: f32 = 5.0;
+
+pub fn camera_movement(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut camera_query: Query<&mut Transform, (With<MainCamera>, Without<GridLine>)>,
+    // Real time so the camera still moves while the simulation is paused
+    time: Res<Time<Real>>,
+) {
+    const CAMERA_SPEED: f32 = 250.0; // pixels per second
+
+    if let Ok(mut transform) = camera_query.get_single_mut() {
+        let mut movement = Vec2::ZERO;
+
+        if input_map.pressed(Action::CameraUp, &keyboard_input) {
+            movement.y += MOVEMENT_SPEED;
+        }
+        if input_map.pressed(Action::CameraLeft, &keyboard_input) {
+            movement.x -= MOVEMENT_SPEED;
+        }
+        if input_map.pressed(Action::CameraDown, &keyboard_input) {
+            movement.y -= MOVEMENT_SPEED;
+        }
+        if input_map.pressed(Action::CameraRight, &keyboard_input) {
+            movement.x += MOVEMENT_SPEED;
+        }
+
+        // Normalize diagonal movement
+        if movement.length() > 0.0 {
+            movement = movement.normalize();
+        }
+
+        // Apply movement
+        let delta = movement * CAMERA_SPEED * time.delta_seconds();
+        transform.translation.x += delta.x;
+        transform.translation.y += delta.y;
+
+        // Optional: Clamp camera to map bounds (or allow free movement)
+        // For now, allow free movement
+    }
+}
+
+const MIN_CAMERA_SCALE: f32 = 0.5;
+// Views fitted to a large map may be zoomed out further
+const MAX_CAMERA_SCALE: f32 = 3.0;
+
+pub fn camera_zoom(
+    mut mouse_wheel_events: EventReader<bevy::input::mouse::MouseWheel>,
+    mut camera_query: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    const ZOOM_SPEED: f32 = 0.1;
+
+    let mut total_scroll = 0.0;
+    for event in mouse_wheel_events.read() {
+        // MouseWheel can be in pixels or lines, handle both
+        total_scroll += match event.unit {
+            bevy::input::mouse::MouseScrollUnit::Line => event.y,
+            bevy::input::mouse::MouseScrollUnit::Pixel => event.y / 10.0, // Convert pixels to approximate lines
+        };
+    }
+
+    if total_scroll != 0.0 {
+        if let Ok(mut projection) = camera_query.get_single_mut() {
+            // Adjust the scale based on scroll
+            // Negative scroll (scroll down) = zoom out (increase scale)
+            // Positive scroll (scroll up) = zoom in (decrease scale)
+            let scale_change = -total_scroll * ZOOM_SPEED;
+            let current_scale = projection.scale;
+            let max_scale = MAX_CAMERA_SCALE.max(current_scale);
+            let new_scale = (current_scale + scale_change).clamp(MIN_CAMERA_SCALE, max_scale);
+            projection.scale = new_scale;
+        }
+    }
+}
+
+// Camera views saved with the save_bookmark key held and a number key, and gone back to with
+// the number key alone. Kept between runs with the other GUI settings.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct CameraBookmarks([Option<SavedCamera>; 9]);
+
+pub fn camera_bookmarks(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let Some(index) = input_map.just_pressed_digit(&keyboard_input) else {
+        return;
+    };
+    let Ok((mut transform, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+    if input_map.pressed(Action::SaveBookmark, &keyboard_input) {
+        bookmarks.0[index] = Some(SavedCamera::of(&transform, &projection));
+    } else if let Some(bookmark) = bookmarks.0[index] {
+        bookmark.apply(&mut transform, &mut projection);
+    }
+}
+
+// Center the camera on the map, zoomed out just enough for all of it to fit in the view
+pub fn fit_camera_to_map(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+    config: Res<Config>,
+) {
+    const MARGIN: f32 = 1.05;
+
+    if !input_map.just_pressed(Action::FitMap, &keyboard_input) {
+        return;
+    }
+    let Ok((mut transform, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+    // World size of the view at scale 1, which the scaling mode sets rather than the window
+    let view = projection.area.size() / projection.scale;
+    let map = Vec2::new(config.map_size.0 as f32, config.map_size.1 as f32) * GRID_CELL_SIZE;
+    transform.translation.x = map.x / 2.0;
+    transform.translation.y = map.y / 2.0;
+    projection.scale = ((map / view).max_element() * MARGIN).max(MIN_CAMERA_SCALE);
+}
+
+pub struct VisualsPlugin;
+
+impl Plugin for VisualsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VisualDetail>()
+            .init_resource::<CameraBookmarks>()
+            .add_systems(Startup, (setup_background, render_grid, setup_marker_layer))
+            .add_systems(
+                Update,
+                (
+                    camera_movement,
+                    camera_zoom,
+                    camera_bookmarks,
+                    fit_camera_to_map,
+                    add_sprites,
+                    add_predator_sprites,
+                    color_ants,
+                    hide_depleted_food,
+                    render_marker_layer
+                        .after(update_marker_lifetimes)
+                        .run_if(not(resource_exists::<StressMode>()))
+                        .run_if(marker_layer_due),
+                    apply_grid_detail.run_if(resource_changed::<VisualDetail>()),
+                    draw_obstacles.run_if(resource_exists_and_changed::<Obstacles>()),
+                ),
+            );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::gui::{update_frame_timing, FrameTiming};
+use crate::input::{Action, InputMap};
+use crate::localization::Locale;
+use crate::logging::LoggingPlugin;
+use crate::mirror::{MirrorCounts, WorldMirror};
+use crate::theme::{Theme, ThemedNode, ThemedSprite};
+use crate::visuals::{marker_layer_image, MainCamera};
+use ant_sim_core::config::Config;
+use ant_sim_core::grid::{GridType, GRID_CELL_SIZE};
+use ant_sim_core::simulation::SimulationPlugin;
+use bevy::app::{AppExit, AppLabel, SubApp};
+use bevy::prelude::*;
+
+// Empty cells left between stacked maps
+const MAP_GAP_CELLS: f32 = 4.0;
+
+// Extra simulation with the given index, run in its own world above the main one
+#[derive(AppLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ExtraWorld(usize);
+
+// An extra world as drawn in the main one
+pub struct WorldView {
+    label: String,
+    offset: Vec2,
+    map_size: (u32, u32),
+    grid_type: GridType,
+    mirror: WorldMirror,
+    counts: MirrorCounts,
+    marker_layer: Option<Handle<Image>>,
+}
+
+impl WorldView {
+    fn center(&self) -> Vec2 {
+        self.offset + map_pixels(self.map_size) / 2.0
+    }
+}
+
+// Extra worlds, and which world the camera was last sent to: 0 for the main one, i + 1 for
+// the extra world i
+#[derive(Resource, Default)]
+pub struct WorldViews {
+    views: Vec<WorldView>,
+    selected: usize,
+}
+
+#[derive(Component)]
+pub struct WorldSelectorText;
+
+fn map_pixels(map_size: (u32, u32)) -> Vec2 {
+    Vec2::new(map_size.0 as f32, map_size.1 as f32) * GRID_CELL_SIZE
+}
+
+// Copy the extra world's state into the main world and pass on the main app's exit so its
+// log is closed. Runs every frame before the extra world is updated.
+fn mirror_extra_world(index: usize, main_world: &mut World, world_app: &mut App) {
+    if !main_world.resource::<Events<AppExit>>().is_empty() {
+        world_app.world.send_event(AppExit);
+    }
+    main_world.resource_scope(|main_world, mut views: Mut<WorldViews>| {
+        let view = &mut views.views[index];
+        view.counts = view.mirror.sync(
+            main_world,
+            &mut world_app.world,
+            view.offset,
+            view.marker_layer.as_ref(),
+        );
+    });
+}
+
+// Background, marker layer and name of each extra world, and the world selector label
+fn setup_world_views(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut views: ResMut<WorldViews>,
+    theme: Res<Theme>,
+) {
+    for view in views.views.iter_mut() {
+        let map_size = map_pixels(view.map_size);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.map,
+                    custom_size: Some(map_size),
+                    ..default()
+                },
+                transform: Transform::from_translation(view.center().extend(-1.0)),
+                ..default()
+            },
+            ThemedSprite::Map,
+        ));
+
+        let (image, rect) = marker_layer_image(view.grid_type, view.map_size);
+        let image = images.add(image);
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(rect.size()),
+                ..default()
+            },
+            texture: image.clone(),
+            transform: Transform::from_translation((view.offset + rect.center()).extend(-0.1)),
+            ..default()
+        });
+        view.marker_layer = Some(image);
+
+        // Name just above the top-left corner of the map
+        commands.spawn(Text2dBundle {
+            text: Text::from_section(
+                view.label.clone(),
+                TextStyle {
+                    font_size: 24.0,
+                    color: theme.text,
+                    ..default()
+                },
+            ),
+            text_anchor: bevy::sprite::Anchor::BottomLeft,
+            transform: Transform::from_translation(
+                (view.offset + Vec2::new(0.0, map_size.y + GRID_CELL_SIZE)).extend(1.0),
+            ),
+            ..default()
+        });
+    }
+
+    // Top-center label naming the world the camera was sent to
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(10.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(6.0)),
+                            ..default()
+                        },
+                        background_color: theme.panel.into(),
+                        ..default()
+                    },
+                    ThemedNode::Panel,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 16.0,
+                                color: theme.text,
+                                ..default()
+                            },
+                        ),
+                        WorldSelectorText,
+                    ));
+                });
+        });
+}
+
+// Move the camera to the center of the next world's map, back to the main one after the last
+pub fn select_next_world(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut views: ResMut<WorldViews>,
+    mut camera: Query<&mut Transform, With<MainCamera>>,
+    config: Res<Config>,
+) {
+    if !input_map.just_pressed(Action::NextWorld, &keyboard_input) {
+        return;
+    }
+    views.selected = (views.selected + 1) % (views.views.len() + 1);
+    let center = match views.selected {
+        0 => map_pixels(config.map_size) / 2.0,
+        selected => views.views[selected - 1].center(),
+    };
+    if let Ok(mut transform) = camera.get_single_mut() {
+        transform.translation = center.extend(transform.translation.z);
+    }
+}
+
+pub fn update_world_selector(
+    views: Res<WorldViews>,
+    mut text: Query<&mut Text, With<WorldSelectorText>>,
+    locale: Res<Locale>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let count = views.views.len() + 1;
+    text.sections[0].value = match views.selected {
+        0 => format!("{} (1/{})", locale.main_world, count),
+        selected => {
+            let view = &views.views[selected - 1];
+            let ants: usize = view.counts.ants_by_state.iter().sum();
+            format!(
+                "{} ({}/{}) - {}: {}, {}: {}",
+                view.label,
+                selected + 1,
+                count,
+                locale.ants,
+                ants,
+                locale.food_left,
+                view.counts.food_left
+            )
+        }
+    };
+}
+
+// Runs more simulations, each with its own config and logger, in worlds of their own drawn
+// above the main map. NextWorld cycles the camera through them.
+pub struct WorldsPlugin {
+    // Label and config of each extra world
+    pub worlds: Vec<(String, Config)>,
+}
+
+impl Plugin for WorldsPlugin {
+    fn build(&self, app: &mut App) {
+        // Stack the extra maps above the main one, left-aligned with it
+        let mut top = map_pixels(app.world.resource::<Config>().map_size).y;
+        let mut views = WorldViews::default();
+        for (index, (label, config)) in self.worlds.iter().enumerate() {
+            let offset = Vec2::new(0.0, top + MAP_GAP_CELLS * GRID_CELL_SIZE);
+            top = offset.y + map_pixels(config.map_size).y;
+            views.views.push(WorldView {
+                label: label.clone(),
+                offset,
+                map_size: config.map_size,
+                grid_type: config.grid_type,
+                mirror: WorldMirror::default(),
+                counts: MirrorCounts::default(),
+                marker_layer: None,
+            });
+
+            let mut world_app = App::new();
+            world_app
+                .add_plugins(MinimalPlugins)
+                .insert_resource(config.clone())
+                .add_plugins(SimulationPlugin)
+                .init_resource::<FrameTiming>()
+                .add_systems(Update, update_frame_timing)
+                .add_plugins(LoggingPlugin {
+                    suffix: Some(format!("world{}", index + 1)),
+                });
+            app.insert_sub_app(
+                ExtraWorld(index),
+                SubApp::new(world_app, move |main_world, world_app| {
+                    mirror_extra_world(index, main_world, world_app)
+                }),
+            );
+        }
+
+        app.insert_resource(views)
+            .init_resource::<Locale>()
+            .add_systems(Startup, setup_world_views)
+            .add_systems(Update, (select_next_world, update_world_selector));
+    }
+}